@@ -14,7 +14,128 @@
 //!
 //! It supports secure access to memory, using the same secure bit mechanism
 //! used by the CPU TrustZone security mechanism through the Security Engine.
+//!
+//! # Arbitration
+//!
+//! Masters on the bus are arbitrated in a round robin fashion, where masters
+//! in the high priority group are preferred over the remaining ones. This can
+//! be configured per master through [`set_master_priority`]. Additionally,
+//! reads from memory may be prefetched for up to eight masters, see
+//! [`enable_prefetch`].
+//!
+//! [`set_master_priority`]: fn.set_master_priority.html
+//! [`enable_prefetch`]: fn.enable_prefetch.html
 
 mod registers;
 
+use tock_registers::{interfaces::*, registers::ReadWrite};
+
 pub use registers::*;
+
+use mem::{AHB_AHB_MEM_PREFETCH_CFG_0, AHB_ARBITRATION_PRIORITY_CTRL_0};
+
+/// The address boundary at which prefetching stops, in the encoding of the register.
+const PREFETCH_ADDR_BOUNDARY: u32 = 0xC;
+
+/// The cycles of inactivity after which prefetched data is invalidated.
+const PREFETCH_INACTIVITY_TIMEOUT: u32 = 0x1000;
+
+/// Masters on the AHB that take part in the arbitration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AhbMaster {
+    /// The CPU complex.
+    Cpu = 0,
+    /// The COP (BPMP).
+    Cop = 1,
+    /// The AHB DMA Controller.
+    AhbDma = 5,
+    /// The USB 1 controller.
+    Usb = 6,
+    /// The APB DMA Controller.
+    ApbDma = 7,
+    /// The USB 3 controller.
+    Usb3 = 17,
+    /// The USB 2 controller.
+    Usb2 = 18,
+}
+
+/// The arbitration priority group of an [`AhbMaster`].
+///
+/// [`AhbMaster`]: enum.AhbMaster.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Priority {
+    /// The master is arbitrated along with all other masters.
+    Low,
+    /// The master is preferred over masters of the low priority group.
+    High,
+}
+
+/// Assigns the given AHB master to an arbitration priority group.
+pub fn set_master_priority(master: AhbMaster, priority: Priority) {
+    let ahb = unsafe { &*mem::REGISTERS };
+
+    let mask = 1 << master as u32;
+    let mut select = ahb
+        .AHB_ARBITRATION_PRIORITY_CTRL_0
+        .read(AHB_ARBITRATION_PRIORITY_CTRL_0::AHB_PRIORITY_SELECT);
+    match priority {
+        Priority::Low => select &= !mask,
+        Priority::High => select |= mask,
+    }
+
+    ahb.AHB_ARBITRATION_PRIORITY_CTRL_0
+        .modify(AHB_ARBITRATION_PRIORITY_CTRL_0::AHB_PRIORITY_SELECT.val(select));
+}
+
+/// Enables or disables memory read prefetching for the given AHB master.
+///
+/// Prefetching is configured through one of eight slots. A slot that is already
+/// assigned to the master is reused, otherwise the first disabled slot is claimed
+/// when enabling prefetching. An error is returned when no slot is available.
+pub fn enable_prefetch(master: AhbMaster, enable: bool) -> Result<(), ()> {
+    let ahb = unsafe { &*mem::REGISTERS };
+
+    let slots: [&ReadWrite<u32, AHB_AHB_MEM_PREFETCH_CFG_0::Register>; 8] = [
+        &ahb.AHB_AHB_MEM_PREFETCH_CFG1_0,
+        &ahb.AHB_AHB_MEM_PREFETCH_CFG2_0,
+        &ahb.AHB_AHB_MEM_PREFETCH_CFG3_0,
+        &ahb.AHB_AHB_MEM_PREFETCH_CFG4_0,
+        &ahb.AHB_AHB_MEM_PREFETCH_CFG5_0,
+        &ahb.AHB_AHB_MEM_PREFETCH_CFG6_0,
+        &ahb.AHB_AHB_MEM_PREFETCH_CFG7_0,
+        &ahb.AHB_AHB_MEM_PREFETCH_CFG8_0,
+    ];
+
+    // Look for a slot that is already assigned to the master.
+    let assigned = slots.iter().find(|slot| {
+        slot.is_set(AHB_AHB_MEM_PREFETCH_CFG_0::ENABLE)
+            && slot.read(AHB_AHB_MEM_PREFETCH_CFG_0::AHB_MST_ID) == master as u32
+    });
+
+    if let Some(slot) = assigned {
+        if !enable {
+            slot.modify(AHB_AHB_MEM_PREFETCH_CFG_0::ENABLE::CLEAR);
+        }
+
+        return Ok(());
+    }
+
+    if !enable {
+        return Ok(());
+    }
+
+    // Claim the first free slot for the master.
+    let slot = slots
+        .iter()
+        .find(|slot| !slot.is_set(AHB_AHB_MEM_PREFETCH_CFG_0::ENABLE))
+        .ok_or(())?;
+    slot.write(
+        AHB_AHB_MEM_PREFETCH_CFG_0::ENABLE::SET
+            + AHB_AHB_MEM_PREFETCH_CFG_0::AHB_MST_ID.val(master as u32)
+            + AHB_AHB_MEM_PREFETCH_CFG_0::ADDR_BNDRY.val(PREFETCH_ADDR_BOUNDARY)
+            + AHB_AHB_MEM_PREFETCH_CFG_0::INACTIVITY_TIMEOUT.val(PREFETCH_INACTIVITY_TIMEOUT),
+    );
+
+    Ok(())
+}
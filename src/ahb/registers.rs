@@ -3,29 +3,60 @@
 /// See Chapter 19.4.2 in the Tegra X1 Technical Reference
 /// Manual for details.
 pub mod mem {
-    use tock_registers::{register_structs, registers::*};
+    use tock_registers::{register_bitfields, register_structs, registers::*};
 
     use crate::memory_map::SYSREG;
 
     /// A pointer to the AHB MC Slave registers that can be accessed by dereferencing it.
     pub const REGISTERS: *const Registers = SYSREG as *const Registers;
 
+    register_bitfields! {
+        u32,
+
+        /// Bitfields of the `AHB_ARBITRATION_PRIORITY_CTRL_0` register.
+        pub AHB_ARBITRATION_PRIORITY_CTRL_0 [
+            /// The weight of the high priority group in the round robin arbitration.
+            AHB_PRIORITY_WEIGHT OFFSET(29) NUMBITS(3) [],
+
+            /// One bit per AHB master, set for masters in the high priority group.
+            AHB_PRIORITY_SELECT OFFSET(0) NUMBITS(29) []
+        ],
+
+        /// Bitfields of the `AHB_AHB_MEM_PREFETCH_CFG<x>_0` registers.
+        pub AHB_AHB_MEM_PREFETCH_CFG_0 [
+            /// Enables read prefetching for the selected master.
+            ENABLE OFFSET(31) NUMBITS(1) [],
+
+            /// The ID of the AHB master to prefetch for.
+            AHB_MST_ID OFFSET(26) NUMBITS(5) [],
+
+            /// The address boundary at which prefetching stops.
+            ADDR_BNDRY OFFSET(21) NUMBITS(5) [],
+
+            /// Cycles of inactivity after which the prefetched data is invalidated.
+            INACTIVITY_TIMEOUT OFFSET(0) NUMBITS(16) []
+        ]
+    }
+
     register_structs! {
         /// Representation of the AHB Memory Controller Slave registers.
         #[allow(non_snake_case)]
         pub Registers {
-            (0x000 => _reserved0: [ReadWrite<u8>; 0xCC]),
-            (0x0CC => pub AHB_AHB_MEM_PREFETCH_CFG5_0: ReadWrite<u32>),
-            (0x0D0 => pub AHB_AHB_MEM_PREFETCH_CFG6_0: ReadWrite<u32>),
-            (0x0D4 => pub AHB_AHB_MEM_PREFETCH_CFG7_0: ReadWrite<u32>),
-            (0x0D8 => pub AHB_AHB_MEM_PREFETCH_CFG8_0: ReadWrite<u32>),
+            (0x000 => _reserved0: [ReadWrite<u8>; 0x4]),
+            (0x004 => pub AHB_ARBITRATION_DISABLE_0: ReadWrite<u32>),
+            (0x008 => pub AHB_ARBITRATION_PRIORITY_CTRL_0: ReadWrite<u32, AHB_ARBITRATION_PRIORITY_CTRL_0::Register>),
+            (0x00C => _reserved1: [ReadWrite<u8>; 0xC0]),
+            (0x0CC => pub AHB_AHB_MEM_PREFETCH_CFG5_0: ReadWrite<u32, AHB_AHB_MEM_PREFETCH_CFG_0::Register>),
+            (0x0D0 => pub AHB_AHB_MEM_PREFETCH_CFG6_0: ReadWrite<u32, AHB_AHB_MEM_PREFETCH_CFG_0::Register>),
+            (0x0D4 => pub AHB_AHB_MEM_PREFETCH_CFG7_0: ReadWrite<u32, AHB_AHB_MEM_PREFETCH_CFG_0::Register>),
+            (0x0D8 => pub AHB_AHB_MEM_PREFETCH_CFG8_0: ReadWrite<u32, AHB_AHB_MEM_PREFETCH_CFG_0::Register>),
             (0x0DC => pub AHB_AHB_MEM_PREFETCH_CFG_X_0: ReadWrite<u32>),
             (0x0E0 => pub AHB_ARBITRATION_XBAR_CTRL_0: ReadWrite<u32>),
-            (0x0E4 => pub AHB_AHB_MEM_PREFETCH_CFG3_0: ReadWrite<u32>),
-            (0x0E8 => pub AHB_AHB_MEM_PREFETCH_CFG4_0: ReadWrite<u32>),
+            (0x0E4 => pub AHB_AHB_MEM_PREFETCH_CFG3_0: ReadWrite<u32, AHB_AHB_MEM_PREFETCH_CFG_0::Register>),
+            (0x0E8 => pub AHB_AHB_MEM_PREFETCH_CFG4_0: ReadWrite<u32, AHB_AHB_MEM_PREFETCH_CFG_0::Register>),
             (0x0EC => pub AHB_AVP_PPCS_RD_COH_STATUS_0: ReadOnly<u32>),
-            (0x0F0 => pub AHB_AHB_MEM_PREFETCH_CFG1_0: ReadWrite<u32>),
-            (0x0F4 => pub AHB_AHB_MEM_PREFETCH_CFG2_0: ReadWrite<u32>),
+            (0x0F0 => pub AHB_AHB_MEM_PREFETCH_CFG1_0: ReadWrite<u32, AHB_AHB_MEM_PREFETCH_CFG_0::Register>),
+            (0x0F4 => pub AHB_AHB_MEM_PREFETCH_CFG2_0: ReadWrite<u32, AHB_AHB_MEM_PREFETCH_CFG_0::Register>),
             (0x0F8 => pub AHB_AHBSLVMEM_STATUS_0: ReadOnly<u32>),
             (0x0FC => pub AHB_ARBITRATION_AHB_MEM_WRQUE_MST_ID_0: ReadOnly<u32>),
             (0x100 => pub AHB_ARBITRATION_CPU_ABORT_ADDR_0: ReadOnly<u32>),
@@ -34,7 +65,7 @@ pub mod mem {
             (0x10C => pub AHB_ARBITRATION_COP_ABORT_INFO_0: ReadOnly<u32>),
             (0x110 => pub AHB_AHB_SPARE_REG_0: ReadWrite<u32>),
             (0x114 => pub AHB_XBAR_SPARE_REG_0: ReadWrite<u32>),
-            (0x118 => _reserved1: [ReadWrite<u8>; 0x8]),
+            (0x118 => _reserved2: [ReadWrite<u8>; 0x8]),
             (0x120 => pub AHB_AVPC_MCCIF_FIFOCTRL_0: ReadWrite<u32>),
             (0x124 => pub AHB_TIMEOUT_WCOAL_AVPC_0: ReadWrite<u32>),
             (0x128 => pub AHB_MPCORE_MCCIF_FIFOCTRL_0: ReadWrite<u32>),
@@ -8,7 +8,39 @@ pub use channel::*;
 mod channel;
 mod core;
 
-use tock_registers::interfaces::*;
+use tock_registers::{fields::FieldValue, interfaces::*};
+
+use crate::memory_map::{APB, DRAM};
+
+/// The maximum amount of 32-bit words that can be transferred in a single block (64kB).
+pub const MAX_BLOCK_WORDS: u32 = 0x4000;
+
+/// DMA errors that may occur when managing channels and preparing transfers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmaError {
+    /// The channel is already claimed, or no free channel is available.
+    ChannelBusy,
+    /// The channel was released without being claimed beforehand.
+    ChannelNotAcquired,
+    /// A transfer of zero words was requested.
+    ZeroLength,
+    /// A transfer exceeds the [`MAX_BLOCK_WORDS`] of a single block.
+    ///
+    /// [`MAX_BLOCK_WORDS`]: constant.MAX_BLOCK_WORDS.html
+    BlockTooLarge,
+    /// An AHB-to-APB copy destination lies outside of the APB address space.
+    InvalidDestination,
+    /// The AHB address range of a transfer exceeds the 32-bit address space.
+    AddressOverflow,
+}
+
+fn check_block_size(size: u32) -> Result<(), DmaError> {
+    match size {
+        0 => Err(DmaError::ZeroLength),
+        size if size > MAX_BLOCK_WORDS => Err(DmaError::BlockTooLarge),
+        _ => Ok(()),
+    }
+}
 
 /// Representation of the AMBA Peripheral Bus DMA Controller.
 ///
@@ -106,7 +138,8 @@ impl Controller {
     ///
     /// The supplied closure takes the [`Channel`] as argument and is expected to
     /// return a `Result`, which is forwarded to the direct return value of this
-    /// method. Note that [`Channel::start`] and [`Channel::finish`] must be called
+    /// method. The channel is released regardless of whether the closure succeeds
+    /// or fails. Note that [`Channel::start`] and [`Channel::finish`] must be called
     /// by the user within the closure to allow more freedom in how to configure and
     /// use the DMA interfaces of specific devices.
     ///
@@ -135,24 +168,182 @@ impl Controller {
     /// [`Channel`]: struct.Channel.html
     /// [`Channel::start`]: struct.Channel.html#method.start
     /// [`Channel::finish`]: struct.Channel.html#method.finish
-    pub fn execute_in_channel<C>(&mut self, mut channel: Option<Channel>, f: C) -> Result<(), ()>
+    pub fn execute_in_channel<C>(
+        &mut self,
+        mut channel: Option<Channel>,
+        f: C,
+    ) -> Result<(), DmaError>
     where
-        C: FnOnce(&Channel) -> Result<(), ()>,
+        C: FnOnce(&Channel) -> Result<(), DmaError>,
     {
         let selected_channel = if let Some(ch) = channel.as_mut() {
             ch
         } else {
-            self.find_free_channel().ok_or(())?
+            self.find_free_channel().ok_or(DmaError::ChannelBusy)?
         };
 
         selected_channel.acquire()?;
-        f(selected_channel)?;
+
+        // Release the channel unconditionally so it isn't leaked when the closure fails.
+        let result = f(selected_channel);
         selected_channel.release()?;
 
-        Ok(())
+        result
+    }
+}
+
+/// Supported AHB burst sizes for DMA transfers.
+///
+/// Wider bursts move more data per bus transaction and thus greatly improve the
+/// throughput of bulk transfers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BurstSize {
+    /// Bursts of 1 word.
+    Words1,
+    /// Bursts of 4 words.
+    Words4,
+    /// Bursts of 8 words.
+    Words8,
+}
+
+impl BurstSize {
+    fn get_field_value(self) -> FieldValue<u32, APBDMACHAN_CHANNEL_AHB_SEQ_0::Register> {
+        match self {
+            BurstSize::Words1 => APBDMACHAN_CHANNEL_AHB_SEQ_0::AHB_BURST::DmaBurst1Words,
+            BurstSize::Words4 => APBDMACHAN_CHANNEL_AHB_SEQ_0::AHB_BURST::DmaBurst4Words,
+            BurstSize::Words8 => APBDMACHAN_CHANNEL_AHB_SEQ_0::AHB_BURST::DmaBurst8Words,
+        }
+    }
+}
+
+/// Supported address wrap-around windows for DMA transfers.
+///
+/// When wrapping is enabled, the address on the respective bus is reset to its starting
+/// value after the given amount of words was transferred, which is useful for accessing
+/// FIFO registers of a peripheral.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressWrap {
+    /// The address is incremented linearly throughout the transfer.
+    NoWrap = 0,
+    /// The address wraps around after every word.
+    Words1 = 1,
+    /// The address wraps around after 2 words.
+    Words2 = 2,
+    /// The address wraps around after 4 words.
+    Words4 = 3,
+    /// The address wraps around after 8 words.
+    Words8 = 4,
+    /// The address wraps around after 16 words.
+    Words16 = 5,
+    /// The address wraps around after 32 words.
+    Words32 = 6,
+    /// The address wraps around after 64 words.
+    Words64 = 7,
+}
+
+impl AddressWrap {
+    fn get_ahb_field_value(self) -> FieldValue<u32, APBDMACHAN_CHANNEL_AHB_SEQ_0::Register> {
+        APBDMACHAN_CHANNEL_AHB_SEQ_0::AHB_ADDR_WRAP.val(self as u32)
+    }
+
+    fn get_apb_field_value(self) -> FieldValue<u32, APBDMACHAN_CHANNEL_APB_SEQ_0::Register> {
+        APBDMACHAN_CHANNEL_APB_SEQ_0::APB_ADDR_WRAP.val(self as u32)
+    }
+}
+
+/// Supported APB bus widths for DMA transfers.
+///
+/// This must match the width of the peripheral register that is accessed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BusWidth {
+    /// 8-bit accesses.
+    Bits8,
+    /// 16-bit accesses.
+    Bits16,
+    /// 32-bit accesses.
+    Bits32,
+}
+
+impl BusWidth {
+    fn get_field_value(self) -> FieldValue<u32, APBDMACHAN_CHANNEL_APB_SEQ_0::Register> {
+        match self {
+            BusWidth::Bits8 => APBDMACHAN_CHANNEL_APB_SEQ_0::APB_BUS_WIDTH::BusWidth8,
+            BusWidth::Bits16 => APBDMACHAN_CHANNEL_APB_SEQ_0::APB_BUS_WIDTH::BusWidth16,
+            BusWidth::Bits32 => APBDMACHAN_CHANNEL_APB_SEQ_0::APB_BUS_WIDTH::BusWidth32,
+        }
+    }
+}
+
+/// Configuration of the bus sequencing for transfers in a DMA [`Channel`].
+///
+/// [`Channel`]: struct.Channel.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferConfig {
+    /// The burst size on the AHB side of the transfer.
+    pub ahb_burst: BurstSize,
+    /// The address wrap-around window on the AHB side of the transfer.
+    pub ahb_wrap: AddressWrap,
+    /// The address wrap-around window on the APB side of the transfer.
+    pub apb_wrap: AddressWrap,
+    /// The bus width on the APB side of the transfer.
+    pub apb_width: BusWidth,
+}
+
+impl TransferConfig {
+    /// The configuration for transfers between memory and a peripheral FIFO register.
+    ///
+    /// Uses 1 word bursts with linear AHB addressing and wraps the APB address on every word
+    /// of the 32-bit APB bus.
+    pub const PERIPHERAL: Self = TransferConfig {
+        ahb_burst: BurstSize::Words1,
+        ahb_wrap: AddressWrap::NoWrap,
+        apb_wrap: AddressWrap::Words1,
+        apb_width: BusWidth::Bits32,
+    };
+
+    /// The configuration for transfers that walk both buffers linearly.
+    ///
+    /// Uses 1 word bursts with linear addressing on both sides and a 32-bit APB bus.
+    pub const LINEAR: Self = TransferConfig {
+        ahb_burst: BurstSize::Words1,
+        ahb_wrap: AddressWrap::NoWrap,
+        apb_wrap: AddressWrap::NoWrap,
+        apb_width: BusWidth::Bits32,
+    };
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        TransferConfig::PERIPHERAL
     }
 }
 
+/// The direction of a DMA transfer, relative to the peripheral on the APB side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferDirection {
+    /// Data is read from memory and written to the peripheral.
+    ToPeripheral,
+    /// Data is read from the peripheral and written to memory.
+    FromPeripheral,
+}
+
+/// A single block of a scatter-gather transfer.
+///
+/// See [`Channel::run_sg`] for details.
+///
+/// [`Channel::run_sg`]: struct.Channel.html#method.run_sg
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DmaDescriptor {
+    /// The address of the block on the AHB side.
+    pub ahb_address: u32,
+    /// The address of the block on the APB side.
+    pub apb_address: u32,
+    /// The amount of 32-bit words to transfer.
+    pub size: u32,
+    /// The direction of the transfer.
+    pub direction: TransferDirection,
+}
+
 /// Representation of an APB DMA Channel.
 ///
 /// Channels are used for data transfers over DMA by the DMA [`Controller`] and need to be
@@ -368,13 +559,15 @@ impl Channel {
 impl Channel {
     /// Acquires the selected channel.
     ///
-    /// Returns an error if the channel is already claimed at the time of calling the method.
+    /// Returns [`DmaError::ChannelBusy`] if the channel is already claimed at the time of
+    /// calling the method.
     /// When the channel is not needed anymore, it must be freed with [`Channel::release`].
     ///
+    /// [`DmaError::ChannelBusy`]: enum.DmaError.html#variant.ChannelBusy
     /// [`Channel::release`]: #method.release
-    pub fn acquire(&mut self) -> Result<(), ()> {
+    pub fn acquire(&mut self) -> Result<(), DmaError> {
         if self.claimed {
-            return Err(());
+            return Err(DmaError::ChannelBusy);
         }
 
         // Mark the channel as claimed.
@@ -385,10 +578,13 @@ impl Channel {
 
     /// Releases the selected channel after it is not needed anymore.
     ///
-    /// Returns an error if the channel is not claimed at the time of calling the method.
-    pub fn release(&mut self) -> Result<(), ()> {
+    /// Returns [`DmaError::ChannelNotAcquired`] if the channel is not claimed at the time
+    /// of calling the method.
+    ///
+    /// [`DmaError::ChannelNotAcquired`]: enum.DmaError.html#variant.ChannelNotAcquired
+    pub fn release(&mut self) -> Result<(), DmaError> {
         if !self.claimed {
-            return Err(());
+            return Err(DmaError::ChannelNotAcquired);
         }
 
         while self.is_busy() {
@@ -459,6 +655,25 @@ impl Channel {
         }
     }
 
+    /// Gets the number of words of the current block that are yet to be transferred.
+    ///
+    /// Together with the size of the block, this allows for tracking the progress
+    /// of a transfer.
+    pub fn words_remaining(&self) -> u32 {
+        let channel = unsafe { &*self.registers };
+
+        // The word count is programmed as the amount of words minus one.
+        let total = channel
+            .APBDMACHAN_CHANNEL_WCOUNT_0
+            .read(APBDMACHAN_CHANNEL_WCOUNT_0::WCOUNT)
+            + 1;
+        let transferred = channel
+            .DMACHAN_CHANNEL_WORD_TRANSFER_0
+            .read(DMACHAN_CHANNEL_WORD_TRANSFER_0::COUNT);
+
+        total.saturating_sub(transferred)
+    }
+
     /// Indicates whether the channel is in idle state and ready for transfers.
     ///
     /// This is the counterpart to [`Channel::is_busy`].
@@ -468,6 +683,59 @@ impl Channel {
         !self.is_busy()
     }
 
+    /// Enables the end-of-transfer interrupt of the selected channel.
+    ///
+    /// Once enabled, the channel raises an interrupt to the CPU whenever a block transfer
+    /// completes. Completion can then be checked through [`Channel::interrupt_fired`]
+    /// instead of busy-polling [`Channel::is_busy`].
+    ///
+    /// [`Channel::interrupt_fired`]: #method.interrupt_fired
+    /// [`Channel::is_busy`]: #method.is_busy
+    pub fn enable_interrupt(&self) {
+        let channel = unsafe { &*self.registers };
+
+        // Route the interrupt to the CPU.
+        channel
+            .APBDMACHAN_CHANNEL_AHB_SEQ_0
+            .modify(APBDMACHAN_CHANNEL_AHB_SEQ_0::INTR_ENB::Cpu);
+
+        channel
+            .APBDMACHAN_CHANNEL_CSR_0
+            .modify(APBDMACHAN_CHANNEL_CSR_0::IE_EOC::SET);
+    }
+
+    /// Disables the end-of-transfer interrupt of the selected channel.
+    pub fn disable_interrupt(&self) {
+        let channel = unsafe { &*self.registers };
+
+        channel
+            .APBDMACHAN_CHANNEL_CSR_0
+            .modify(APBDMACHAN_CHANNEL_CSR_0::IE_EOC::CLEAR);
+    }
+
+    /// Indicates whether the end-of-transfer interrupt of the selected channel is pending.
+    ///
+    /// The status remains set until it is acknowledged through [`Channel::clear_interrupt`].
+    ///
+    /// [`Channel::clear_interrupt`]: #method.clear_interrupt
+    pub fn interrupt_fired(&self) -> bool {
+        let channel = unsafe { &*self.registers };
+
+        channel
+            .APBDMACHAN_CHANNEL_STA_0
+            .matches_all(APBDMACHAN_CHANNEL_STA_0::ISE_EOC::Intr)
+    }
+
+    /// Acknowledges a pending end-of-transfer interrupt of the selected channel.
+    pub fn clear_interrupt(&self) {
+        let channel = unsafe { &*self.registers };
+
+        // The status is cleared by writing a 1 to it, leave the other bits untouched.
+        channel
+            .APBDMACHAN_CHANNEL_STA_0
+            .write(APBDMACHAN_CHANNEL_STA_0::ISE_EOC::Intr);
+    }
+
     /// Prepares data to be queried through the selected channel over DMA.
     ///
     /// This function doesn't actually trigger transfers, it only prepares the data output
@@ -475,52 +743,86 @@ impl Channel {
     /// through [`Channel::start`] and terminated through [`Channel::finish`].
     /// For transfer status details, see [`Channel::is_busy`].
     ///
+    /// The transfer is configured with [`TransferConfig::PERIPHERAL`], see
+    /// [`Channel::query_with_config`] for choosing a different burst size and
+    /// address wrapping.
+    ///
+    /// Sizes beyond [`MAX_BLOCK_WORDS`] are rejected, larger buffers can be transferred
+    /// through [`Channel::transfer_large`].
+    ///
     /// [`Channel::start`]: struct.Channel.html#method.start
     /// [`Channel::finish`]: struct.Channel.html#method.finish
     /// [`Channel::is_busy`]: struct.Channel.html#method.is_busy
+    /// [`TransferConfig::PERIPHERAL`]: struct.TransferConfig.html#associatedconstant.PERIPHERAL
+    /// [`Channel::query_with_config`]: struct.Channel.html#method.query_with_config
+    /// [`MAX_BLOCK_WORDS`]: constant.MAX_BLOCK_WORDS.html
+    /// [`Channel::transfer_large`]: struct.Channel.html#method.transfer_large
     pub fn query(
         &self,
         slave: u32,
         ahb_address: u32,
         apb_address: u32,
         size: u32,
-    ) -> Result<(), ()> {
-        let channel = unsafe { &*self.registers };
-
-        if size == 0 {
-            return Err(());
-        }
-
-        // Program AHB and APB Starting addresses.
-        channel.APBDMACHAN_CHANNEL_AHB_PTR_0.set(ahb_address);
-        channel.APBDMACHAN_CHANNEL_APB_PTR_0.set(apb_address);
-
-        // Set AHB 1 word burst, and no address wrapping.
-        channel.APBDMACHAN_CHANNEL_AHB_SEQ_0.modify(
-            APBDMACHAN_CHANNEL_AHB_SEQ_0::AHB_BURST::DmaBurst1Words
-                + APBDMACHAN_CHANNEL_AHB_SEQ_0::AHB_ADDR_WRAP::NoWrap,
-        );
+    ) -> Result<(), DmaError> {
+        self.query_with_config(
+            slave,
+            ahb_address,
+            apb_address,
+            size,
+            &TransferConfig::PERIPHERAL,
+        )
+    }
 
-        // Set APB bus width, and address wrap for each word.
-        channel.APBDMACHAN_CHANNEL_APB_SEQ_0.modify(
-            APBDMACHAN_CHANNEL_APB_SEQ_0::APB_BUS_WIDTH::BusWidth32
-                + APBDMACHAN_CHANNEL_APB_SEQ_0::APB_ADDR_WRAP::WrapOn1Words,
-        );
+    /// Prepares `len` bytes to be queried through the selected channel over DMA.
+    ///
+    /// This is equivalent to [`Channel::query`] with the size given in bytes instead
+    /// of 32-bit words.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `len` is not a multiple of 4.
+    ///
+    /// [`Channel::query`]: struct.Channel.html#method.query
+    pub fn query_bytes(
+        &self,
+        slave: u32,
+        ahb_address: u32,
+        apb_address: u32,
+        len: u32,
+    ) -> Result<(), DmaError> {
+        assert_eq!(len & 3, 0, "Length must be a multiple of 4 bytes!");
 
-        // Set the amount of words to be transferred.
-        channel.APBDMACHAN_CHANNEL_WCOUNT_0.set((size - 1) as u32);
+        self.query(slave, ahb_address, apb_address, len >> 2)
+    }
 
+    /// Prepares data to be queried through the selected channel over DMA, using the
+    /// burst size, address wrapping and APB bus width from the supplied [`TransferConfig`].
+    ///
+    /// See [`Channel::query`] for further explanation.
+    ///
+    /// [`TransferConfig`]: struct.TransferConfig.html
+    /// [`Channel::query`]: struct.Channel.html#method.query
+    pub fn query_with_config(
+        &self,
+        slave: u32,
+        ahb_address: u32,
+        apb_address: u32,
+        size: u32,
+        config: &TransferConfig,
+    ) -> Result<(), DmaError> {
         // Set transfer mode to one block at a time (64kB),
         // set DMA direction for AHB to read,
         // and set up flow control.
-        channel.APBDMACHAN_CHANNEL_CSR_0.modify(
+        self.configure(
+            ahb_address,
+            apb_address,
+            size,
+            config,
             APBDMACHAN_CHANNEL_CSR_0::ONCE::SingleBlock
                 + APBDMACHAN_CHANNEL_CSR_0::DIR::AhbRead
                 + APBDMACHAN_CHANNEL_CSR_0::REQ_SEL.val(slave)
                 + APBDMACHAN_CHANNEL_CSR_0::FLOW::SET,
-        );
-
-        Ok(())
+        )
     }
 
     /// Prepares data to be written through the selected channel over DMA.
@@ -530,50 +832,294 @@ impl Channel {
     /// [`Channel::start`] and terminated through [`Channel::finish`].
     /// For transfer status details, see [`Channel::is_busy`].
     ///
+    /// The transfer is configured with [`TransferConfig::PERIPHERAL`], see
+    /// [`Channel::write_with_config`] for choosing a different burst size and
+    /// address wrapping.
+    ///
+    /// Sizes beyond [`MAX_BLOCK_WORDS`] are rejected, larger buffers can be transferred
+    /// through [`Channel::transfer_large`].
+    ///
     /// [`Channel::start`]: struct.Channel.html#method.start
     /// [`Channel::finish`]: struct.Channel.html#method.finish
     /// [`Channel::is_busy`]: struct.Channel.html#method.is_busy
+    /// [`TransferConfig::PERIPHERAL`]: struct.TransferConfig.html#associatedconstant.PERIPHERAL
+    /// [`Channel::write_with_config`]: struct.Channel.html#method.write_with_config
+    /// [`MAX_BLOCK_WORDS`]: constant.MAX_BLOCK_WORDS.html
+    /// [`Channel::transfer_large`]: struct.Channel.html#method.transfer_large
     pub fn write(
         &self,
         slave: u32,
         ahb_address: u32,
         apb_address: u32,
         size: u32,
-    ) -> Result<(), ()> {
-        let channel = unsafe { &*self.registers };
+    ) -> Result<(), DmaError> {
+        self.write_with_config(
+            slave,
+            ahb_address,
+            apb_address,
+            size,
+            &TransferConfig::PERIPHERAL,
+        )
+    }
+
+    /// Prepares `len` bytes to be written through the selected channel over DMA.
+    ///
+    /// This is equivalent to [`Channel::write`] with the size given in bytes instead
+    /// of 32-bit words.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `len` is not a multiple of 4.
+    ///
+    /// [`Channel::write`]: struct.Channel.html#method.write
+    pub fn write_bytes(
+        &self,
+        slave: u32,
+        ahb_address: u32,
+        apb_address: u32,
+        len: u32,
+    ) -> Result<(), DmaError> {
+        assert_eq!(len & 3, 0, "Length must be a multiple of 4 bytes!");
+
+        self.write(slave, ahb_address, apb_address, len >> 2)
+    }
+
+    /// Prepares data to be written through the selected channel over DMA, using the
+    /// burst size, address wrapping and APB bus width from the supplied [`TransferConfig`].
+    ///
+    /// See [`Channel::write`] for further explanation.
+    ///
+    /// [`TransferConfig`]: struct.TransferConfig.html
+    /// [`Channel::write`]: struct.Channel.html#method.write
+    pub fn write_with_config(
+        &self,
+        slave: u32,
+        ahb_address: u32,
+        apb_address: u32,
+        size: u32,
+        config: &TransferConfig,
+    ) -> Result<(), DmaError> {
+        // Set transfer mode to one block at a time (64kB),
+        // set DMA direction for AHB to write,
+        // and set up flow control.
+        self.configure(
+            ahb_address,
+            apb_address,
+            size,
+            config,
+            APBDMACHAN_CHANNEL_CSR_0::ONCE::SingleBlock
+                + APBDMACHAN_CHANNEL_CSR_0::DIR::AhbWrite
+                + APBDMACHAN_CHANNEL_CSR_0::REQ_SEL.val(slave)
+                + APBDMACHAN_CHANNEL_CSR_0::FLOW::SET,
+        )
+    }
+
+    /// Prepares a copy of `words` 32-bit words from an AHB address into the APB address
+    /// space through the selected channel.
+    ///
+    /// Unlike [`Channel::query`] and [`Channel::write`], this doesn't involve a peripheral,
+    /// so no request selector needs to be supplied. The transfer is not flow-controlled and
+    /// runs as fast as the buses permit once started. The source address is read over the
+    /// AHB side of the controller, while the destination address is written through the
+    /// APB pointer, with address wrapping disabled on both sides so that both buffers are
+    /// walked linearly.
+    ///
+    /// NOTE: The APB pointer can only address the APB register space, so AHB-to-AHB copies,
+    /// e.g. from IRAM to DRAM, are not possible on this engine and have to be done by the
+    /// CPU or a blit through the [`vic`]. Destinations that don't lie entirely within the
+    /// APB address space are rejected with [`DmaError::InvalidDestination`].
+    ///
+    /// The transfer is configured with [`TransferConfig::LINEAR`], which uses a burst
+    /// length of 1 word. 4 and 8 word bursts significantly improve throughput for bulk
+    /// copies as long as `words` is a multiple of the burst length, see
+    /// [`Channel::ahb_to_apb_with_config`].
+    ///
+    /// This method doesn't actually trigger transfers, it only configures the channel. The
+    /// actual transfer process can be initiated through [`Channel::start`] and terminated
+    /// through [`Channel::finish`]. For transfer status details, see [`Channel::is_busy`].
+    ///
+    /// [`Channel::query`]: struct.Channel.html#method.query
+    /// [`Channel::write`]: struct.Channel.html#method.write
+    /// [`Channel::start`]: struct.Channel.html#method.start
+    /// [`Channel::finish`]: struct.Channel.html#method.finish
+    /// [`Channel::is_busy`]: struct.Channel.html#method.is_busy
+    /// [`vic`]: ../../vic/index.html
+    /// [`TransferConfig::LINEAR`]: struct.TransferConfig.html#associatedconstant.LINEAR
+    /// [`Channel::ahb_to_apb_with_config`]: struct.Channel.html#method.ahb_to_apb_with_config
+    /// [`DmaError::InvalidDestination`]: enum.DmaError.html#variant.InvalidDestination
+    pub fn ahb_to_apb(&self, source: u32, destination: u32, words: u32) -> Result<(), DmaError> {
+        self.ahb_to_apb_with_config(source, destination, words, &TransferConfig::LINEAR)
+    }
+
+    /// Prepares a copy from an AHB address into the APB address space through the
+    /// selected channel, using the burst size and address wrapping from the supplied
+    /// [`TransferConfig`].
+    ///
+    /// See [`Channel::ahb_to_apb`] for further explanation.
+    ///
+    /// [`TransferConfig`]: struct.TransferConfig.html
+    /// [`Channel::ahb_to_apb`]: struct.Channel.html#method.ahb_to_apb
+    pub fn ahb_to_apb_with_config(
+        &self,
+        source: u32,
+        destination: u32,
+        words: u32,
+        config: &TransferConfig,
+    ) -> Result<(), DmaError> {
+        // The APB pointer can't reach beyond the APB address space.
+        let end = destination as u64 + ((words as u64) << 2);
+        if destination < APB || end > DRAM as u64 {
+            return Err(DmaError::InvalidDestination);
+        }
+
+        // Set transfer mode to one block at a time (64kB),
+        // set DMA direction for AHB to read,
+        // and disable flow control as there is no peripheral involved.
+        self.configure(
+            source,
+            destination,
+            words,
+            config,
+            APBDMACHAN_CHANNEL_CSR_0::ONCE::SingleBlock
+                + APBDMACHAN_CHANNEL_CSR_0::DIR::AhbRead
+                + APBDMACHAN_CHANNEL_CSR_0::REQ_SEL::CntrReq
+                + APBDMACHAN_CHANNEL_CSR_0::FLOW::CLEAR,
+        )
+    }
+
+    /// Transfers a chain of non-contiguous blocks through the selected channel.
+    ///
+    /// The APB DMA controller has no support for hardware linked descriptors, so the
+    /// channel is reprogrammed between the blocks, which are transferred in order. Each
+    /// block is started and finished by this method, so unlike [`Channel::query`] and
+    /// [`Channel::write`], no calls to [`Channel::start`] and [`Channel::finish`] are
+    /// needed. The transfer is configured with [`TransferConfig::PERIPHERAL`] and uses
+    /// the supplied request selector for flow control.
+    ///
+    /// All descriptors are validated before the first block is transferred. Blocks are
+    /// transferred with polling, interrupts are acknowledged along the way.
+    ///
+    /// [`Channel::query`]: struct.Channel.html#method.query
+    /// [`Channel::write`]: struct.Channel.html#method.write
+    /// [`Channel::start`]: struct.Channel.html#method.start
+    /// [`Channel::finish`]: struct.Channel.html#method.finish
+    /// [`TransferConfig::PERIPHERAL`]: struct.TransferConfig.html#associatedconstant.PERIPHERAL
+    pub fn run_sg(&self, slave: u32, descriptors: &[DmaDescriptor]) -> Result<(), DmaError> {
+        for descriptor in descriptors {
+            check_block_size(descriptor.size)?;
+        }
+
+        for descriptor in descriptors {
+            self.run_block(slave, descriptor)?;
+        }
+
+        Ok(())
+    }
 
+    /// Transfers a buffer of `size` 32-bit words, which may exceed the 64kB limit of a
+    /// single block, through the selected channel.
+    ///
+    /// The buffer is split into sequential blocks of at most [`MAX_BLOCK_WORDS`] words,
+    /// advancing the AHB address after each block while the APB address is kept as-is.
+    /// Like [`Channel::run_sg`], each block is started and finished by this method.
+    ///
+    /// The AHB address range is validated before the first block is transferred.
+    ///
+    /// [`MAX_BLOCK_WORDS`]: constant.MAX_BLOCK_WORDS.html
+    /// [`Channel::run_sg`]: struct.Channel.html#method.run_sg
+    pub fn transfer_large(
+        &self,
+        slave: u32,
+        ahb_address: u32,
+        apb_address: u32,
+        size: u32,
+        direction: TransferDirection,
+    ) -> Result<(), DmaError> {
         if size == 0 {
-            return Err(());
+            return Err(DmaError::ZeroLength);
+        }
+
+        // Reject address ranges that wrap around before any block is transferred.
+        if ahb_address as u64 + ((size as u64) << 2) > 1 << 32 {
+            return Err(DmaError::AddressOverflow);
         }
 
+        let mut offset = 0;
+        while offset < size {
+            let descriptor = DmaDescriptor {
+                ahb_address: ahb_address + offset * 4,
+                apb_address,
+                size: (size - offset).min(MAX_BLOCK_WORDS),
+                direction,
+            };
+            self.run_block(slave, &descriptor)?;
+
+            offset += descriptor.size;
+        }
+
+        Ok(())
+    }
+
+    fn run_block(&self, slave: u32, descriptor: &DmaDescriptor) -> Result<(), DmaError> {
+        match descriptor.direction {
+            TransferDirection::ToPeripheral => self.query(
+                slave,
+                descriptor.ahb_address,
+                descriptor.apb_address,
+                descriptor.size,
+            )?,
+            TransferDirection::FromPeripheral => self.write(
+                slave,
+                descriptor.ahb_address,
+                descriptor.apb_address,
+                descriptor.size,
+            )?,
+        }
+
+        self.start();
+        while self.is_busy() {
+            // Wait until the block was transferred.
+        }
+        self.finish();
+        self.clear_interrupt();
+
+        Ok(())
+    }
+
+    fn configure(
+        &self,
+        ahb_address: u32,
+        apb_address: u32,
+        size: u32,
+        config: &TransferConfig,
+        mode: FieldValue<u32, APBDMACHAN_CHANNEL_CSR_0::Register>,
+    ) -> Result<(), DmaError> {
+        let channel = unsafe { &*self.registers };
+
+        // Reject transfers that don't fit into the word count of a single block.
+        check_block_size(size)?;
+
         // Program AHB and APB Starting addresses.
         channel.APBDMACHAN_CHANNEL_AHB_PTR_0.set(ahb_address);
         channel.APBDMACHAN_CHANNEL_APB_PTR_0.set(apb_address);
 
-        // Set AHB 1 word burst, and no address wrapping.
-        channel.APBDMACHAN_CHANNEL_AHB_SEQ_0.modify(
-            APBDMACHAN_CHANNEL_AHB_SEQ_0::AHB_BURST::DmaBurst1Words
-                + APBDMACHAN_CHANNEL_AHB_SEQ_0::AHB_ADDR_WRAP::NoWrap,
-        );
+        // Set AHB burst size and address wrapping.
+        channel
+            .APBDMACHAN_CHANNEL_AHB_SEQ_0
+            .modify(config.ahb_burst.get_field_value() + config.ahb_wrap.get_ahb_field_value());
 
-        // Set APB bus width, and address wrap for each word.
-        channel.APBDMACHAN_CHANNEL_APB_SEQ_0.modify(
-            APBDMACHAN_CHANNEL_APB_SEQ_0::APB_BUS_WIDTH::BusWidth32
-                + APBDMACHAN_CHANNEL_APB_SEQ_0::APB_ADDR_WRAP::WrapOn1Words,
-        );
+        // Set APB bus width and address wrapping.
+        channel
+            .APBDMACHAN_CHANNEL_APB_SEQ_0
+            .modify(config.apb_width.get_field_value() + config.apb_wrap.get_apb_field_value());
 
         // Set the amount of words to be transferred.
-        channel.APBDMACHAN_CHANNEL_WCOUNT_0.set((size - 1) as u32);
+        channel
+            .APBDMACHAN_CHANNEL_WCOUNT_0
+            .write(APBDMACHAN_CHANNEL_WCOUNT_0::WCOUNT.val(size - 1));
 
-        // Set transfer mode to one block at a time (64kB),
-        // set DMA direction for AHB to read,
-        // and set up flow control.
-        channel.APBDMACHAN_CHANNEL_CSR_0.modify(
-            APBDMACHAN_CHANNEL_CSR_0::ONCE::SingleBlock
-                + APBDMACHAN_CHANNEL_CSR_0::DIR::AhbWrite
-                + APBDMACHAN_CHANNEL_CSR_0::REQ_SEL.val(slave)
-                + APBDMACHAN_CHANNEL_CSR_0::FLOW::SET,
-        );
+        // Configure the transfer mode, direction and flow control.
+        channel.APBDMACHAN_CHANNEL_CSR_0.modify(mode);
 
         Ok(())
     }
@@ -1,12 +1,17 @@
 //! Miscellaneous system control registers.
 
-use tock_registers::{register_structs, registers::ReadWrite};
+use tock_registers::{interfaces::Readable, register_structs, registers::ReadWrite};
+
+use self::misc_pp::APB_MISC_PP_STRAPPING_OPT_A_0;
 
 use crate::memory_map::APB;
 
 /// A pointer to the AMBA Peripheral Bus register block that can be accessed by dereferencing it.
 pub const REGISTERS: *const AmbaPeripheralBus = APB as *const AmbaPeripheralBus;
 
+/// The first major chip revision which identifies a Tegra X1+ (T210B01) SoC.
+const HIDREV_MAJOR_T210B01: u8 = 2;
+
 pub mod misc_pp {
     use tock_registers::{register_bitfields, register_structs, registers::*};
 
@@ -264,3 +269,104 @@ register_structs! {
 }
 
 assert_eq_size!(AmbaPeripheralBus, [u8; 0xB7C]);
+
+/// Tegra X1 SoC variants that can be told apart at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SocVariant {
+    /// The original Tegra X1 (T210, "Erista").
+    T210,
+    /// The revised Tegra X1+ (T210B01, "Mariko").
+    T210B01,
+}
+
+/// Chip identification decoded from the `APB_MISC_GP_HIDREV_0` register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChipId {
+    /// The chip ID, `0x21` for all Tegra X1 variants.
+    pub chip_id: u8,
+    /// The major chip revision.
+    pub major: u8,
+    /// The minor chip revision.
+    pub minor: u8,
+    /// The SoC variant derived from the major revision.
+    pub variant: SocVariant,
+}
+
+/// Reads and splits the `APB_MISC_GP_HIDREV_0` register into its fields.
+///
+/// Returns the pre-silicon platform, minor revision, chip ID, major revision and
+/// chip family, in that order.
+pub(crate) fn hidrev() -> (u32, u32, u32, u32, u32) {
+    let hidrev = unsafe { (*REGISTERS).gp.APB_MISC_GP_HIDREV_0.get() };
+
+    (
+        hidrev >> 20 & 0xF, // pre_si_platform revision.
+        hidrev >> 16 & 0xF, // Chip ID minor revision.
+        hidrev >> 8 & 0xFF, // Chip ID.
+        hidrev >> 4 & 0xF,  // Chip ID major revision.
+        hidrev & 0xF,       // Chip ID family register.
+    )
+}
+
+/// Reads and decodes the chip identification of the running SoC.
+///
+/// Unlike the `mariko` feature, this detects T210B01 hardware at runtime and
+/// allows a single binary to correctly branch for both SoC variants.
+pub fn chip_id() -> ChipId {
+    let (_, minor, chip_id, major, _) = hidrev();
+
+    let major = major as u8;
+    ChipId {
+        chip_id: chip_id as u8,
+        major,
+        minor: minor as u8,
+        variant: if major >= HIDREV_MAJOR_T210B01 {
+            SocVariant::T210B01
+        } else {
+            SocVariant::T210
+        },
+    }
+}
+
+/// The hardware straps sampled at reset, decoded from `APB_MISC_PP_STRAPPING_OPT_A_0`.
+///
+/// The values are reported as strapped, their interpretation is up to the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Straps {
+    /// The boot device selection straps.
+    pub boot_select: u8,
+    /// The straps that force the bootrom into Recovery Mode.
+    pub rcm_straps: u8,
+    /// The RAM code which identifies the populated DRAM configuration.
+    pub ram_code: u8,
+    /// Whether UART is selected for NVPROD.
+    pub nvprod_uart: bool,
+    /// Whether the bootrom uses the fast UART configuration.
+    pub boot_fast_uart: bool,
+    /// Whether the MIO interface is wide.
+    pub mio_width: bool,
+    /// Whether the NOR interface is wide.
+    pub nor_width: bool,
+}
+
+impl Straps {
+    /// Indicates whether any of the Recovery Mode straps are set.
+    pub fn rcm_forced(&self) -> bool {
+        self.rcm_straps != 0
+    }
+}
+
+/// Reads and decodes the hardware straps the SoC was booted with.
+pub fn boot_straps() -> Straps {
+    let straps = unsafe { &(*REGISTERS).pp.APB_MISC_PP_STRAPPING_OPT_A_0 };
+
+    Straps {
+        boot_select: straps.read(APB_MISC_PP_STRAPPING_OPT_A_0::BOOT_SELECT) as u8,
+        rcm_straps: straps.read(APB_MISC_PP_STRAPPING_OPT_A_0::RCM_STRAPS) as u8,
+        ram_code: straps.read(APB_MISC_PP_STRAPPING_OPT_A_0::RAM_CODE) as u8,
+        nvprod_uart: straps.is_set(APB_MISC_PP_STRAPPING_OPT_A_0::NVPROD_UART),
+        boot_fast_uart: straps.is_set(APB_MISC_PP_STRAPPING_OPT_A_0::BOOT_FAST_UART),
+        mio_width: straps.is_set(APB_MISC_PP_STRAPPING_OPT_A_0::MIO_WIDTH),
+        nor_width: straps.is_set(APB_MISC_PP_STRAPPING_OPT_A_0::NOR_WIDTH),
+    }
+}
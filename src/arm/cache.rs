@@ -52,7 +52,14 @@ impl<T, const N: usize> DerefMut for CachePad<T, { N }> {
     }
 }
 
+/// The size of a data cache line on the Cortex-A57 cores, in bytes.
+pub const DATA_CACHE_LINE_SIZE: usize = 64;
+
 /// Flushes the data cache line starting from the given address.
+///
+/// # Safety
+///
+/// `line` must be a valid, mapped virtual address.
 #[cfg(not(target_arch = "aarch64"))]
 #[allow(unused)]
 pub unsafe fn flush_data_cache_line(_: usize) {
@@ -60,28 +67,128 @@ pub unsafe fn flush_data_cache_line(_: usize) {
 }
 
 /// Flushes the data cache line starting from the given address.
+///
+/// # Safety
+///
+/// `line` must be a valid, mapped virtual address.
 #[cfg(target_arch = "aarch64")]
 pub unsafe fn flush_data_cache_line(line: usize) {
     asm!("dc civac, {}", in(reg) line);
 }
 
 /// Flushes all data cache lines which are covered by an object of known size.
+///
+/// # Safety
+///
+/// `size` must not exceed the bounds of the memory `_obj` lives in.
 pub unsafe fn flush_data_cache<T>(_obj: &T, _size: usize)
 where
     T: ?Sized,
 {
     #[cfg(target_arch = "aarch64")]
     {
-        const DATA_CACHE_LINE_SIZE: usize = 64;
-
-        let start = &_obj as *const _ as usize;
-        let end = super::align_up(start + _size, DATA_CACHE_LINE_SIZE);
+        let start = _obj as *const T as *const u8 as usize;
 
         cortex_a::asm::barrier::dmb(cortex_a::asm::barrier::SY);
-        for line in (start..end).step_by(DATA_CACHE_LINE_SIZE) {
+        for line in cache_lines(start, _size) {
             // Flush all data cache lines within the given area.
             flush_data_cache_line(line);
         }
         cortex_a::asm::barrier::dmb(cortex_a::asm::barrier::SY);
     }
 }
+
+/// Yields the addresses of all data cache lines that overlap a memory range.
+#[cfg(target_arch = "aarch64")]
+fn cache_lines(start: usize, size: usize) -> impl Iterator<Item = usize> {
+    let end = super::align_up(start + size, DATA_CACHE_LINE_SIZE);
+    let start = super::align_down(start, DATA_CACHE_LINE_SIZE);
+
+    (start..end).step_by(DATA_CACHE_LINE_SIZE)
+}
+
+/// Cleans all data cache lines in the given memory range to the Point of Coherency.
+///
+/// Dirty lines are written back to memory but remain valid in the cache. This is
+/// needed before a device reads a buffer that was written by the CPU.
+///
+/// # Safety
+///
+/// `ptr` must be valid for `len` bytes.
+pub unsafe fn clean_data_cache(_ptr: *const u8, _len: usize) {
+    #[cfg(target_arch = "aarch64")]
+    {
+        for line in cache_lines(_ptr as usize, _len) {
+            asm!("dc cvac, {}", in(reg) line);
+        }
+        cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::SY);
+    }
+}
+
+/// Invalidates all data cache lines in the given memory range to the Point of Coherency.
+///
+/// Lines are discarded without being written back, so subsequent reads observe
+/// what a device wrote to memory. This is needed before the CPU reads a buffer
+/// that was written by a device.
+///
+/// # Safety
+///
+/// `ptr` must be valid for `len` bytes. Since whole cache lines are invalidated,
+/// dirty data that shares a cache line with either end of the range is lost, so
+/// the range should be aligned to cache line size.
+pub unsafe fn invalidate_data_cache(_ptr: *mut u8, _len: usize) {
+    #[cfg(target_arch = "aarch64")]
+    {
+        for line in cache_lines(_ptr as usize, _len) {
+            asm!("dc ivac, {}", in(reg) line);
+        }
+        cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::SY);
+    }
+}
+
+/// Cleans and invalidates the entire data cache hierarchy by set/way.
+///
+/// # Safety
+///
+/// Set/way maintenance is not coherent with other processors. The caller must
+/// make sure that no other agent is modifying cached data concurrently.
+pub unsafe fn clean_and_invalidate_all() {
+    #[cfg(target_arch = "aarch64")]
+    {
+        let clidr: u64;
+        asm!("mrs {}, clidr_el1", out(reg) clidr);
+
+        // Walk all cache levels up to the Level of Coherency.
+        let level_of_coherency = (clidr >> 24) & 0x7;
+        for level in 0..level_of_coherency {
+            // Skip levels which do not implement a data or unified cache.
+            let cache_type = (clidr >> (level * 3)) & 0x7;
+            if cache_type < 2 {
+                continue;
+            }
+
+            // Select the data cache of the level and read its geometry.
+            let ccsidr: u64;
+            asm!("msr csselr_el1, {}", in(reg) level << 1);
+            cortex_a::asm::barrier::isb(cortex_a::asm::barrier::SY);
+            asm!("mrs {}, ccsidr_el1", out(reg) ccsidr);
+
+            let line_shift = (ccsidr & 0x7) + 4;
+            let ways = ((ccsidr >> 3) & 0x3FF) + 1;
+            let sets = ((ccsidr >> 13) & 0x7FFF) + 1;
+            let way_shift = (ways as u32 - 1).leading_zeros();
+
+            for way in 0..ways {
+                for set in 0..sets {
+                    let way = if ways > 1 { way << way_shift } else { 0 };
+                    let operand = way | (set << line_shift) | (level << 1);
+
+                    asm!("dc cisw, {}", in(reg) operand);
+                }
+            }
+        }
+
+        cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::SY);
+        cortex_a::asm::barrier::isb(cortex_a::asm::barrier::SY);
+    }
+}
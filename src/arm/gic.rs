@@ -26,6 +26,8 @@ impl Gic {
     /// The maximum number of allowed IRQs to be handled by this driver.
     const MAX_IRQS: usize = 224; // Normally 1019, but reserve some space.
     const NUM_IRQS: usize = Self::MAX_IRQS + 1;
+    /// The first interrupt number that is reserved for spurious interrupts.
+    const SPURIOUS_IRQ_START: u32 = 1020;
 
     /// Gets an instance of the GIC through the base addresses to the CPU Interface
     /// and Distributor registers.
@@ -86,6 +88,32 @@ impl Gic {
         Irq::new(num as usize)
     }
 
+    /// Acknowledges the IRQ with the highest priority from pending interrupts.
+    ///
+    /// Unlike [`Gic::get_pending_irq`], this handles spurious interrupts by
+    /// returning `None`. Interrupts with numbers beyond what is supported by the
+    /// driver are completed right away and also yield `None`.
+    ///
+    /// This function should be directly called from the CPU's IRQ exception
+    /// vector. A reference to [`IrqContext`] must be passed to this method to
+    /// ensure that this is the case.
+    ///
+    /// [`Gic::get_pending_irq`]: #method.get_pending_irq
+    /// [`IrqContext`]: struct.IrqContext.html
+    pub fn acknowledge<'ctx>(&'ctx self, _ic: &IrqContext<'ctx>) -> Option<Irq> {
+        let gicc = unsafe { &*self.gicc };
+
+        let num = gicc.GICC_IAR.read(gicc::GICC_IAR::InterruptID);
+        if num >= Self::SPURIOUS_IRQ_START {
+            None
+        } else if num as usize >= Self::NUM_IRQS {
+            gicc.GICC_EOIR.write(gicc::GICC_EOIR::EOIINTID.val(num));
+            None
+        } else {
+            Some(Irq::new(num as usize))
+        }
+    }
+
     /// Completes the handling of a currently active IRQ by signaling an EOI
     /// (End of Interrupt).
     ///
@@ -94,13 +122,23 @@ impl Gic {
     /// ensure that this is the case.
     ///
     /// [`IrqContext`]: struct.IrqContext.html
-    pub fn mark_irq_completed<'ctx>(&'ctx self, irq: Irq, _ic: &IrqContext<'ctx>) {
+    pub fn end_of_interrupt<'ctx>(&'ctx self, irq: Irq, _ic: &IrqContext<'ctx>) {
         let gicc = unsafe { &*self.gicc };
 
         let irq_num = irq.into_inner() as u32;
         gicc.GICC_EOIR.write(gicc::GICC_EOIR::EOIINTID.val(irq_num));
     }
 
+    /// Completes the handling of a currently active IRQ by signaling an EOI
+    /// (End of Interrupt).
+    ///
+    /// This is equivalent to [`Gic::end_of_interrupt`].
+    ///
+    /// [`Gic::end_of_interrupt`]: #method.end_of_interrupt
+    pub fn mark_irq_completed<'ctx>(&'ctx self, irq: Irq, ic: &IrqContext<'ctx>) {
+        self.end_of_interrupt(irq, ic);
+    }
+
     /// Registers an interrupt descriptor that corresponds to the given IRQ number.
     ///
     /// This overrides previously configured IRQ descriptors for the interrupt,
@@ -138,23 +176,45 @@ impl Gic {
         }
     }
 
+    /// Enables the forwarding of a specific IRQ to the CPU interfaces.
+    pub fn enable_irq(&self, irq: Irq) {
+        let gicd = unsafe { &*self.gicd };
+
+        // Find the index of the `ISENABLER[i]` register corresponding to the
+        // IRQ number and determine the bit to set in it.
+        let irq_num = irq.into_inner();
+        let isenabler_reg_index = irq_num >> 5;
+        let isenabler_bit = 1 << (irq_num % 32);
+
+        // Writing zeroes has no effect, so only the bit for this IRQ is written.
+        gicd.GICD_ISENABLER[isenabler_reg_index].set(isenabler_bit);
+    }
+
+    /// Disables the forwarding of a specific IRQ to the CPU interfaces.
+    pub fn disable_irq(&self, irq: Irq) {
+        let gicd = unsafe { &*self.gicd };
+
+        // Find the index of the `ICENABLER[i]` register corresponding to the
+        // IRQ number and determine the bit to set in it.
+        let irq_num = irq.into_inner();
+        let icenabler_reg_index = irq_num >> 5;
+        let icenabler_bit = 1 << (irq_num % 32);
+
+        // Writing zeroes has no effect, so only the bit for this IRQ is written.
+        gicd.GICD_ICENABLER[icenabler_reg_index].set(icenabler_bit);
+    }
+
     /// Sets the enabled state for a specific IRQ.
     ///
     /// By marking an interrupt as disabled, it will not be dispatched by the GIC
     /// anymore until it is re-enabled again and vice versa. Can be used to bypass
     /// specific IRQs either permanently or temporarily.
     pub fn set_irq_enable(&self, irq: Irq, enable: bool) {
-        let gicd = unsafe { &*self.gicd };
-
-        // Find the index of the `ISENABLER[i]` register corresponding to the
-        // IRQ number and determine bit to set or clear in it.
-        let irq_num = irq.into_inner();
-        let enable_reg_index = irq_num >> 5;
-        let enable_bit = (enable as u32) << (irq_num % 32);
-
-        // Set the bit in the corresponding ISENABLER register.
-        let isenabler = &gicd.GICD_ISENABLER[enable_reg_index];
-        isenabler.set(isenabler.get() | enable_bit);
+        if enable {
+            self.enable_irq(irq);
+        } else {
+            self.disable_irq(irq);
+        }
     }
 
     /// Configures the interrupt triggering mode for the given SPI.
@@ -233,7 +293,9 @@ impl Gic {
         igroupr.set(value | ((group << igroupr_reg_bit) & mask));
     }
 
-    /// Configures the target CPU core to route the given IRQs to.
+    /// Configures the target CPU cores to route the given IRQs to.
+    ///
+    /// `cpu` is a bit mask where bit `n` selects CPU interface `n`.
     pub fn set_spi_target_cpu(&self, irq: Irq, cpu: u32) {
         let gicd = unsafe { &*self.gicd };
 
@@ -3,8 +3,12 @@
 //! This covers both, the ARM7TDMI used as the [bpmp] and the Cortex-A53/A57 processors
 //! forming the main CPU Complex (CCPLEX).
 
-pub(crate) mod cache;
+pub mod cache;
 pub mod gic;
+#[cfg(target_arch = "aarch64")]
+mod pmu;
 mod utils;
 
+#[cfg(target_arch = "aarch64")]
+pub use pmu::*;
 pub use utils::*;
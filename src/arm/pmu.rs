@@ -0,0 +1,88 @@
+//! Cycle-accurate delays through the ARM Performance Monitors Unit.
+//!
+//! # Description
+//!
+//! The PMU provides a 64-bit cycle counter, `PMCCNTR_EL0`, which increments with
+//! every clock cycle of the CPU core it belongs to. This allows for delays that are
+//! deterministic and much finer than the microsecond resolution of the [`timer`].
+//!
+//! The counter must be turned on through [`enable_cycle_counter`] before it can be
+//! used. Since every core has its own PMU, this has to be done on each core that
+//! calls [`delay_cycles`].
+//!
+//! NOTE: The PMU is part of the Cortex-A57/A53 cores and therefore only available
+//! on `aarch64`.
+//!
+//! [`timer`]: ../timer/index.html
+//! [`enable_cycle_counter`]: fn.enable_cycle_counter.html
+//! [`delay_cycles`]: fn.delay_cycles.html
+
+use core::arch::asm;
+
+/// The enable bit in `PMCR_EL0`.
+const PMCR_E: u64 = 1 << 0;
+/// The bit in `PMCR_EL0` which makes the cycle counter overflow at 64 bits.
+const PMCR_LC: u64 = 1 << 6;
+
+/// The cycle counter enable bit in `PMCNTENSET_EL0`.
+const PMCNTENSET_C: u64 = 1 << 31;
+
+/// The bit in `PMCCFILTR_EL0` which enables counting in EL2.
+const PMCCFILTR_NSH: u64 = 1 << 27;
+
+/// Enables the cycle counter of the PMU on the current core.
+///
+/// The counter counts in all Exception Levels and is not reset by this.
+///
+/// NOTE: This must be called from EL1 or higher.
+pub fn enable_cycle_counter() {
+    unsafe {
+        asm!(
+            "
+            mrs {pmcr}, pmcr_el0
+            orr {pmcr}, {pmcr}, {flags}
+            msr pmcr_el0, {pmcr}
+            msr pmccfiltr_el0, {filter}
+            msr pmcntenset_el0, {enable}
+            isb
+            ",
+            pmcr = out(reg) _,
+            flags = in(reg) PMCR_E | PMCR_LC,
+            filter = in(reg) PMCCFILTR_NSH,
+            enable = in(reg) PMCNTENSET_C,
+            options(nomem, nostack),
+        );
+    }
+}
+
+/// Reads the current value of the cycle counter.
+#[inline]
+pub fn cycles() -> u64 {
+    let cycles: u64;
+    unsafe {
+        // Synchronize the context so the counter is not read speculatively.
+        asm!(
+            "
+            isb
+            mrs {cycles}, pmccntr_el0
+            ",
+            cycles = out(reg) cycles,
+            options(nomem, nostack),
+        );
+    }
+
+    cycles
+}
+
+/// Spins for at least `n` CPU cycles.
+///
+/// NOTE: The cycle counter must have been enabled through [`enable_cycle_counter`]
+/// on the current core beforehand, otherwise this never returns.
+///
+/// [`enable_cycle_counter`]: fn.enable_cycle_counter.html
+#[inline]
+pub fn delay_cycles(n: u64) {
+    let start = cycles();
+
+    while cycles().wrapping_sub(start) < n {}
+}
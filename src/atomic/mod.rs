@@ -7,7 +7,10 @@
 use tock_registers::interfaces::*;
 
 mod registers;
+mod spinlock;
+
 pub use registers::*;
+pub use spinlock::*;
 
 /// A raw pointer which can be used atomically.
 #[cfg(target_arch = "arm")]
@@ -78,6 +81,56 @@ macro_rules! register {
     }};
 }
 
+/// Abstraction for a `bool` that can be used atomically using
+/// the Tegra X1 atomic operations.
+pub struct AtomicBool {
+    /// The underlying atomic value, which is `0` for `false` and `1` for `true`.
+    inner: AtomicU32,
+}
+
+impl AtomicBool {
+    /// Creates a new `AtomicBool` that will operate in the given target register.
+    ///
+    /// The initial value is the given `value`.
+    ///
+    /// # Safety
+    ///
+    /// The given target register must not be used by any other
+    /// `Atomic` type, otherwise the data will get corrupted.
+    pub unsafe fn new(target_register: u32, value: bool) -> Self {
+        Self {
+            inner: AtomicU32::new(target_register, value as u32),
+        }
+    }
+
+    /// Loads the value for this `Atomic`.
+    pub fn load(&self) -> bool {
+        self.inner.get() != 0
+    }
+
+    /// Stores `val` into this atomic value.
+    pub fn store(&self, val: bool) {
+        self.inner.put(val as u32);
+    }
+
+    /// Atomically swap this value with `val`, and return the old value that
+    /// was stored in this atomic.
+    pub fn swap(&self, val: bool) -> bool {
+        self.inner.exchange(val as u32) != 0
+    }
+
+    /// Replaces the value of this atomic with `new`, if it matches `current`.
+    ///
+    /// Returns `Ok` with the previous value if it was replaced, `Err` with the
+    /// previous value otherwise.
+    pub fn compare_exchange(&self, current: bool, new: bool) -> Result<bool, bool> {
+        self.inner
+            .compare_exchange(current as u32, new as u32)
+            .map(|previous| previous != 0)
+            .map_err(|previous| previous != 0)
+    }
+}
+
 /// Abstraction for a `u32` that can be used atomically using
 /// the Tegra X1 atomic operations.
 pub struct AtomicU32 {
@@ -112,7 +165,15 @@ impl AtomicU32 {
     }
 
     /// Replaces the value of this atomic with `new`, if it matches `current`.
-    pub fn compare_exchange(&self, current: u32, new: u32) {
+    ///
+    /// The result register always receives the value the target register held
+    /// before the operation, regardless of whether the comparison succeeded. It
+    /// is only the comparison result itself which is not reported by hardware,
+    /// so it is derived by comparing the previous value with `current`.
+    ///
+    /// Returns `Ok` with the previous value if it was replaced, `Err` with the
+    /// previous value otherwise.
+    pub fn compare_exchange(&self, current: u32, new: u32) -> Result<u32, u32> {
         // setup phase: store `current` and `new` in the setup register
         register!(SETUP_V_0)[self.target_register as usize].set(new);
         register!(SETUP_C_0)[self.target_register as usize].set(current);
@@ -124,9 +185,13 @@ impl AtomicU32 {
                 + TRIGGER::ID.val(self.target_register),
         );
 
-        // if the comparison succeeded, the previous value will be in the result register,
-        // but we don't return it because it may be undefined
-        register!(RESULT_0)[self.target_register as usize].get();
+        // read the previous value out of the result register
+        let previous = register!(RESULT_0)[self.target_register as usize].get();
+        if previous == current {
+            Ok(previous)
+        } else {
+            Err(previous)
+        }
     }
 
     /// Increment the value of this atomic by `x`.
@@ -138,14 +203,32 @@ impl AtomicU32 {
 
     /// Decrement the value of this atomic by `x`.
     ///
-    /// If the addition goes under zero, a saturation will be added
-    /// and the value becomes `0`, similair to Rusts [`wrapping_sub`](usize::wrapping_sub).
+    /// The hardware saturates the result at zero, so if `x` exceeds the
+    /// current value, the value becomes `0` instead of wrapping around,
+    /// similar to Rusts [`saturating_sub`](u32::saturating_sub).
     ///
     /// Returns the previous value.
     pub fn decrement(&self, x: u32) -> u32 {
         simple_op!(self, TRIGGER::CMD::DECREMENT, x)
     }
 
+    /// Decrement the value of this atomic by `x` and report whether the
+    /// result saturated.
+    ///
+    /// This performs the same single operation as [`decrement`](Self::decrement),
+    /// the saturation is derived from the previous value it returns.
+    ///
+    /// Returns `Ok` with the previous value if it was at least `x`, `Err` with
+    /// the previous value if the result saturated at `0` instead.
+    pub fn try_decrement(&self, x: u32) -> Result<u32, u32> {
+        let previous = self.decrement(x);
+        if previous >= x {
+            Ok(previous)
+        } else {
+            Err(previous)
+        }
+    }
+
     /// Loads the value for this `Atomic`.
     pub fn get(&self) -> u32 {
         // for the get operation, no setup is required, so trigger the operation instantly
@@ -222,7 +305,15 @@ impl AtomicU64 {
     }
 
     /// Replaces the value of this atomic with `new`, if it matches `current`.
-    pub fn compare_exchange(&self, current: u64, new: u64) {
+    ///
+    /// The result register always receives the value the target register held
+    /// before the operation, regardless of whether the comparison succeeded. It
+    /// is only the comparison result itself which is not reported by hardware,
+    /// so it is derived by comparing the previous value with `current`.
+    ///
+    /// Returns `Ok` with the previous value if it was replaced, `Err` with the
+    /// previous value otherwise.
+    pub fn compare_exchange(&self, current: u64, new: u64) -> Result<u64, u64> {
         // setup phase: store `current` and `new` in the setup register,
         // by storing the lsb and msb in two different registers
         let new_low = (new & 0xFFFFFFFF) as u32;
@@ -234,8 +325,8 @@ impl AtomicU64 {
         register!(SETUP_V_0)[self.target_register as usize].set(new_low);
         register!(SETUP_V_0)[self.target_register as usize + 1].set(new_high);
 
-        register!(SETUP_C_0)[self.target_register as usize].set(current_high);
-        register!(SETUP_C_0)[self.target_register as usize + 1].set(current_low);
+        register!(SETUP_C_0)[self.target_register as usize].set(current_low);
+        register!(SETUP_C_0)[self.target_register as usize + 1].set(current_high);
 
         // trigger the operation
         register!(TRIGGER_0).write(
@@ -244,10 +335,16 @@ impl AtomicU64 {
                 + TRIGGER::ID.val(self.target_register),
         );
 
-        // if the comparison succeeded, the previous value will be in the result register,
-        // but we don't return it because it may be undefined
-        register!(RESULT_0)[self.target_register as usize].get();
-        register!(RESULT_0)[self.target_register as usize + 1].get();
+        // read the lsb and msb of the previous value from two result registers
+        let low = register!(RESULT_0)[self.target_register as usize].get() as u64;
+        let high = register!(RESULT_0)[self.target_register as usize + 1].get() as u64;
+
+        let previous = (high << 32) | low;
+        if previous == current {
+            Ok(previous)
+        } else {
+            Err(previous)
+        }
     }
 
     /// Increment the value of this atomic by `x`.
@@ -259,14 +356,32 @@ impl AtomicU64 {
 
     /// Decrement the value of this atomic by `x`.
     ///
-    /// If the addition goes under zero, a saturation will be added
-    /// and the value becomes `0`, similair to Rusts [`saturating_sub`](usize::saturating_sub).
+    /// The hardware saturates the result at zero, so if `x` exceeds the
+    /// current value, the value becomes `0` instead of wrapping around,
+    /// similar to Rusts [`saturating_sub`](u64::saturating_sub).
     ///
     /// Returns the previous value.
     pub fn decrement(&self, x: u64) -> u64 {
         simple_op!(u64, self, TRIGGER::CMD::DECREMENT, x)
     }
 
+    /// Decrement the value of this atomic by `x` and report whether the
+    /// result saturated.
+    ///
+    /// This performs the same single operation as [`decrement`](Self::decrement),
+    /// the saturation is derived from the previous value it returns.
+    ///
+    /// Returns `Ok` with the previous value if it was at least `x`, `Err` with
+    /// the previous value if the result saturated at `0` instead.
+    pub fn try_decrement(&self, x: u64) -> Result<u64, u64> {
+        let previous = self.decrement(x);
+        if previous >= x {
+            Ok(previous)
+        } else {
+            Err(previous)
+        }
+    }
+
     /// Loads the value for this `Atomic`.
     pub fn get(&self) -> u64 {
         // for the get operation, no setup is required, so trigger the operation instantly
@@ -0,0 +1,122 @@
+//! A spinlock built on top of the Tegra X1 atomic operations.
+//!
+//! Since the hardware atomics are shared between the CCPLEX and the BPMP, the
+//! [`SpinLock`] can be used to provide mutual exclusion across both processors.
+//!
+//! [`SpinLock`]: struct.SpinLock.html
+
+use core::{
+    cell::UnsafeCell,
+    ops::{Deref, DerefMut},
+    sync::atomic::{fence, Ordering},
+};
+
+use super::AtomicU32;
+
+/// The value of the lock target register when the lock is free.
+const UNLOCKED: u32 = 0;
+
+/// The value of the lock target register when the lock is held.
+const LOCKED: u32 = 1;
+
+/// A mutual exclusion primitive that spins until the lock is acquired.
+///
+/// The lock state lives in a dedicated target register of the hardware
+/// atomics, so it is consistent between all processors of the SoC.
+pub struct SpinLock<T> {
+    /// The atomic value which holds the lock state.
+    lock: AtomicU32,
+    /// The data which is protected by the lock.
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for SpinLock<T> {}
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    /// Creates a new, unlocked `SpinLock` that will operate in the given target
+    /// register and protects `data`.
+    ///
+    /// # Safety
+    ///
+    /// The given target register must not be used by any other
+    /// `Atomic` type, otherwise the data will get corrupted.
+    /// The lock must not be held by another processor at the time of creation.
+    pub unsafe fn new(target_register: u32, data: T) -> Self {
+        Self {
+            lock: AtomicU32::new(target_register, UNLOCKED),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Acquires the lock, spinning until it becomes available.
+    ///
+    /// The lock is released when the returned guard goes out of scope.
+    pub fn lock(&self) -> SpinLockGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Attempts to acquire the lock without spinning.
+    ///
+    /// Returns `None` if the lock is currently held.
+    pub fn try_lock(&self) -> Option<SpinLockGuard<'_, T>> {
+        self.lock.compare_exchange(UNLOCKED, LOCKED).ok().map(|_| {
+            // Make sure no access to the data is performed before the lock is held.
+            fence(Ordering::Acquire);
+
+            SpinLockGuard { lock: self }
+        })
+    }
+
+    /// Gets a mutable reference to the protected data.
+    ///
+    /// No locking is needed since the mutable borrow statically guarantees
+    /// that no other reference to the lock exists.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Consumes the lock and returns the protected data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// A guard that provides access to the data protected by a [`SpinLock`].
+///
+/// The lock is released when the guard is dropped.
+///
+/// [`SpinLock`]: struct.SpinLock.html
+pub struct SpinLockGuard<'a, T> {
+    /// The lock which is held by this guard.
+    lock: &'a SpinLock<T>,
+}
+
+impl<T> Deref for SpinLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T> DerefMut for SpinLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T> Drop for SpinLockGuard<'_, T> {
+    fn drop(&mut self) {
+        // Make sure all accesses to the data are complete before the lock is released.
+        fence(Ordering::Release);
+
+        self.lock.lock.put(UNLOCKED);
+    }
+}
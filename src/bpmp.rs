@@ -69,17 +69,193 @@
 //! }
 //! ```
 //!
+//! ## Messaging
+//!
+//! Services of the BPMP-FW, such as clock and voltage changes, are requested
+//! by exchanging messages over channels in shared memory. The ownership of a
+//! channel is tracked in the arbitration semaphores and the BPMP is notified
+//! of pending requests through a doorbell interrupt. [`send_message`] does the
+//! full handshake for a single request.
+//!
 //! [Flow Controller]: ../flow
 //! [`msleep`]: fn.msleep.html
 //! [`usleep`]: fn.usleep.html
 //! [RTC]: ../timer
 //! [`halt`]: fn.halt.html
+//! [`send_message`]: fn.send_message.html
+
+use core::{cmp::min, ptr};
+
+use tock_registers::{interfaces::*, registers::ReadWrite};
+
+use crate::{
+    atomic, flow,
+    memory_map::{ARB_SEMA, ICTLR},
+    timer::{get_microseconds, has_elapsed},
+};
+
+/// The maximum size of the payload of a message in bytes.
+pub const MESSAGE_DATA_SIZE: usize = 120;
+
+/// The number of channels whose states are tracked in the arbitration semaphores.
+pub const CHANNEL_COUNT: u32 = 16;
+
+/// The time in microseconds to wait for a channel to be freed or a response to arrive.
+const MESSAGE_TIMEOUT: u32 = 1_000_000;
+
+/// Requests the BPMP-FW to acknowledge the message with a response.
+const MESSAGE_FLAG_ACK: u32 = 1 << 0;
+/// Requests the BPMP-FW to ring the doorbell once the response is ready.
+const MESSAGE_FLAG_RING: u32 = 1 << 1;
+
+/// The interrupt line that acts as the doorbell of the BPMP.
+const DOORBELL_IRQ: u32 = 6;
+
+/// The Forced Interrupt Set register of the first Legacy Interrupt Controller bank.
+const ICTLR_FIR_SET: u32 = ICTLR + 0x18;
+
+/// The Shared Semaphore Status register of the arbitration semaphores.
+const SHRD_SMP_STA: u32 = ARB_SEMA;
+/// The Shared Semaphore Clear register of the arbitration semaphores.
+const SHRD_SMP_CLR: u32 = ARB_SEMA + 0x8;
+
+/// The channel is free to be used by the master.
+const CHANNEL_MA_FREE: u32 = 0x2;
+/// The response of the slave was acknowledged.
+const CHANNEL_MA_ACKD: u32 = 0x3;
+
+/// The layout of a message in the shared memory of a channel.
+#[repr(C)]
+struct MessageData {
+    code: i32,
+    flags: u32,
+    data: [u8; MESSAGE_DATA_SIZE],
+}
+
+assert_eq_size!(MessageData, [u8; 0x80]);
+
+/// Errors that may occur while exchanging messages with the BPMP-FW.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BpmpError {
+    /// The channel did not become free in time.
+    ChannelBusy,
+    /// The BPMP-FW did not respond in time.
+    Timeout,
+    /// The request or response exceeds [`MESSAGE_DATA_SIZE`].
+    ///
+    /// [`MESSAGE_DATA_SIZE`]: constant.MESSAGE_DATA_SIZE.html
+    MessageTooLarge,
+    /// The channel is not below [`CHANNEL_COUNT`].
+    ///
+    /// [`CHANNEL_COUNT`]: constant.CHANNEL_COUNT.html
+    InvalidChannel,
+    /// The BPMP-FW responded with the contained error code.
+    Firmware(i32),
+}
+
+fn channel_state(channel: u32) -> u32 {
+    let status = unsafe { &*(SHRD_SMP_STA as *const ReadWrite<u32>) };
+
+    (status.get() >> (channel * 2)) & 0x3
+}
+
+fn wait_for_channel_state(channel: u32, state: u32) -> bool {
+    let start = get_microseconds();
+    while channel_state(channel) != state {
+        if has_elapsed(start, get_microseconds(), MESSAGE_TIMEOUT) {
+            return false;
+        }
+    }
 
-use core::cmp::min;
+    true
+}
+
+fn channel_data(channel: u32) -> *mut MessageData {
+    let atomics = unsafe { &*atomic::REGISTERS };
+
+    // The BPMP-FW publishes the address of the shared memory in the atomic registers.
+    atomics.ATOMICS_AP0_TRIGGER_0.write(
+        atomic::TRIGGER::CMD::GET
+            + atomic::TRIGGER::WIDTH64::CLEAR
+            + atomic::TRIGGER::ID.val(channel),
+    );
+    atomics.ATOMICS_AP0_RESULT_0[channel as usize].get() as usize as *mut MessageData
+}
+
+/// Sends a message request with the `mrq` code to the BPMP-FW over `channel`
+/// and waits for the response.
+///
+/// The `request` payload is copied into the shared memory of the channel and
+/// the BPMP is notified through its doorbell. Once it acknowledges the message,
+/// the beginning of the response payload is copied to `response`. Both payloads
+/// may not exceed [`MESSAGE_DATA_SIZE`] bytes.
+///
+/// An error is returned for channels that are not below [`CHANNEL_COUNT`], when
+/// the channel doesn't become free or the BPMP-FW doesn't respond in time, or
+/// when it responds with an error code.
+///
+/// [`MESSAGE_DATA_SIZE`]: constant.MESSAGE_DATA_SIZE.html
+/// [`CHANNEL_COUNT`]: constant.CHANNEL_COUNT.html
+pub fn send_message(
+    channel: u32,
+    mrq: i32,
+    request: &[u8],
+    response: &mut [u8],
+) -> Result<(), BpmpError> {
+    let doorbell = unsafe { &*(ICTLR_FIR_SET as *const ReadWrite<u32>) };
+    let clear = unsafe { &*(SHRD_SMP_CLR as *const ReadWrite<u32>) };
 
-use tock_registers::interfaces::*;
+    if channel >= CHANNEL_COUNT {
+        return Err(BpmpError::InvalidChannel);
+    }
+    if request.len() > MESSAGE_DATA_SIZE || response.len() > MESSAGE_DATA_SIZE {
+        return Err(BpmpError::MessageTooLarge);
+    }
 
-use crate::flow;
+    // Wait until the channel is free to be used.
+    if !wait_for_channel_state(channel, CHANNEL_MA_FREE) {
+        return Err(BpmpError::ChannelBusy);
+    }
+
+    // Write the request to the shared memory.
+    let message = channel_data(channel);
+    unsafe {
+        ptr::write_volatile(ptr::addr_of_mut!((*message).code), mrq);
+        ptr::write_volatile(
+            ptr::addr_of_mut!((*message).flags),
+            MESSAGE_FLAG_ACK | MESSAGE_FLAG_RING,
+        );
+        for (i, byte) in request.iter().enumerate() {
+            ptr::write_volatile(ptr::addr_of_mut!((*message).data[i]), *byte);
+        }
+    }
+
+    // Post the request and ring the doorbell.
+    clear.set(0x3 << (channel * 2));
+    doorbell.set(1 << DOORBELL_IRQ);
+
+    // Wait for the response to arrive.
+    if !wait_for_channel_state(channel, CHANNEL_MA_ACKD) {
+        return Err(BpmpError::Timeout);
+    }
+
+    // Read the response from the shared memory.
+    let code = unsafe {
+        for (i, byte) in response.iter_mut().enumerate() {
+            *byte = ptr::read_volatile(ptr::addr_of!((*message).data[i]));
+        }
+
+        ptr::read_volatile(ptr::addr_of!((*message).code))
+    };
+
+    // Acknowledge the response, which frees the channel again.
+    clear.set((CHANNEL_MA_ACKD ^ CHANNEL_MA_FREE) << (channel * 2));
+
+    match code {
+        0 => Ok(()),
+        code => Err(BpmpError::Firmware(code)),
+    }
+}
 
 /// Sleeps for the given amount of microseconds.
 ///
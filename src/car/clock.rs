@@ -1,6 +1,9 @@
 use tock_registers::{interfaces::*, registers::ReadWrite};
 
-use crate::{memory_map::CAR, timer::usleep};
+use crate::{car::registers::REGISTERS, memory_map::CAR, timer::usleep};
+
+/// The fixed output frequency of PLLP_OUT0, in Hz.
+pub const PLLP_OUT0_FREQUENCY: u32 = 408_000_000;
 
 pub const CLK_RST_CONTROLLER_RST_DEVICES_L: u32 = 0x4;
 pub const CLK_RST_CONTROLLER_RST_DEVICES_H: u32 = 0x8;
@@ -37,6 +40,10 @@ pub const CLK_RST_CONTROLLER_CLK_SOURCE_TSECB: u32 = 0x6D8;
 pub const CLK_RST_CONTROLLER_CLK_SOURCE_SOR1: u32 = 0x410;
 pub const CLK_RST_CONTROLLER_CLK_SOURCE_CSITE: u32 = 0x1D4;
 pub const CLK_RST_CONTROLLER_CLK_SOURCE_PWM: u32 = 0x110;
+pub const CLK_RST_CONTROLLER_CLK_SOURCE_SBC1: u32 = 0x134;
+pub const CLK_RST_CONTROLLER_CLK_SOURCE_SBC2: u32 = 0x118;
+pub const CLK_RST_CONTROLLER_CLK_SOURCE_SBC3: u32 = 0x11C;
+pub const CLK_RST_CONTROLLER_CLK_SOURCE_SBC4: u32 = 0x1B4;
 
 pub const CLK_L_CPU: u8 = 0;
 pub const CLK_L_BPMP: u8 = 1;
@@ -569,6 +576,54 @@ impl Clock {
         clock_source: 6,
         clock_divisor: 4,
     };
+
+    /// Representation of the SPI 1 clock.
+    ///
+    /// Derived from PLLP_OUT0 at 25.5MHz by default.
+    pub const SPI_1: Self = Clock {
+        reset: CLK_RST_CONTROLLER_RST_DEVICES_H,
+        enable: CLK_RST_CONTROLLER_CLK_OUT_ENB_H,
+        source: CLK_RST_CONTROLLER_CLK_SOURCE_SBC1,
+        index: CLK_H_SPI1,
+        clock_source: 0,
+        clock_divisor: 30,
+    };
+
+    /// Representation of the SPI 2 clock.
+    ///
+    /// Derived from PLLP_OUT0 at 25.5MHz by default.
+    pub const SPI_2: Self = Clock {
+        reset: CLK_RST_CONTROLLER_RST_DEVICES_H,
+        enable: CLK_RST_CONTROLLER_CLK_OUT_ENB_H,
+        source: CLK_RST_CONTROLLER_CLK_SOURCE_SBC2,
+        index: CLK_H_SPI2,
+        clock_source: 0,
+        clock_divisor: 30,
+    };
+
+    /// Representation of the SPI 3 clock.
+    ///
+    /// Derived from PLLP_OUT0 at 25.5MHz by default.
+    pub const SPI_3: Self = Clock {
+        reset: CLK_RST_CONTROLLER_RST_DEVICES_H,
+        enable: CLK_RST_CONTROLLER_CLK_OUT_ENB_H,
+        source: CLK_RST_CONTROLLER_CLK_SOURCE_SBC3,
+        index: CLK_H_SPI3,
+        clock_source: 0,
+        clock_divisor: 30,
+    };
+
+    /// Representation of the SPI 4 clock.
+    ///
+    /// Derived from PLLP_OUT0 at 25.5MHz by default.
+    pub const SPI_4: Self = Clock {
+        reset: CLK_RST_CONTROLLER_RST_DEVICES_U,
+        enable: CLK_RST_CONTROLLER_CLK_OUT_ENB_U,
+        source: CLK_RST_CONTROLLER_CLK_SOURCE_SBC4,
+        index: CLK_U_SPI4,
+        clock_source: 0,
+        clock_divisor: 30,
+    };
 }
 
 impl Clock {
@@ -621,29 +676,66 @@ impl Clock {
         self.disable();
 
         // Configure the clock source, if needed.
-        if self.source != CLK_NO_SOURCE {
-            unsafe {
-                (*((CAR + self.source) as *const ReadWrite<u32>))
-                    .set((self.clock_source << 29) | self.clock_divisor);
-            }
+        self.program_default_source();
+
+        self.bring_up();
+    }
+
+    /// Boots up the device, clocked from the given source with the given raw divisor
+    /// instead of its built-in default.
+    ///
+    /// The source is programmed while the device is still held in reset, so it comes
+    /// up running from the requested source. See [`Clock::set_source`] for the format
+    /// of the divisor.
+    ///
+    /// An error is returned, without touching the device, for clocks without a source
+    /// register and when the device cannot be clocked from the given source.
+    ///
+    /// [`Clock::set_source`]: #method.set_source
+    pub fn enable_with_source(&self, source: ClockSource, divisor: u8) -> Result<(), ()> {
+        // Validate the configuration before the device is taken down.
+        self.source_register()?;
+        self.encode_source(source)?;
+
+        // Disable the clock.
+        self.disable();
+
+        // Configure the requested clock source.
+        self.set_source(source, divisor)?;
+
+        self.bring_up();
+
+        Ok(())
+    }
+
+    fn program_default_source(&self) {
+        if let Ok(source_reg) = self.source_register() {
+            source_reg.set((self.clock_source << 29) | self.clock_divisor);
         }
+    }
 
+    /// Gets the delays in microseconds to wait after enabling the clock and after
+    /// taking the device off reset, respectively.
+    fn settle_delays(&self) -> (u32, u32) {
         // KFUSE needs more time for the changes to take effect.
         if self == &Self::KFUSE {
-            // Enable the clock.
-            self.set_enable(true);
-            usleep(100);
-
-            // Take clock off reset.
-            self.set_reset(false);
-            usleep(200);
+            (100, 200)
         } else {
-            // Enable the clock.
-            self.set_enable(true);
-            usleep(2);
+            (2, 0)
+        }
+    }
+
+    fn bring_up(&self) {
+        let (enable_delay, reset_delay) = self.settle_delays();
 
-            // Take clock off reset.
-            self.set_reset(false);
+        // Enable the clock.
+        self.set_enable(true);
+        usleep(enable_delay);
+
+        // Take clock off reset.
+        self.set_reset(false);
+        if reset_delay != 0 {
+            usleep(reset_delay);
         }
 
         assert!(self.is_enabled());
@@ -659,6 +751,100 @@ impl Clock {
         assert!(!self.is_enabled());
     }
 
+    fn reset_set_clr_registers(&self) -> (&'static ReadWrite<u32>, &'static ReadWrite<u32>) {
+        let car = unsafe { &*REGISTERS };
+
+        match self.reset {
+            CLK_RST_CONTROLLER_RST_DEVICES_L => (
+                &car.CLK_RST_CONTROLLER_RST_DEV_L_SET_0,
+                &car.CLK_RST_CONTROLLER_RST_DEV_L_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_RST_DEVICES_H => (
+                &car.CLK_RST_CONTROLLER_RST_DEV_H_SET_0,
+                &car.CLK_RST_CONTROLLER_RST_DEV_H_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_RST_DEVICES_U => (
+                &car.CLK_RST_CONTROLLER_RST_DEV_U_SET_0,
+                &car.CLK_RST_CONTROLLER_RST_DEV_U_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_RST_DEVICES_X => (
+                &car.CLK_RST_CONTROLLER_RST_DEV_X_SET_0,
+                &car.CLK_RST_CONTROLLER_RST_DEV_X_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_RST_DEVICES_Y => (
+                &car.CLK_RST_CONTROLLER_RST_DEV_Y_SET_0,
+                &car.CLK_RST_CONTROLLER_RST_DEV_Y_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_RST_DEVICES_V => (
+                &car.CLK_RST_CONTROLLER_RST_DEV_V_SET_0,
+                &car.CLK_RST_CONTROLLER_RST_DEV_V_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_RST_DEVICES_W => (
+                &car.CLK_RST_CONTROLLER_RST_DEV_W_SET_0,
+                &car.CLK_RST_CONTROLLER_RST_DEV_W_CLR_0,
+            ),
+            _ => unreachable!(),
+        }
+    }
+
+    fn enable_set_clr_registers(&self) -> (&'static ReadWrite<u32>, &'static ReadWrite<u32>) {
+        let car = unsafe { &*REGISTERS };
+
+        match self.enable {
+            CLK_RST_CONTROLLER_CLK_OUT_ENB_L => (
+                &car.CLK_RST_CONTROLLER_CLK_ENB_L_SET_0,
+                &car.CLK_RST_CONTROLLER_CLK_ENB_L_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_CLK_OUT_ENB_H => (
+                &car.CLK_RST_CONTROLLER_CLK_ENB_H_SET_0,
+                &car.CLK_RST_CONTROLLER_CLK_ENB_H_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_CLK_OUT_ENB_U => (
+                &car.CLK_RST_CONTROLLER_CLK_ENB_U_SET_0,
+                &car.CLK_RST_CONTROLLER_CLK_ENB_U_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_CLK_OUT_ENB_X => (
+                &car.CLK_RST_CONTROLLER_CLK_ENB_X_SET_0,
+                &car.CLK_RST_CONTROLLER_CLK_ENB_X_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_CLK_OUT_ENB_Y => (
+                &car.CLK_RST_CONTROLLER_CLK_ENB_Y_SET_0,
+                &car.CLK_RST_CONTROLLER_CLK_ENB_Y_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_CLK_OUT_ENB_V => (
+                &car.CLK_RST_CONTROLLER_CLK_ENB_V_SET_0,
+                &car.CLK_RST_CONTROLLER_CLK_ENB_V_CLR_0,
+            ),
+            CLK_RST_CONTROLLER_CLK_OUT_ENB_W => (
+                &car.CLK_RST_CONTROLLER_CLK_ENB_W_SET_0,
+                &car.CLK_RST_CONTROLLER_CLK_ENB_W_CLR_0,
+            ),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Puts the device into reset without touching its clock enable.
+    pub fn assert_reset(&self) {
+        let (set_reg, _) = self.reset_set_clr_registers();
+        set_reg.set(self.get_mask());
+    }
+
+    /// Takes the device out of reset without touching its clock enable.
+    pub fn deassert_reset(&self) {
+        let (_, clr_reg) = self.reset_set_clr_registers();
+        clr_reg.set(self.get_mask());
+    }
+
+    /// Pulses the reset of the device, leaving its clock enable untouched.
+    ///
+    /// This can be used to recover a device that is already up and running
+    /// from an erroneous state.
+    pub fn reset(&self) {
+        self.assert_reset();
+        usleep(2);
+        self.deassert_reset();
+    }
+
     /// Indicates whether the device is enabled or not.
     pub fn is_enabled(&self) -> bool {
         // Figure out the register to read from.
@@ -668,4 +854,357 @@ impl Clock {
         let mask = self.get_mask();
         (enable_reg.get() & mask) == mask
     }
+
+    #[inline(always)]
+    fn source_register(&self) -> Result<&'static ReadWrite<u32>, ()> {
+        if self.source == CLK_NO_SOURCE {
+            return Err(());
+        }
+
+        Ok(unsafe { &*((CAR + self.source) as *const ReadWrite<u32>) })
+    }
+
+    fn divisor_format(&self) -> DivisorFormat {
+        match self.source {
+            CLK_RST_CONTROLLER_CLK_SOURCE_I2C_1
+            | CLK_RST_CONTROLLER_CLK_SOURCE_I2C_2
+            | CLK_RST_CONTROLLER_CLK_SOURCE_I2C_3
+            | CLK_RST_CONTROLLER_CLK_SOURCE_I2C_4
+            | CLK_RST_CONTROLLER_CLK_SOURCE_I2C_5
+            | CLK_RST_CONTROLLER_CLK_SOURCE_I2C_6 => DivisorFormat::Integer16,
+            CLK_RST_CONTROLLER_CLK_SOURCE_UART_A
+            | CLK_RST_CONTROLLER_CLK_SOURCE_UART_B
+            | CLK_RST_CONTROLLER_CLK_SOURCE_UART_C
+            | CLK_RST_CONTROLLER_CLK_SOURCE_UART_D
+            | CLK_RST_CONTROLLER_CLK_SOURCE_UART_APE => DivisorFormat::Uart,
+            _ => DivisorFormat::Fractional8,
+        }
+    }
+
+    fn encode_source(&self, source: ClockSource) -> Result<u32, ()> {
+        // HOST1X uses a different multiplexer layout than the other peripherals.
+        if self == &Self::HOST1X {
+            return match source {
+                ClockSource::PllP => Ok(4),
+                ClockSource::ClkM => Ok(5),
+                _ => Err(()),
+            };
+        }
+
+        Ok(match source {
+            ClockSource::PllP => 0,
+            ClockSource::PllC2 => 1,
+            ClockSource::PllC => 2,
+            ClockSource::PllC3 => 3,
+            ClockSource::PllM => 4,
+            ClockSource::ClkM => 6,
+        })
+    }
+
+    fn decode_source(&self, mux: u32) -> Result<ClockSource, ()> {
+        [
+            ClockSource::PllP,
+            ClockSource::PllC2,
+            ClockSource::PllC,
+            ClockSource::PllC3,
+            ClockSource::PllM,
+            ClockSource::ClkM,
+        ]
+        .iter()
+        .copied()
+        .find(|&source| self.encode_source(source) == Ok(mux))
+        .ok_or(())
+    }
+
+    /// Gets the clock source the device is currently derived from.
+    ///
+    /// An error is returned for clocks without a source register and when the
+    /// multiplexer selects a source that is not covered by [`ClockSource`].
+    ///
+    /// [`ClockSource`]: enum.ClockSource.html
+    pub fn source(&self) -> Result<ClockSource, ()> {
+        self.decode_source(self.source_register()?.get() >> 29)
+    }
+
+    /// Selects the clock source and the raw divisor of the device.
+    ///
+    /// For most devices, the divisor is in 7.1 fixed-point format, so that the
+    /// resulting frequency is `source * 2 / (divisor + 2)`. The I²C controllers
+    /// use an integer divisor instead, which yields `source / (divisor + 1)`.
+    ///
+    /// An error is returned for clocks without a source register and when the
+    /// device cannot be clocked from the given source.
+    ///
+    /// NOTE: [`Clock::enable`] programs the built-in default source and divisor of the
+    /// device, which discards the configuration done here. To bring up a device on a
+    /// different source, use [`Clock::enable_with_source`] instead.
+    ///
+    /// [`Clock::enable`]: #method.enable
+    /// [`Clock::enable_with_source`]: #method.enable_with_source
+    pub fn set_source(&self, source: ClockSource, divisor: u8) -> Result<(), ()> {
+        let divisor = match self.divisor_format() {
+            DivisorFormat::Uart => divisor as u32 | DivisorFormat::UART_DIV_ENB,
+            _ => divisor as u32,
+        };
+
+        self.program_source(source, divisor)
+    }
+
+    fn program_source(&self, source: ClockSource, divisor: u32) -> Result<(), ()> {
+        let source_reg = self.source_register()?;
+        let mux = self.encode_source(source)?;
+
+        source_reg.set((mux << 29) | divisor);
+        usleep(2);
+
+        Ok(())
+    }
+
+    /// Reads the raw value of the `CLK_SOURCE_*` register of the device, so that
+    /// it can later be restored through [`Clock::restore_source_config`].
+    ///
+    /// [`Clock::restore_source_config`]: #method.restore_source_config
+    pub(crate) fn source_config(&self) -> Result<u32, ()> {
+        Ok(self.source_register()?.get())
+    }
+
+    /// Restores a raw `CLK_SOURCE_*` register value that was obtained through
+    /// [`Clock::source_config`].
+    ///
+    /// [`Clock::source_config`]: #method.source_config
+    pub(crate) fn restore_source_config(&self, config: u32) -> Result<(), ()> {
+        self.source_register()?.set(config);
+        usleep(2);
+
+        Ok(())
+    }
+
+    /// Computes the frequency the device is currently clocked at, in Hz.
+    ///
+    /// The rate is derived from the source selection and the divisor that are
+    /// currently programmed in the `CLK_SOURCE_*` register of the device.
+    ///
+    /// An error is returned for clocks without a source register and when the
+    /// selected clock source is not PLLP_OUT0 or CLK_M, whose rates are known.
+    pub fn current_frequency(&self) -> Result<u32, ()> {
+        let value = self.source_register()?.get();
+        let parent = self.decode_source(value >> 29)?.frequency()?;
+
+        Ok(self.divisor_format().divide(parent, value))
+    }
+
+    /// Reprograms the clock source and divisor of the device to run it at the
+    /// highest frequency that does not exceed `hz`.
+    ///
+    /// PLLP_OUT0 is preferred as the clock source, CLK_M is used as a fallback
+    /// when the requested frequency cannot be reached with the divisor range.
+    ///
+    /// An error is returned for clocks without a source register and when no
+    /// suitable configuration could be found.
+    pub fn set_frequency(&self, hz: u32) -> Result<(), ()> {
+        let format = self.divisor_format();
+
+        for &source in [ClockSource::PllP, ClockSource::ClkM].iter() {
+            if let Some(divisor) = format.compute(source.frequency()?, hz) {
+                return self.program_source(source, divisor);
+            }
+        }
+
+        Err(())
+    }
+}
+
+/// Clock sources that device clocks can be derived from.
+///
+/// Not every device can be clocked from every source, the multiplexer layouts
+/// differ between devices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockSource {
+    /// PLLP_OUT0, running at a fixed frequency of 408MHz.
+    PllP,
+    /// PLLC2_OUT0.
+    PllC2,
+    /// PLLC_OUT0.
+    PllC,
+    /// PLLC3_OUT0.
+    PllC3,
+    /// PLLM_OUT0.
+    PllM,
+    /// CLK_M, derived from the oscillator.
+    ClkM,
+}
+
+impl ClockSource {
+    /// Gets the frequency of the clock source, in Hz.
+    ///
+    /// An error is returned for PLLs whose configuration is not tracked by this
+    /// crate and whose rate therefore cannot be determined.
+    pub fn frequency(self) -> Result<u32, ()> {
+        match self {
+            ClockSource::PllP => Ok(PLLP_OUT0_FREQUENCY),
+            ClockSource::ClkM => clk_m_frequency(),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The encodings of the divisor field in `CLK_SOURCE_*` registers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DivisorFormat {
+    /// An 8-bit divisor in 7.1 fixed-point format.
+    Fractional8,
+    /// A 16-bit integer divisor, as used by the I²C controllers.
+    Integer16,
+    /// A 16-bit divisor in 7.1 fixed-point format which is only applied when
+    /// the UART divisor enable bit is set.
+    Uart,
+}
+
+impl DivisorFormat {
+    /// The UART divisor enable bit.
+    const UART_DIV_ENB: u32 = 1 << 24;
+
+    fn max(self) -> u32 {
+        match self {
+            DivisorFormat::Fractional8 => 0xFF,
+            DivisorFormat::Integer16 | DivisorFormat::Uart => 0xFFFF,
+        }
+    }
+
+    fn divide(self, parent: u32, value: u32) -> u32 {
+        let divisor = (value & self.max()) as u64;
+        let parent = parent as u64;
+
+        let rate = match self {
+            DivisorFormat::Integer16 => parent / (divisor + 1),
+            DivisorFormat::Uart if value & Self::UART_DIV_ENB == 0 => parent,
+            _ => parent * 2 / (divisor + 2),
+        };
+
+        rate as u32
+    }
+
+    fn compute(self, parent: u32, hz: u32) -> Option<u32> {
+        if hz == 0 || hz > parent {
+            return None;
+        }
+
+        let (parent, hz) = (parent as u64, hz as u64);
+        let divisor = match self {
+            DivisorFormat::Integer16 => parent.div_ceil(hz) - 1,
+            _ => (parent * 2).div_ceil(hz) - 2,
+        };
+
+        if divisor > self.max() as u64 {
+            return None;
+        }
+
+        let divisor = divisor as u32;
+        match self {
+            DivisorFormat::Uart => Some(divisor | Self::UART_DIV_ENB),
+            _ => Some(divisor),
+        }
+    }
+}
+
+/// Computes the frequency of CLK_M, in Hz.
+///
+/// CLK_M is derived from the oscillator frequency configured in
+/// `CLK_RST_CONTROLLER_OSC_CTRL_0` and the divisor in `CLK_RST_CONTROLLER_SPARE_REG0_0`.
+pub fn clk_m_frequency() -> Result<u32, ()> {
+    let car = unsafe { &*REGISTERS };
+
+    let osc = match car.CLK_RST_CONTROLLER_OSC_CTRL_0.get() >> 28 {
+        0 => 13_000_000,
+        1 => 16_800_000,
+        4 => 19_200_000,
+        5 => 38_400_000,
+        8 => 12_000_000,
+        9 => 48_000_000,
+        12 => 26_000_000,
+        _ => return Err(()),
+    };
+    let divisor = (car.CLK_RST_CONTROLLER_SPARE_REG0_0.get() >> 2) & 3;
+
+    Ok(osc / (divisor + 1))
+}
+
+/// Calls `write` once per register bank with the combined mask of all clocks in it.
+fn for_each_bank<F>(clocks: &[&Clock], mut write: F)
+where
+    F: FnMut(&Clock, u32),
+{
+    for (i, clock) in clocks.iter().enumerate() {
+        // Skip banks which have already been covered by a previous clock.
+        if clocks[..i].iter().any(|other| other.enable == clock.enable) {
+            continue;
+        }
+
+        let mask = clocks[i..]
+            .iter()
+            .filter(|other| other.enable == clock.enable)
+            .fold(0, |mask, other| mask | other.get_mask());
+        write(clock, mask);
+    }
+}
+
+/// Boots up a group of devices at once.
+///
+/// This works like [`Clock::enable`], but the clock enables and resets of all
+/// devices that share a `CLK_ENB_*`/`RST_DEV_*` register bank are changed with
+/// a single write to the respective `SET`/`CLR` register. Thus, no dependent
+/// device ends up running while another one of the same bank is still down.
+///
+/// [`Clock::enable`]: struct.Clock.html#method.enable
+pub fn enable_clocks(clocks: &[&Clock]) {
+    // Disable the clocks.
+    disable_clocks(clocks);
+
+    // Configure the clock sources, if needed.
+    for clock in clocks {
+        clock.program_default_source();
+    }
+
+    // Wait as long as the slowest device of the group requires.
+    let (enable_delay, reset_delay) = clocks
+        .iter()
+        .map(|clock| clock.settle_delays())
+        .fold((0, 0), |(e, r), (ce, cr)| (e.max(ce), r.max(cr)));
+
+    // Enable the clocks.
+    for_each_bank(clocks, |clock, mask| {
+        clock.enable_set_clr_registers().0.set(mask);
+    });
+    usleep(enable_delay);
+
+    // Take the clocks off reset.
+    for_each_bank(clocks, |clock, mask| {
+        clock.reset_set_clr_registers().1.set(mask);
+    });
+    if reset_delay != 0 {
+        usleep(reset_delay);
+    }
+
+    assert!(clocks.iter().all(|clock| clock.is_enabled()));
+}
+
+/// Disables a group of devices at once.
+///
+/// This works like [`Clock::disable`], with the register writes coalesced
+/// per register bank as described for [`enable_clocks`].
+///
+/// [`Clock::disable`]: struct.Clock.html#method.disable
+/// [`enable_clocks`]: fn.enable_clocks.html
+pub fn disable_clocks(clocks: &[&Clock]) {
+    // Put the clocks into reset.
+    for_each_bank(clocks, |clock, mask| {
+        clock.reset_set_clr_registers().0.set(mask);
+    });
+
+    // Disable.
+    for_each_bank(clocks, |clock, mask| {
+        clock.enable_set_clr_registers().1.set(mask);
+    });
+
+    assert!(clocks.iter().all(|clock| !clock.is_enabled()));
 }
@@ -20,7 +20,17 @@
 //! constant instances of itself, which represent most of the known device clocks without
 //! non-standard programming guidelines.
 //!
+//! Devices with a `CLK_SOURCE_*` register can additionally be retuned at runtime by selecting
+//! a different [`ClockSource`] and divisor, or by requesting a target frequency directly.
+//!
+//! Groups of devices that depend on each other, such as the clocks needed by the TSEC, can
+//! be brought up together through [`enable_clocks`] and [`disable_clocks`], which coalesce
+//! the register writes for clocks in the same register bank.
+//!
 //! [`Clock`]: struct.Clock.html
+//! [`ClockSource`]: enum.ClockSource.html
+//! [`enable_clocks`]: fn.enable_clocks.html
+//! [`disable_clocks`]: fn.disable_clocks.html
 
 mod clock;
 mod registers;
@@ -1,5 +1,394 @@
 //! Driver for the MIPI Display Serial Interface (DSI) implemented by the Tegra X1.
+//!
+//! See Chapter 27.4 in the Tegra X1 Technical Reference Manual for details.
+//!
+//! # Description
+//!
+//! The Tegra X1 features two DSI controllers, DSIA and DSIB, which are exposed
+//! through the [`Dsi`] structure. Each controller can be driven in two modes:
+//!
+//! - Command mode, where the host writes packets into the host FIFO and triggers
+//!   their transmission manually. This is what [`Dsi::send_command`] uses to
+//!   transfer DCS commands, e.g. for panel initialization sequences.
+//!
+//! - Video mode, where pixel data is fetched from a display controller and framed
+//!   into packets by the hardware sequencer. See [`Dsi::set_video_mode`].
+//!
+//! # Implementation details
+//!
+//! The driver does not take care of pad calibration, PHY timings or the MIPI
+//! calibration unit. These must be configured manually before the interface is
+//! used for high speed transmissions.
+//!
+//! [`Dsi`]: struct.Dsi.html
+//! [`Dsi::send_command`]: struct.Dsi.html#method.send_command
+//! [`Dsi::set_video_mode`]: struct.Dsi.html#method.set_video_mode
 
 mod registers;
 
 pub use registers::*;
+
+use tock_registers::{fields::FieldValue, interfaces::*};
+
+use crate::{car::Clock, timer};
+
+/// The depth of the host FIFO, in words.
+const HOST_FIFO_DEPTH: usize = 64;
+
+/// The DCS short write packet data type, without parameters.
+const DCS_SHORT_WRITE: u32 = 0x05;
+
+/// The DCS short write packet data type, with one parameter.
+const DCS_SHORT_WRITE_PARAM: u32 = 0x15;
+
+/// The DCS long write packet data type.
+const DCS_LONG_WRITE: u32 = 0x39;
+
+/// The Vertical Sync Start packet data type.
+const VSYNC_START: u32 = 0x01;
+
+/// The Horizontal Sync Start packet data type.
+const HSYNC_START: u32 = 0x21;
+
+/// The Blanking packet data type.
+const BLANKING_PACKET: u32 = 0x19;
+
+/// Errors that may occur when driving a DSI controller.
+#[derive(Clone, Copy, Debug)]
+pub enum Error {
+    /// The supplied command is either empty or does not fit into the host FIFO.
+    InvalidLength,
+    /// The controller did not finish the transmission in time.
+    Timeout,
+}
+
+/// The number of D-PHY data lanes used for high speed transmissions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataLanes {
+    /// 1 data lane.
+    One,
+    /// 2 data lanes.
+    Two,
+    /// 3 data lanes.
+    Three,
+    /// 4 data lanes.
+    Four,
+}
+
+impl DataLanes {
+    fn count(self) -> u32 {
+        match self {
+            DataLanes::One => 1,
+            DataLanes::Two => 2,
+            DataLanes::Three => 3,
+            DataLanes::Four => 4,
+        }
+    }
+
+    fn get_field_value(self) -> FieldValue<u32, DSI_DSI_CONTROL_0::Register> {
+        match self {
+            DataLanes::One => DSI_DSI_CONTROL_0::DSI_NUM_DATA_LANES::One,
+            DataLanes::Two => DSI_DSI_CONTROL_0::DSI_NUM_DATA_LANES::Two,
+            DataLanes::Three => DSI_DSI_CONTROL_0::DSI_NUM_DATA_LANES::Three,
+            DataLanes::Four => DSI_DSI_CONTROL_0::DSI_NUM_DATA_LANES::Four,
+        }
+    }
+}
+
+/// The format of the pixel data transmitted in video mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 16 bpp RGB, packed.
+    Rgb565,
+    /// 18 bpp RGB, not packed.
+    Rgb666,
+    /// 18 bpp RGB, packed.
+    Rgb666Packed,
+    /// 24 bpp RGB, packed.
+    Rgb888,
+}
+
+impl PixelFormat {
+    fn bits_per_pixel(self) -> u32 {
+        match self {
+            PixelFormat::Rgb565 => 16,
+            PixelFormat::Rgb666Packed => 18,
+            PixelFormat::Rgb666 | PixelFormat::Rgb888 => 24,
+        }
+    }
+
+    fn data_type(self) -> u32 {
+        match self {
+            PixelFormat::Rgb565 => 0x0E,
+            PixelFormat::Rgb666 => 0x2E,
+            PixelFormat::Rgb666Packed => 0x1E,
+            PixelFormat::Rgb888 => 0x3E,
+        }
+    }
+
+    fn get_field_value(self) -> FieldValue<u32, DSI_DSI_CONTROL_0::Register> {
+        match self {
+            PixelFormat::Rgb565 => DSI_DSI_CONTROL_0::DSI_DATA_FORMAT::Bit16P,
+            PixelFormat::Rgb666 => DSI_DSI_CONTROL_0::DSI_DATA_FORMAT::Bit18NP,
+            PixelFormat::Rgb666Packed => DSI_DSI_CONTROL_0::DSI_DATA_FORMAT::Bit18P,
+            PixelFormat::Rgb888 => DSI_DSI_CONTROL_0::DSI_DATA_FORMAT::Bit24P,
+        }
+    }
+}
+
+/// The display controller that video mode pixels are sourced from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VideoSource {
+    /// Pixels come from Display A.
+    DisplayA,
+    /// Pixels come from Display B.
+    DisplayB,
+}
+
+/// Configuration of a DSI controller operating in non-burst video mode.
+///
+/// All horizontal timings are given in pixels and will be converted to packet
+/// lengths by the driver.
+#[derive(Clone, Copy, Debug)]
+pub struct VideoModeConfig {
+    /// The number of data lanes connected to the panel.
+    pub lanes: DataLanes,
+    /// The format of the transmitted pixel data.
+    pub format: PixelFormat,
+    /// The display controller to fetch pixels from.
+    pub source: VideoSource,
+    /// The virtual channel of the panel.
+    pub virtual_channel: u8,
+    /// The number of active pixels per line.
+    pub h_active: u32,
+    /// The width of the horizontal sync pulse.
+    pub h_sync_width: u32,
+    /// The width of the horizontal back porch.
+    pub h_back_porch: u32,
+    /// The width of the horizontal front porch.
+    pub h_front_porch: u32,
+}
+
+/// Representation of a DSI controller.
+///
+/// Before sending any commands, the controller must be brought up through
+/// [`Dsi::enable`].
+///
+/// [`Dsi::enable`]: #method.enable
+#[derive(Debug)]
+pub struct Dsi {
+    // The device clock instance for the corresponding DSI controller.
+    clock: &'static Clock,
+    // A pointer to the DSI register block in memory.
+    registers: *const Registers,
+}
+
+// Definitions of known DSI controllers.
+impl Dsi {
+    /// Representation of the DSIA controller.
+    pub const A: Self = Dsi {
+        clock: &Clock::DSI,
+        registers: DSIA_REGISTERS,
+    };
+
+    /// Representation of the DSIB controller.
+    pub const B: Self = Dsi {
+        clock: &Clock::DSIB,
+        registers: DSIB_REGISTERS,
+    };
+}
+
+impl Dsi {
+    /// Enables the DSI controller in command mode.
+    ///
+    /// Packets are sent in low power mode with hardware generated ECC and
+    /// checksums and have to be triggered by the host explicitly.
+    pub fn enable(&self) {
+        let dsi = unsafe { &*self.registers };
+
+        // Enable the clock for the controller.
+        self.clock.enable();
+
+        // Keep the interface powered down while it is being configured.
+        dsi.DSI_DSI_POWER_CONTROL_0
+            .write(DSI_DSI_POWER_CONTROL_0::LEG_DSI_ENABLE::CLEAR);
+
+        dsi.DSI_HOST_DSI_CONTROL_0.write(
+            DSI_HOST_DSI_CONTROL_0::FIFO_STAT_RESET::SET
+                + DSI_HOST_DSI_CONTROL_0::CRC_RESET::SET
+                + DSI_HOST_DSI_CONTROL_0::DSI_PHY_CLK_DIV::Div1
+                + DSI_HOST_DSI_CONTROL_0::HOST_TX_TRIG_SRC::Immediate
+                + DSI_HOST_DSI_CONTROL_0::DSI_HIGH_SPEED_TRANS::Low
+                + DSI_HOST_DSI_CONTROL_0::PKT_WR_FIFO_SEL::Host
+                + DSI_HOST_DSI_CONTROL_0::CS_ENABLE::SET
+                + DSI_HOST_DSI_CONTROL_0::ECC_ENABLE::SET,
+        );
+        dsi.DSI_DSI_CONTROL_0
+            .write(DSI_DSI_CONTROL_0::DSI_HOST_ENABLE::SET);
+        dsi.DSI_DSI_TRIGGER_0.set(0);
+
+        // Power up the interface.
+        dsi.DSI_DSI_POWER_CONTROL_0
+            .write(DSI_DSI_POWER_CONTROL_0::LEG_DSI_ENABLE::SET);
+        timer::usleep(300);
+    }
+
+    /// Disables the DSI controller and gates its clock.
+    pub fn disable(&self) {
+        let dsi = unsafe { &*self.registers };
+
+        dsi.DSI_DSI_POWER_CONTROL_0
+            .write(DSI_DSI_POWER_CONTROL_0::LEG_DSI_ENABLE::CLEAR);
+        self.clock.disable();
+    }
+
+    /// Sends a DCS command to the panel on virtual channel 0.
+    ///
+    /// The first byte of `cmd` is the DCS command itself, followed by its
+    /// parameters. Commands with up to one parameter are sent as DCS short
+    /// writes, everything else as a DCS long write.
+    pub fn send_command(&self, cmd: &[u8]) -> Result<(), Error> {
+        let dsi = unsafe { &*self.registers };
+
+        // The packet header and the payload must both fit into the host FIFO.
+        if cmd.is_empty() || cmd.len().div_ceil(4) + 1 > HOST_FIFO_DEPTH {
+            return Err(Error::InvalidLength);
+        }
+
+        // Write the packet header. ECC is appended by the hardware.
+        match *cmd {
+            [command] => dsi
+                .DSI_DSI_WR_DATA_0
+                .set(DCS_SHORT_WRITE | (command as u32) << 8),
+            [command, parameter] => dsi
+                .DSI_DSI_WR_DATA_0
+                .set(DCS_SHORT_WRITE_PARAM | (command as u32) << 8 | (parameter as u32) << 16),
+            _ => {
+                dsi.DSI_DSI_WR_DATA_0
+                    .set(DCS_LONG_WRITE | (cmd.len() as u32) << 8);
+
+                // Write the payload, padding the last word with zeroes.
+                for chunk in cmd.chunks(4) {
+                    let mut word = [0; 4];
+                    word[..chunk.len()].copy_from_slice(chunk);
+
+                    dsi.DSI_DSI_WR_DATA_0.set(u32::from_le_bytes(word));
+                }
+            }
+        }
+
+        // Kick off the transmission.
+        dsi.DSI_DSI_TRIGGER_0
+            .write(DSI_DSI_TRIGGER_0::DSI_HOST_TRIGGER::SET);
+
+        let start = timer::get_milliseconds();
+        while dsi
+            .DSI_DSI_TRIGGER_0
+            .is_set(DSI_DSI_TRIGGER_0::DSI_HOST_TRIGGER)
+        {
+            // Wait until the packet was sent or facing a timeout.
+            if timer::has_elapsed(start, timer::get_milliseconds(), 250) {
+                return Err(Error::Timeout);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Switches the DSI controller to non-burst video mode with sync events.
+    ///
+    /// Active lines are framed as Horizontal Sync Start, blanking, pixel data
+    /// and blanking again while vertical blanking lines only carry sync packets
+    /// with the lanes dropping into low power mode afterwards.
+    pub fn set_video_mode(&self, config: &VideoModeConfig) {
+        let dsi = unsafe { &*self.registers };
+
+        let bpp = config.format.bits_per_pixel();
+        let bytes = |pixels: u32| pixels * bpp / 8;
+
+        // Convert the horizontal timings to packet lengths, subtracting the
+        // overhead of the packet headers and checksums.
+        let h_active = bytes(config.h_active);
+        let h_back_porch = bytes(config.h_sync_width + config.h_back_porch).saturating_sub(14);
+        let h_front_porch = bytes(config.h_front_porch).saturating_sub(8);
+
+        // Keep the interface powered down while it is being reconfigured.
+        dsi.DSI_DSI_POWER_CONTROL_0
+            .write(DSI_DSI_POWER_CONTROL_0::LEG_DSI_ENABLE::CLEAR);
+
+        // Program the packet sequence of a frame.
+        let vsync_line = DSI_DSI_PKT_SEQ_0_LO_0::SEQ_0_FORCE_LP::SET
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_00_EN::SET
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_00_ID.val(VSYNC_START)
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_00_SIZE.val(0);
+        let blank_line = DSI_DSI_PKT_SEQ_0_LO_0::SEQ_0_FORCE_LP::SET
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_00_EN::SET
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_00_ID.val(HSYNC_START)
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_00_SIZE.val(0);
+        let active_line_lo = DSI_DSI_PKT_SEQ_0_LO_0::PKT_00_EN::SET
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_00_ID.val(HSYNC_START)
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_00_SIZE.val(0)
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_01_EN::SET
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_01_ID.val(BLANKING_PACKET)
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_01_SIZE.val(1)
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_02_EN::SET
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_02_ID.val(config.format.data_type())
+            + DSI_DSI_PKT_SEQ_0_LO_0::PKT_02_SIZE.val(2);
+        let active_line_hi = DSI_DSI_PKT_SEQ_0_HI_0::PKT_03_EN::SET
+            + DSI_DSI_PKT_SEQ_0_HI_0::PKT_03_ID.val(BLANKING_PACKET)
+            + DSI_DSI_PKT_SEQ_0_HI_0::PKT_03_SIZE.val(3);
+
+        dsi.DSI_DSI_PKT_SEQ_0_LO_0.set(vsync_line.value);
+        dsi.DSI_DSI_PKT_SEQ_0_HI_0.set(0);
+        dsi.DSI_DSI_PKT_SEQ_1_LO_0.set(blank_line.value);
+        dsi.DSI_DSI_PKT_SEQ_1_HI_0.set(0);
+        dsi.DSI_DSI_PKT_SEQ_2_LO_0.set(blank_line.value);
+        dsi.DSI_DSI_PKT_SEQ_2_HI_0.set(0);
+        dsi.DSI_DSI_PKT_SEQ_3_LO_0.set(active_line_lo.value);
+        dsi.DSI_DSI_PKT_SEQ_3_HI_0.set(active_line_hi.value);
+        dsi.DSI_DSI_PKT_SEQ_4_LO_0.set(blank_line.value);
+        dsi.DSI_DSI_PKT_SEQ_4_HI_0.set(0);
+        dsi.DSI_DSI_PKT_SEQ_5_LO_0.set(active_line_lo.value);
+        dsi.DSI_DSI_PKT_SEQ_5_HI_0.set(active_line_hi.value);
+
+        // Program the packet lengths referenced by the sequence.
+        dsi.DSI_DSI_PKT_LEN_0_1_0.write(
+            DSI_DSI_PKT_LEN_0_1_0::LENGTH_0.val(0)
+                + DSI_DSI_PKT_LEN_0_1_0::LENGTH_1.val(h_back_porch),
+        );
+        dsi.DSI_DSI_PKT_LEN_2_3_0.write(
+            DSI_DSI_PKT_LEN_2_3_0::LENGTH_2.val(h_active)
+                + DSI_DSI_PKT_LEN_2_3_0::LENGTH_3.val(h_front_porch),
+        );
+        dsi.DSI_DSI_PKT_LEN_4_5_0.set(0);
+        dsi.DSI_DSI_PKT_LEN_6_7_0.set(0);
+
+        dsi.DSI_DSI_SOL_DELAY_0
+            .write(DSI_DSI_SOL_DELAY_0::SOL_DELAY.val(bpp / config.lanes.count()));
+        dsi.DSI_DSI_MAX_THRESHOLD_0
+            .write(DSI_DSI_MAX_THRESHOLD_0::MAX_THRESHOLD.val(0x40));
+
+        let source = match config.source {
+            VideoSource::DisplayA => DSI_DSI_CONTROL_0::DSI_VID_SOURCE::Display0,
+            VideoSource::DisplayB => DSI_DSI_CONTROL_0::DSI_VID_SOURCE::Display1,
+        };
+
+        dsi.DSI_HOST_DSI_CONTROL_0.modify(
+            DSI_HOST_DSI_CONTROL_0::DSI_HIGH_SPEED_TRANS::High
+                + DSI_HOST_DSI_CONTROL_0::PKT_WR_FIFO_SEL::Host,
+        );
+        dsi.DSI_DSI_CONTROL_0.write(
+            DSI_DSI_CONTROL_0::DSI_HS_CLK_CTRL::Continuos
+                + DSI_DSI_CONTROL_0::DSI_VIRTUAL_CHANNEL.val(config.virtual_channel as u32)
+                + config.format.get_field_value()
+                + DSI_DSI_CONTROL_0::VID_TX_TRIG_SRC::Sol
+                + config.lanes.get_field_value()
+                + source
+                + DSI_DSI_CONTROL_0::DSI_VID_ENABLE::SET,
+        );
+
+        // Power the interface back up.
+        dsi.DSI_DSI_POWER_CONTROL_0
+            .write(DSI_DSI_POWER_CONTROL_0::LEG_DSI_ENABLE::SET);
+    }
+}
@@ -8,18 +8,82 @@
 //! The Flow Controller provides the sequencing of hardware-controlled
 //! CPU power states for the main CPU complex and the BPMP.
 
-use tock_registers::{interfaces::*, registers::ReadWrite};
+use tock_registers::{fields::FieldValue, interfaces::*, registers::ReadWrite};
 
 use crate::car;
-use crate::memory_map::EXCEPTION_VECTORS;
+use crate::memory_map::EVP_COP_RESET_VECTOR;
 use crate::timer::usleep;
 
 pub use registers::*;
 
 mod registers;
 
-/// Powers up the given CPU.
-pub fn power_cpu(cpu: u32) {
+/// The halt modes of the Flow Controller for the CPU cores.
+///
+/// These control under which conditions a CPU is halted when it enters
+/// a halt state and what wakes it up again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HaltMode {
+    /// The CPU is not halted.
+    None,
+    /// The CPU keeps running and an interrupt is raised.
+    RunAndInterrupt,
+    /// The CPU is halted until an event occurs.
+    WaitEvent,
+    /// The CPU is halted until an event occurs and an interrupt is raised.
+    WaitEventAndInterrupt,
+    /// The CPU is stopped until an IRQ occurs, the mode used for WFI-based halting.
+    StopUntilIrq,
+    /// The CPU is stopped until an IRQ occurs and an interrupt is raised.
+    StopUntilIrqAndInterrupt,
+    /// The CPU is stopped until an event or an IRQ occurs.
+    StopUntilEventAndIrq,
+}
+
+impl HaltMode {
+    fn get_field_value(self) -> FieldValue<u32, FLOW_CTLR_HALT_CPU_EVENTS_0::Register> {
+        match self {
+            HaltMode::None => FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeNone,
+            HaltMode::RunAndInterrupt => FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeRunAndInt,
+            HaltMode::WaitEvent => FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeWaitevent,
+            HaltMode::WaitEventAndInterrupt => {
+                FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeWaiteventAndInt
+            }
+            HaltMode::StopUntilIrq => FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeStopUntilIrq,
+            HaltMode::StopUntilIrqAndInterrupt => {
+                FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeStopUntilIrqAndInt
+            }
+            HaltMode::StopUntilEventAndIrq => {
+                FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeStopUntilEventAndIrq
+            }
+        }
+    }
+}
+
+fn halt_cpu_events(cpu: u32) -> &'static ReadWrite<u32, FLOW_CTLR_HALT_CPU_EVENTS_0::Register> {
+    let flow = unsafe { &*REGISTERS };
+
+    match cpu {
+        0 => &flow.FLOW_CTLR_HALT_CPU0_EVENTS_0,
+        1 => &flow.FLOW_CTLR_HALT_CPU1_EVENTS_0,
+        2 => &flow.FLOW_CTLR_HALT_CPU2_EVENTS_0,
+        3 => &flow.FLOW_CTLR_HALT_CPU3_EVENTS_0,
+        _ => panic!("Invalid CPU given!"),
+    }
+}
+
+/// Configures the halt mode for the given CPU.
+pub fn set_cpu_halt_mode(cpu: u32, mode: HaltMode) {
+    let halt_events = halt_cpu_events(cpu);
+
+    halt_events.modify(mode.get_field_value());
+
+    // Dummy read.
+    halt_events.get();
+}
+
+/// Powers up the given CPU and configures it for the given halt mode.
+pub fn power_cpu(cpu: u32, mode: HaltMode) {
     let flow = unsafe { &*REGISTERS };
 
     match cpu {
@@ -31,11 +95,9 @@ pub fn power_cpu(cpu: u32) {
             // Dummy read.
             flow.FLOW_CTLR_CPU0_CSR_0.get();
 
-            // Put CPU0 in WaitEvent state and resume on SYSCLK cycle ticks.
-            flow.FLOW_CTLR_HALT_CPU0_EVENTS_0.modify(
-                FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeWaitevent
-                    + FLOW_CTLR_HALT_CPU_EVENTS_0::SCLK::SET,
-            );
+            // Put CPU0 in the given halt mode and resume on SYSCLK cycle ticks.
+            flow.FLOW_CTLR_HALT_CPU0_EVENTS_0
+                .modify(mode.get_field_value() + FLOW_CTLR_HALT_CPU_EVENTS_0::SCLK::SET);
 
             // Dummy read.
             flow.FLOW_CTLR_HALT_CPU0_EVENTS_0.get();
@@ -48,11 +110,9 @@ pub fn power_cpu(cpu: u32) {
             // Dummy read.
             flow.FLOW_CTLR_CPU1_CSR_0.get();
 
-            // Put CPU1 in WaitEvent state and resume on SYSCLK cycle ticks.
-            flow.FLOW_CTLR_HALT_CPU1_EVENTS_0.modify(
-                FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeWaitevent
-                    + FLOW_CTLR_HALT_CPU_EVENTS_0::SCLK::SET,
-            );
+            // Put CPU1 in the given halt mode and resume on SYSCLK cycle ticks.
+            flow.FLOW_CTLR_HALT_CPU1_EVENTS_0
+                .modify(mode.get_field_value() + FLOW_CTLR_HALT_CPU_EVENTS_0::SCLK::SET);
 
             // Dummy read.
             flow.FLOW_CTLR_HALT_CPU1_EVENTS_0.get();
@@ -65,11 +125,9 @@ pub fn power_cpu(cpu: u32) {
             // Dummy read.
             flow.FLOW_CTLR_CPU2_CSR_0.get();
 
-            // Put CPU2 in WaitEvent state and resume on SYSCLK cycle ticks.
-            flow.FLOW_CTLR_HALT_CPU2_EVENTS_0.modify(
-                FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeWaitevent
-                    + FLOW_CTLR_HALT_CPU_EVENTS_0::SCLK::SET,
-            );
+            // Put CPU2 in the given halt mode and resume on SYSCLK cycle ticks.
+            flow.FLOW_CTLR_HALT_CPU2_EVENTS_0
+                .modify(mode.get_field_value() + FLOW_CTLR_HALT_CPU_EVENTS_0::SCLK::SET);
 
             // Dummy read.
             flow.FLOW_CTLR_HALT_CPU2_EVENTS_0.get();
@@ -82,11 +140,9 @@ pub fn power_cpu(cpu: u32) {
             // Dummy read.
             flow.FLOW_CTLR_CPU3_CSR_0.get();
 
-            // Put CPU3 in WaitEvent state and resume on SYSCLK cycle ticks.
-            flow.FLOW_CTLR_HALT_CPU3_EVENTS_0.modify(
-                FLOW_CTLR_HALT_CPU_EVENTS_0::MODE::FlowModeWaitevent
-                    + FLOW_CTLR_HALT_CPU_EVENTS_0::SCLK::SET,
-            );
+            // Put CPU3 in the given halt mode and resume on SYSCLK cycle ticks.
+            flow.FLOW_CTLR_HALT_CPU3_EVENTS_0
+                .modify(mode.get_field_value() + FLOW_CTLR_HALT_CPU_EVENTS_0::SCLK::SET);
 
             // Dummy read.
             flow.FLOW_CTLR_HALT_CPU3_EVENTS_0.get();
@@ -204,6 +260,43 @@ pub fn deplete_cpu(cpu: u32) {
     }
 }
 
+fn cc4_core_ctrl(core: u32) -> &'static ReadWrite<u32, FLOW_CTLR_CC4_CORE_CTRL_0::Register> {
+    let flow = unsafe { &*REGISTERS };
+
+    match core {
+        0 => &flow.FLOW_CTLR_CC4_CORE0_CTRL_0,
+        1 => &flow.FLOW_CTLR_CC4_CORE1_CTRL_0,
+        2 => &flow.FLOW_CTLR_CC4_CORE2_CTRL_0,
+        3 => &flow.FLOW_CTLR_CC4_CORE3_CTRL_0,
+        _ => panic!("Invalid CPU given!"),
+    }
+}
+
+/// Configures CC4 Retention for the given CPU core.
+///
+/// When enabled, the core may enter CC4 Retention once its idle countdown,
+/// initialized to `idle_timer`, expires. Otherwise, the countdown timer is
+/// reset like [`deplete_cpu`] does.
+///
+/// NOTE: The retention voltage itself is not controlled by the Flow Controller,
+/// but by the CPU rail regulator.
+pub fn configure_cc4(core: u32, idle_timer: u32, enable: bool) {
+    let cc4_ctrl = cc4_core_ctrl(core);
+
+    if enable {
+        cc4_ctrl.write(
+            FLOW_CTLR_CC4_CORE_CTRL_0::CORE_IDLE_TIMER.val(idle_timer)
+                + FLOW_CTLR_CC4_CORE_CTRL_0::TIMER_COUNTDOWN_VALID::SET
+                + FLOW_CTLR_CC4_CORE_CTRL_0::CORE_RET_ENABLE::SET,
+        );
+    } else {
+        cc4_ctrl.set(0);
+    }
+
+    // Dummy read.
+    cc4_ctrl.get();
+}
+
 /// Informs the BPMP that the cluster power-up sequence has completed.
 pub fn lock_active_cluster() {
     let flow = unsafe { &*REGISTERS };
@@ -226,11 +319,11 @@ pub fn power_bpmp(entrypoint: u32) {
         .modify(FLOW_CTLR_HALT_COP_EVENTS_0::MODE::FlowModeWaitevent);
 
     // Assert BPMP reset.
-    car.CLK_RST_CONTROLLER_RST_DEV_L_SET_0.set(1 << 1);
+    car.CLK_RST_CONTROLLER_RST_DEV_L_SET_0
+        .set(1 << car::CLK_L_BPMP);
 
     // Set reset address (stored in PMC_SCRATCH39).
-    let bpmp_exception_reset_vector =
-        unsafe { &*((EXCEPTION_VECTORS + 0x200) as *const ReadWrite<u32>) };
+    let bpmp_exception_reset_vector = unsafe { &*(EVP_COP_RESET_VECTOR as *const ReadWrite<u32>) };
     bpmp_exception_reset_vector.set(entrypoint);
 
     while bpmp_exception_reset_vector.get() != entrypoint {
@@ -241,7 +334,8 @@ pub fn power_bpmp(entrypoint: u32) {
     usleep(2);
 
     // De-assert BPMP reset.
-    car.CLK_RST_CONTROLLER_RST_DEV_L_SET_0.set(1 << 1);
+    car.CLK_RST_CONTROLLER_RST_DEV_L_CLR_0
+        .set(1 << car::CLK_L_BPMP);
 
     // Un-halt the BPMP.
     flow.FLOW_CTLR_HALT_COP_EVENTS_0.set(0);
@@ -257,11 +351,11 @@ pub fn deplete_bpmp() {
         .modify(FLOW_CTLR_HALT_COP_EVENTS_0::MODE::FlowModeWaitevent);
 
     // Assert BPMP reset.
-    car.CLK_RST_CONTROLLER_RST_DEV_L_SET_0.set(1 << 1);
+    car.CLK_RST_CONTROLLER_RST_DEV_L_SET_0
+        .set(1 << car::CLK_L_BPMP);
 
     // Clear reset address.
-    let bpmp_exception_reset_vector =
-        unsafe { &*((EXCEPTION_VECTORS + 0x200) as *const ReadWrite<u32>) };
+    let bpmp_exception_reset_vector = unsafe { &*(EVP_COP_RESET_VECTOR as *const ReadWrite<u32>) };
     bpmp_exception_reset_vector.set(0);
 
     while bpmp_exception_reset_vector.get() != 0 {
@@ -15,7 +15,7 @@ register_bitfields! {
 
     /// Bitfields of the `FLOW_CTLR_HALT_CPU_<x>_EVENTS_0` register.
     pub FLOW_CTLR_HALT_CPU_EVENTS_0 [
-        MODE OFFSET(29) NUMBITS(2) [
+        MODE OFFSET(29) NUMBITS(3) [
             FlowModeNone = 0,
             FlowModeRunAndInt = 1,
             FlowModeWaitevent = 2,
@@ -73,7 +73,7 @@ register_bitfields! {
 
     /// Bitfields of the `FLOW_CTLR_HALT_COP_EVENTS_0` register.
     pub FLOW_CTLR_HALT_COP_EVENTS_0 [
-        MODE OFFSET(29) NUMBITS(2) [
+        MODE OFFSET(29) NUMBITS(3) [
             FlowModeNone = 0,
             FlowModeRunAndInt = 1,
             FlowModeWaitevent = 2,
@@ -8,7 +8,25 @@ use tock_registers::interfaces::*;
 
 mod registers;
 
+/// Errors that may occur when burning fuses through [`write_verified`].
+///
+/// [`write_verified`]: fn.write_verified.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FuseError {
+    /// The address is outside of the hardware array.
+    OutOfRange,
+    /// The array is not powered or programming was disabled.
+    ProgrammingDisabled,
+    /// The bits to be burnt didn't read back as set after programming.
+    VerifyFailed,
+}
+
 /// Initializes the FUSE driver.
+///
+/// NOTE: This disables FUSE programming, which cannot be re-enabled until the next
+/// SoC reset. Consequently, [`write`] will refuse to burn fuses afterwards.
+///
+/// [`write`]: fn.write.html
 pub fn init() {
     let car = unsafe { &*car::REGISTERS };
 
@@ -101,15 +119,74 @@ pub fn read(address: u32) -> Result<u32, ()> {
     Ok(controller.fuse.FUSE_RDATA.get())
 }
 
-/// Writes a FUSE in the hardware array.
-pub fn write(address: u32, value: u32) -> Result<(), ()> {
+/// Reads the whole FUSE hardware array into `buffer`.
+///
+/// The array is sensed into the shadow cache once and then read out word by word.
+/// As the hardware doesn't support auto-incrementing the address, every word is
+/// still read with an individual command, but only a single idle wait is needed
+/// per word as opposed to repeatedly calling [`read`].
+///
+/// [`read`]: fn.read.html
+pub fn read_all(buffer: &mut [u32; 192]) -> Result<(), ()> {
     let controller = unsafe { &*REGISTERS };
 
+    // Sense the hardware array into the shadow cache, which leaves the controller idle.
+    sense();
+
+    for (address, word) in buffer.iter_mut().enumerate() {
+        // Program the target address.
+        controller.fuse.FUSE_ADDR.set(address as u32);
+
+        // Enable read operation in control register.
+        let mut control_value = controller.fuse.FUSE_CTRL.get();
+        control_value &= !0x3; // Mask the value.
+        control_value |= 0x1; // Set READ command.
+        controller.fuse.FUSE_CTRL.set(control_value);
+
+        // Wait for idle state, which is also a prerequisite for the next read.
+        wait_idle();
+
+        *word = controller.fuse.FUSE_RDATA.get();
+    }
+
+    Ok(())
+}
+
+/// Indicates whether power to the FUSE hardware array is currently enabled.
+pub fn is_power_enabled() -> bool {
+    let pmc = unsafe { &*pmc::REGISTERS };
+
+    // Check PMC_FUSE_CTRL_PS18_LATCH_SET.
+    (pmc.APBDEV_PMC_FUSE_CONTROL_0.get() & 0x100) != 0
+}
+
+/// Writes a FUSE in the hardware array.
+///
+/// Power to the hardware array must be enabled through [`enable_power`] before
+/// calling this function, otherwise an error is returned. An error is also
+/// returned when programming was disabled, e.g. through [`init`].
+///
+/// # Safety
+///
+/// Burning fuses is irreversible and writing the wrong value to the wrong fuse
+/// may permanently brick the device. The caller must ensure that `address` and
+/// `value` are exactly what is intended to be burnt.
+///
+/// [`enable_power`]: fn.enable_power.html
+/// [`init`]: fn.init.html
+pub unsafe fn write(address: u32, value: u32) -> Result<(), ()> {
+    let controller = &*REGISTERS;
+
     // Check if address is in a valid range.
     if address >= 192 {
         return Err(());
     }
 
+    // Refuse to proceed unless the array is powered and programming is allowed.
+    if !is_power_enabled() || controller.fuse.FUSE_DISABLEREGPROGRAM.get() != 0 {
+        return Err(());
+    }
+
     // Wait for idle state.
     wait_idle();
 
@@ -129,6 +206,49 @@ pub fn write(address: u32, value: u32) -> Result<(), ()> {
     Ok(())
 }
 
+/// Writes a FUSE in the hardware array and verifies that it was burnt correctly.
+///
+/// After programming, the array is sensed again and the word is read back to
+/// confirm that all bits set in `value` actually took. Bits which were already
+/// burnt before are not an error, as fuses can't be cleared.
+///
+/// See [`write`] for the prerequisites of programming.
+///
+/// # Safety
+///
+/// Burning fuses is irreversible and writing the wrong value to the wrong fuse
+/// may permanently brick the device. The caller must ensure that `address` and
+/// `value` are exactly what is intended to be burnt.
+///
+/// [`write`]: fn.write.html
+pub unsafe fn write_verified(address: u32, value: u32) -> Result<(), FuseError> {
+    let controller = &*REGISTERS;
+
+    // Check if address is in a valid range.
+    if address >= 192 {
+        return Err(FuseError::OutOfRange);
+    }
+
+    // Refuse to proceed unless the array is powered and programming is allowed.
+    if !is_power_enabled() || controller.fuse.FUSE_DISABLEREGPROGRAM.get() != 0 {
+        return Err(FuseError::ProgrammingDisabled);
+    }
+
+    // Burn the fuses.
+    write(address, value).map_err(|_| FuseError::ProgrammingDisabled)?;
+
+    // Sense the array so that the read back reflects the newly burnt fuses.
+    sense();
+
+    // Confirm that all requested bits are set.
+    let burnt = read(address).map_err(|_| FuseError::OutOfRange)?;
+    if burnt & value != value {
+        return Err(FuseError::VerifyFailed);
+    }
+
+    Ok(())
+}
+
 /// Senses the FUSE hardware array into shadow cache.
 pub fn sense() {
     let controller = unsafe { &*REGISTERS };
@@ -145,3 +265,59 @@ pub fn sense() {
     // Wait for idle state.
     wait_idle();
 }
+
+fn common_chip_registers() -> &'static chip_common::Registers {
+    let controller = unsafe { &*REGISTERS };
+
+    unsafe { &controller.chip.common }
+}
+
+/// Gets the SKU info of the chip from the shadow cache.
+pub fn get_sku_info() -> u32 {
+    common_chip_registers().FUSE_SKU_INFO.get() & 0xFF
+}
+
+/// Gets the unique ID of the chip from the shadow cache.
+///
+/// The ID is derived from the manufacturing information burnt into the fuses,
+/// namely the fab code, the lot code, the wafer ID and the die coordinates on
+/// the wafer.
+pub fn get_chip_id() -> u64 {
+    let chip = common_chip_registers();
+
+    let y_coordinate = (chip.FUSE_OPT_Y_COORDINATE.get() & 0x1FF) as u64;
+    let x_coordinate = (chip.FUSE_OPT_X_COORDINATE.get() & 0x1FF) as u64;
+    let wafer_id = (chip.FUSE_OPT_WAFER_ID.get() & 0x3F) as u64;
+    let lot_code = chip.FUSE_OPT_LOT_CODE_0.get();
+    let fab_code = (chip.FUSE_OPT_FAB_CODE.get() & 0x3F) as u64;
+
+    // Decode the lot code which is stored as five base-36 digits.
+    let mut derived_lot_code = 0;
+    for i in 0..5 {
+        derived_lot_code = (derived_lot_code * 36) + ((lot_code >> (24 - 6 * i)) & 0x3F) as u64;
+    }
+    derived_lot_code &= 0x03FF_FFFF;
+
+    y_coordinate
+        | (x_coordinate << 9)
+        | (wafer_id << 18)
+        | (derived_lot_code << 24)
+        | (fab_code << 50)
+}
+
+/// Gets the version of the IPATCH bootrom patches from the shadow cache.
+pub fn get_bootrom_patch_version() -> u32 {
+    common_chip_registers().FUSE_SOC_SPEEDO_1_CALIB.get()
+}
+
+/// Reads one of the ODM reserved fuses from the shadow cache.
+///
+/// Returns an error if `index` is not within the range of available fuses (0-7).
+pub fn read_odm(index: usize) -> Result<u32, ()> {
+    let chip = common_chip_registers();
+
+    chip.FUSE_RESERVED_ODM_0
+        .get(index)
+        .map(|fuse| fuse.get())
+        .ok_or(())
+}
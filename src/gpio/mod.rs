@@ -89,6 +89,54 @@ pub enum Port {
     FF,
 }
 
+/// All GPIO ports, in the order of their controller registers.
+const PORTS: [Port; 32] = [
+    Port::A,
+    Port::B,
+    Port::C,
+    Port::D,
+    Port::E,
+    Port::F,
+    Port::G,
+    Port::H,
+    Port::I,
+    Port::J,
+    Port::K,
+    Port::L,
+    Port::M,
+    Port::N,
+    Port::O,
+    Port::P,
+    Port::Q,
+    Port::R,
+    Port::S,
+    Port::T,
+    Port::U,
+    Port::V,
+    Port::W,
+    Port::X,
+    Port::Y,
+    Port::Z,
+    Port::AA,
+    Port::BB,
+    Port::CC,
+    Port::DD,
+    Port::EE,
+    Port::FF,
+];
+
+/// All GPIO pins of a port, in the order of their register bits.
+const PINS: [Pin; 8] = [
+    Pin::P0,
+    Pin::P1,
+    Pin::P2,
+    Pin::P3,
+    Pin::P4,
+    Pin::P5,
+    Pin::P6,
+    Pin::P7,
+];
+
 /// The GPIO pins that are provided for each port.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Pin {
@@ -161,6 +209,20 @@ pub enum InterruptType {
     LowLevel,
 }
 
+impl InterruptType {
+    /// Gets the `GPIO_INT_LEVEL` bits for pin 0, consisting of the level (bit 0),
+    /// edge (bit 8) and delta (bit 16) selectors.
+    fn get_bits(self) -> u32 {
+        match self {
+            InterruptType::RisingEdge => 0x000101,
+            InterruptType::FallingEdge => 0x000100,
+            InterruptType::BothEdge => 0x010100,
+            InterruptType::HighLevel => 0x000001,
+            InterruptType::LowLevel => 0x000000,
+        }
+    }
+}
+
 /// Representation of a Tegra X1 GPIO.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Gpio {
@@ -325,6 +387,36 @@ impl Gpio {
         self.read_gpio::<Level>(in_reg)
     }
 
+    /// Reads the GPIO level the pin is driven to as an output.
+    ///
+    /// Unlike [`Gpio::read`], this reflects the last level written to the
+    /// pin and not the electrical state of the pad, so both may disagree
+    /// when the pin is fighting an external driver.
+    pub fn read_output(&self) -> Level {
+        let controller = unsafe { &*CONTROLLER };
+
+        // Figure out the register to read from.
+        let out_reg = &controller.banks[self.get_bank()].GPIO_OUT[self.get_port()];
+
+        // Read the register and return the result.
+        self.read_gpio::<Level>(out_reg)
+    }
+
+    /// Reads the input levels of all pins of a port at once.
+    ///
+    /// Bit `n` of the result holds the level of pin `n`, with a set bit denoting
+    /// a high level. As all pins are sampled through a single register read, the
+    /// result is consistent across the whole port.
+    pub fn read_port(port: Port) -> u8 {
+        let controller = unsafe { &*CONTROLLER };
+
+        // Figure out the register to read from.
+        let in_reg = &controller.banks[port as usize >> 2].GPIO_IN[port as usize & 3];
+
+        // Read the register and return the pin levels.
+        in_reg.get() as u8
+    }
+
     /// Writes the given GPIO level to the pin.
     pub fn write(&self, level: Level) {
         let controller = unsafe { &*CONTROLLER };
@@ -403,7 +495,32 @@ impl Gpio {
 
         // Configure the interrupt.
         value &= !(0x010101 << self.pin as u32);
-        value |= (interrupt as u32) << self.pin as u32;
+        value |= interrupt.get_bits() << self.pin as u32;
+
+        // Write the new value to the register.
+        int_lvl_reg.set(value);
+
+        // Dummy read.
+        int_lvl_reg.get();
+    }
+
+    /// Configures the interrupt types of multiple pins of a port at once.
+    ///
+    /// The register value is built for all given pins and written in a single access,
+    /// without reading the previous configuration. Consequently, pins of the port that
+    /// are not listed in `interrupts` are configured for [`InterruptType::LowLevel`].
+    ///
+    /// [`InterruptType::LowLevel`]: enum.InterruptType.html#variant.LowLevel
+    pub fn set_interrupt_for_port(port: Port, interrupts: &[(Pin, InterruptType)]) {
+        let controller = unsafe { &*CONTROLLER };
+
+        // Figure out the register to write to.
+        let int_lvl_reg = &controller.banks[port as usize >> 2].GPIO_INT_LEVEL[port as usize & 3];
+
+        // Build the configuration for all pins.
+        let value = interrupts.iter().fold(0, |value, &(pin, interrupt)| {
+            (value & !(0x010101 << pin as u32)) | interrupt.get_bits() << pin as u32
+        });
 
         // Write the new value to the register.
         int_lvl_reg.set(value);
@@ -412,3 +529,60 @@ impl Gpio {
         int_lvl_reg.get();
     }
 }
+
+/// A front-end for dispatching the interrupts of all GPIOs.
+///
+/// Instead of checking the status registers of every bank by hand, an interrupt
+/// handler can use this to find out which GPIOs fired.
+///
+/// ```no_run
+/// use libtegra::gpio::InterruptController;
+///
+/// InterruptController::new().handle(|gpio| {
+///     // React to the interrupt of `gpio`.
+/// });
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InterruptController;
+
+impl InterruptController {
+    /// Creates a new interrupt front-end.
+    pub const fn new() -> Self {
+        InterruptController
+    }
+
+    /// Scans the `GPIO_INT_STATUS` registers of all ports and yields the GPIOs
+    /// with a pending interrupt.
+    ///
+    /// Only GPIOs that have interrupts enabled are considered. The interrupts
+    /// are not cleared by this.
+    pub fn pending_pins(&self) -> impl Iterator<Item = Gpio> {
+        let controller = unsafe { &*CONTROLLER };
+
+        PORTS.iter().enumerate().flat_map(move |(index, &port)| {
+            let bank = &controller.banks[index >> 2];
+
+            // Mask out the pins which don't have interrupts enabled.
+            let pending =
+                bank.GPIO_INT_STATUS[index & 3].get() & bank.GPIO_INT_ENABLE[index & 3].get();
+
+            PINS.iter()
+                .filter(move |&&pin| pending & (1 << pin as u32) != 0)
+                .map(move |&pin| Gpio { port, pin })
+        })
+    }
+
+    /// Dispatches all pending GPIO interrupts to `handler`.
+    ///
+    /// The interrupt of each GPIO is cleared right before it is passed to the
+    /// handler, so that an edge which occurs while handling it is not lost.
+    pub fn handle<F>(&self, mut handler: F)
+    where
+        F: FnMut(Gpio),
+    {
+        for gpio in self.pending_pins() {
+            gpio.clear_interrupts();
+            handler(gpio);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use embedded_hal::blocking::delay;
+use embedded_hal_1::delay::DelayNs;
 
 use crate::timer;
 
@@ -46,3 +47,23 @@ impl delay::DelayUs<u8> for Delay {
         timer::usleep(duration as u32);
     }
 }
+
+impl DelayNs for Delay {
+    fn delay_ns(&mut self, ns: u32) {
+        // The ARM Generic Timer provides sub-microsecond resolution.
+        #[cfg(target_arch = "aarch64")]
+        timer::arch::busy_wait_ns(ns as u64);
+
+        // Otherwise, round up to the next full microsecond.
+        #[cfg(not(target_arch = "aarch64"))]
+        timer::usleep(ns.div_ceil(1000));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        timer::usleep(us);
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        timer::msleep(ms);
+    }
+}
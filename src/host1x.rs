@@ -0,0 +1,86 @@
+//! Abstractions over the Host1X synchronization points.
+//!
+//! # Description
+//!
+//! Host1X clients like the [VIC] and the [TSEC] signal the progress of their work
+//! through syncpoints. A syncpoint is a 32-bit counter which is incremented by the
+//! hardware, e.g. through the `INCR_SYNCPT` method of a client, once a condition such
+//! as the completion of an operation is met.
+//!
+//! The CPU can observe a syncpoint through [`read`] and wait for a client to finish
+//! its work through [`wait`]. Syncpoints may also be incremented from software with
+//! [`increment`], which is useful for releasing waiters manually.
+//!
+//! ```no_run
+//! use libtegra::host1x;
+//!
+//! // Expect the next increment of syncpoint 8 within a millisecond.
+//! let threshold = host1x::read(8).wrapping_add(1);
+//! host1x::wait(8, threshold, 1_000).unwrap();
+//! ```
+//!
+//! [VIC]: ../vic/index.html
+//! [TSEC]: ../tsec/index.html
+//! [`read`]: fn.read.html
+//! [`wait`]: fn.wait.html
+//! [`increment`]: fn.increment.html
+
+use tock_registers::{interfaces::*, registers::ReadWrite};
+
+use crate::{
+    memory_map::HOST1X,
+    timer::{get_microseconds, has_elapsed},
+};
+
+/// The number of syncpoints that are provided by Host1X.
+pub const SYNCPOINT_COUNT: u32 = 192;
+
+/// Base address of the Host1X synchronization registers.
+const SYNC_BASE: u32 = HOST1X + 0x2100;
+
+/// The first of the registers that increment syncpoints from the CPU, one bit per syncpoint.
+const SYNC_SYNCPT_CPU_INCR: u32 = SYNC_BASE + 0xF00;
+/// The first of the registers that hold the current syncpoint values.
+const SYNC_SYNCPT: u32 = SYNC_BASE + 0xF80;
+
+fn syncpoint(id: u32) -> &'static ReadWrite<u32> {
+    assert!(id < SYNCPOINT_COUNT, "Invalid syncpoint given!");
+
+    unsafe { &*((SYNC_SYNCPT + id * 4) as *const ReadWrite<u32>) }
+}
+
+/// Reads the current value of the syncpoint `id`.
+pub fn read(id: u32) -> u32 {
+    syncpoint(id).get()
+}
+
+/// Increments the syncpoint `id` by one from the CPU.
+pub fn increment(id: u32) {
+    assert!(id < SYNCPOINT_COUNT, "Invalid syncpoint given!");
+
+    let incr = unsafe { &*((SYNC_SYNCPT_CPU_INCR + (id >> 5) * 4) as *const ReadWrite<u32>) };
+    incr.set(1 << (id & 0x1F));
+}
+
+/// Indicates whether the syncpoint `id` has reached `threshold`.
+///
+/// The comparison accounts for the counter wrapping around.
+pub fn is_expired(id: u32, threshold: u32) -> bool {
+    read(id).wrapping_sub(threshold) as i32 >= 0
+}
+
+/// Spins until the syncpoint `id` reaches `threshold` and returns its value.
+///
+/// An error is returned if the threshold isn't reached within `timeout` microseconds.
+pub fn wait(id: u32, threshold: u32, timeout: u32) -> Result<u32, ()> {
+    let start = get_microseconds();
+    loop {
+        if is_expired(id, threshold) {
+            return Ok(read(id));
+        }
+
+        if has_elapsed(start, get_microseconds(), timeout) {
+            return Err(());
+        }
+    }
+}
@@ -1,10 +1,23 @@
 use core::convert::TryInto;
 
+use crate::gpio::{Direction, Gpio, Level, Mode};
 use crate::i2c::registers::*;
-use crate::{car::Clock, timer};
+use crate::{car::Clock, tegra_gpio, timer};
 
 use tock_registers::interfaces::*;
 
+/// The maximum amount of clock pulses issued to free a stuck bus.
+const BUS_RECOVERY_PULSES: usize = 9;
+
+/// Half of the period of a 100KHz I²C clock, in microseconds.
+const BUS_RECOVERY_HALF_PERIOD: u32 = 5;
+
+/// The first 7-bit address that is not reserved by the I²C specification.
+const I2C_FIRST_ADDRESS: u8 = 0x08;
+
+/// The last 7-bit address that is not reserved by the I²C specification.
+const I2C_LAST_ADDRESS: u8 = 0x77;
+
 fn bytes_to_word(buf: &[u8]) -> u32 {
     if buf.len() == 4 {
         u32::from_le_bytes(buf.try_into().unwrap())
@@ -59,6 +72,8 @@ pub struct I2c {
     clock: &'static Clock,
     // A pointer to the I2C register block in memory.
     registers: *const Registers,
+    // The GPIOs of the SCL and SDA pads, if they can be bit-banged.
+    recovery_pins: Option<(Gpio, Gpio)>,
 }
 
 // Definitions of known I2C controllers.
@@ -67,36 +82,42 @@ impl I2c {
     pub const C1: Self = I2c {
         clock: &Clock::I2C_1,
         registers: I2C_1_REGISTERS,
+        recovery_pins: Some((tegra_gpio!(J, 1), tegra_gpio!(J, 0))),
     };
 
     /// Representation of the I2C2 controller.
     pub const C2: Self = I2c {
         clock: &Clock::I2C_2,
         registers: I2C_2_REGISTERS,
+        recovery_pins: Some((tegra_gpio!(J, 2), tegra_gpio!(J, 3))),
     };
 
     /// Representation of the I2C3 controller.
     pub const C3: Self = I2c {
         clock: &Clock::I2C_3,
         registers: I2C_3_REGISTERS,
+        recovery_pins: Some((tegra_gpio!(F, 0), tegra_gpio!(F, 1))),
     };
 
     /// Representation of the I2C4 controller.
     pub const C4: Self = I2c {
         clock: &Clock::I2C_4,
         registers: I2C_4_REGISTERS,
+        recovery_pins: None,
     };
 
     /// Representation of the I2C5 controller.
     pub const C5: Self = I2c {
         clock: &Clock::I2C_5,
         registers: I2C_5_REGISTERS,
+        recovery_pins: Some((tegra_gpio!(Y, 3), tegra_gpio!(Y, 4))),
     };
 
     /// Representation of the I2C6 controller.
     pub const C6: Self = I2c {
         clock: &Clock::I2C_6,
         registers: I2C_6_REGISTERS,
+        recovery_pins: None,
     };
 }
 
@@ -311,6 +332,91 @@ impl I2c {
             .set(i2c.I2C_INTERRUPT_STATUS_REGISTER_0.get());
     }
 
+    /// Recovers the bus from a slave that holds the SDA line low.
+    ///
+    /// The SCL and SDA pads are temporarily switched from SFIO to GPIO mode to
+    /// issue up to nine clock pulses until the slave releases SDA, followed by
+    /// a STOP condition. Afterwards, the pads are handed back to the controller.
+    ///
+    /// An error is returned if the bus is still stuck after the recovery attempt
+    /// or for the I2C6 controller, whose pads cannot be driven as GPIOs.
+    pub fn recover_bus(&self) -> Result<(), Error> {
+        let (scl, sda) = self.recovery_pins.ok_or(Error::Generic)?;
+
+        // Emulate open-drain outputs by only ever driving the lines low.
+        let release = |gpio: &Gpio| {
+            gpio.set_direction(Direction::Input);
+            timer::usleep(BUS_RECOVERY_HALF_PERIOD);
+        };
+        let drive_low = |gpio: &Gpio| {
+            gpio.write(Level::Low);
+            gpio.set_direction(Direction::Output);
+            timer::usleep(BUS_RECOVERY_HALF_PERIOD);
+        };
+
+        // Take over the pads with both lines released.
+        for gpio in [scl, sda].iter() {
+            gpio.set_direction(Direction::Input);
+            gpio.set_mode(Mode::Gpio);
+        }
+        timer::usleep(BUS_RECOVERY_HALF_PERIOD);
+
+        // Clock out pulses until the slave releases SDA.
+        for _ in 0..BUS_RECOVERY_PULSES {
+            if sda.is_high() {
+                break;
+            }
+
+            drive_low(&scl);
+            release(&scl);
+        }
+
+        // Issue a STOP condition by releasing SDA while SCL is high.
+        drive_low(&scl);
+        drive_low(&sda);
+        release(&scl);
+        release(&sda);
+
+        let released = scl.is_high() && sda.is_high();
+
+        // Hand the pads back to the controller.
+        scl.set_mode(Mode::Sfio);
+        sda.set_mode(Mode::Sfio);
+
+        if released {
+            Ok(())
+        } else {
+            Err(Error::IoError)
+        }
+    }
+
+    /// Probes all non-reserved 7-bit addresses on the bus for devices that ACK.
+    ///
+    /// The addresses of all responding devices are stored into `devices` in ascending
+    /// order and the number of devices found is returned. Devices that don't fit into
+    /// the buffer anymore are not recorded.
+    ///
+    /// As Normal Mode transactions cannot be zero-length, each address is probed with
+    /// a single byte read, which does not alter the state of the register pointer
+    /// that most devices have.
+    pub fn scan(&self, devices: &mut [u8]) -> usize {
+        let mut found = 0;
+
+        for address in I2C_FIRST_ADDRESS..=I2C_LAST_ADDRESS {
+            if found == devices.len() {
+                break;
+            }
+
+            let mut byte = [0; 1];
+            if self.receive_normal(address as u32, &mut byte).is_ok() {
+                devices[found] = address;
+                found += 1;
+            }
+        }
+
+        found
+    }
+
     /// Writes a buffer of data to a device register over I²C.
     ///
     /// This method drives the I2C controller to operate in Normal Mode, using 7-bit
@@ -28,7 +28,14 @@
 //! added in the future) or 10-bit addressing transactions, one would have to
 //! implement the functionality themselves.
 //!
+//! # Bus recovery
+//!
+//! A slave which got stuck in the middle of a transfer may hold the SDA line low
+//! and thereby block the bus. [`I2c::recover_bus`] bit-bangs the standard recovery
+//! sequence on the pads of the controller to free it again.
+//!
 //! [`I2c`]: struct.I2c.html\
+//! [`I2c::recover_bus`]: struct.I2c.html#method.recover_bus
 //! [`Clock`]: ../car/struct.Clock.html
 
 mod controller;
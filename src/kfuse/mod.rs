@@ -32,6 +32,7 @@ mod registers;
 
 use crate::car::Clock;
 pub use crate::kfuse::registers::*;
+use crate::timer::{get_milliseconds, has_elapsed};
 
 use tock_registers::interfaces::*;
 
@@ -39,16 +40,26 @@ use tock_registers::interfaces::*;
 /// the KFUSE.
 pub const KFUSE_KEY_BUFFER_SIZE: usize = 576 >> 2;
 
+/// The time in milliseconds to wait for the KFUSE to finish initialization.
+const READY_TIMEOUT: u32 = 100;
+
 /// Waits until KFUSE is ready to be used.
 ///
+/// An error is returned if the KFUSE doesn't finish initialization within a
+/// bounded interval or if the CRC check of its data fails.
+///
 /// NOTE: This function expects the KFUSE [`Clock`] to be brought up before calling it.
 ///
 /// [`Clock`]: ../car/struct.Clock.html
 pub fn wait_until_ready() -> Result<(), ()> {
     let kfuse = unsafe { &*REGISTERS };
 
+    // Wait for KFUSE to finish initialization and verification of data.
+    let start = get_milliseconds();
     while !kfuse.KFUSE_STATE_0.is_set(KFUSE_STATE_0::DONE) {
-        // Wait for KFUSE to finish initialization and verification of data.
+        if has_elapsed(start, get_milliseconds(), READY_TIMEOUT) {
+            return Err(());
+        }
     }
 
     // Ensure that CRC passes.
@@ -2,6 +2,9 @@
 #![recursion_limit = "1024"]
 #![allow(clippy::result_unit_err)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[macro_use]
 extern crate enum_primitive;
 
@@ -21,12 +24,14 @@ pub mod fuse;
 pub mod gpio;
 #[cfg(feature = "hal")]
 pub mod hal;
+pub mod host1x;
 pub mod i2c;
 pub mod kfuse;
 pub mod mc;
 pub mod memory_map;
 pub mod pinmux;
 pub mod pmc;
+pub mod pmic;
 pub mod pwm;
 pub mod se;
 pub mod spi;
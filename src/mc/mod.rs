@@ -3,13 +3,194 @@
 //! See Chapter 18 of the Tegra X1 Technical Reference Manual
 //! for details.
 
-use crate::{car, timer::usleep};
+use crate::{
+    car,
+    memory_map::{EMC, MC},
+    timer::{get_microseconds, has_elapsed, usleep},
+};
 
 pub use registers::*;
 
 mod registers;
 
-use tock_registers::interfaces::*;
+use tock_registers::{interfaces::*, registers::ReadWrite};
+
+/// The lock bit in the `CFG0` register of a security carveout.
+const CARVEOUT_CFG0_LOCK: u32 = 1 << 0;
+
+/// The granularity of security carveout base addresses and sizes, in KiB.
+const CARVEOUT_GRANULARITY_KB: u32 = 128;
+
+/// Enumeration over the generalized security carveouts of the Memory Controller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CarveoutId {
+    Carveout1 = 1,
+    Carveout2 = 2,
+    Carveout3 = 3,
+    Carveout4 = 4,
+    Carveout5 = 5,
+}
+
+impl CarveoutId {
+    fn registers(self) -> &'static CarveoutRegisters {
+        let offset = MC_SECURITY_CARVEOUT1_OFFSET + (self as u32 - 1) * MC_SECURITY_CARVEOUT_STRIDE;
+        unsafe { &*((MC + offset) as *const CarveoutRegisters) }
+    }
+}
+
+/// The access configuration of a security carveout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClientAccess {
+    /// The `CFG0` configuration value without the lock bit.
+    ///
+    /// This holds the protection mode and the access levels of the carveout.
+    pub config: u32,
+    /// The client access bit masks for the `CA0`-`CA4` registers.
+    pub allowed: [u32; 5],
+    /// The bit masks for the `CFIA0`-`CFIA4` registers, which force accesses of the
+    /// corresponding clients to be treated as internal.
+    pub force_internal: [u32; 5],
+}
+
+impl ClientAccess {
+    /// An access configuration that does not grant access to any client.
+    pub const NONE: Self = ClientAccess {
+        config: 0,
+        allowed: [0; 5],
+        force_internal: [0; 5],
+    };
+}
+
+/// Configures a generalized security carveout of the Memory Controller.
+///
+/// `base` and `size_kb` must be aligned to 128KiB, otherwise an error is returned.
+///
+/// If `lock` is set, the carveout configuration cannot be changed anymore until
+/// the next reset.
+pub fn configure_carveout(
+    id: CarveoutId,
+    base: u64,
+    size_kb: u32,
+    clients: ClientAccess,
+    lock: bool,
+) -> Result<(), ()> {
+    let granularity = CARVEOUT_GRANULARITY_KB as u64 * 1024;
+    if base & (granularity - 1) != 0 || size_kb & (CARVEOUT_GRANULARITY_KB - 1) != 0 {
+        return Err(());
+    }
+
+    let carveout = id.registers();
+
+    carveout.BOM.set(base as u32);
+    carveout.BOM_HI.set((base >> 32) as u32);
+    carveout.SIZE_128KB.set(size_kb / CARVEOUT_GRANULARITY_KB);
+    for (register, &value) in carveout.CA.iter().zip(clients.allowed.iter()) {
+        register.set(value);
+    }
+    for (register, &value) in carveout.CFIA.iter().zip(clients.force_internal.iter()) {
+        register.set(value);
+    }
+
+    let lock = if lock { CARVEOUT_CFG0_LOCK } else { 0 };
+    carveout
+        .CFG0
+        .set((clients.config & !CARVEOUT_CFG0_LOCK) | lock);
+
+    Ok(())
+}
+
+/// The client ID field in `MC_ERR_STATUS_0`.
+const MC_ERR_STATUS_CLIENT_ID_MASK: u32 = 0xFF;
+
+/// The write access bit in `MC_ERR_STATUS_0`.
+const MC_ERR_STATUS_RW: u32 = 1 << 16;
+
+/// The secure access bit in `MC_ERR_STATUS_0`.
+const MC_ERR_STATUS_SECURITY: u32 = 1 << 17;
+
+/// The shift of the upper address bits in `MC_ERR_STATUS_0`.
+const MC_ERR_STATUS_ADR_HI_SHIFT: u32 = 20;
+
+/// The upper address bits in `MC_ERR_STATUS_0`.
+const MC_ERR_STATUS_ADR_HI_MASK: u32 = 0x3;
+
+/// The shift of the error type field in `MC_ERR_STATUS_0`.
+const MC_ERR_STATUS_TYPE_SHIFT: u32 = 28;
+
+/// The error type field in `MC_ERR_STATUS_0`.
+const MC_ERR_STATUS_TYPE_MASK: u32 = 0x7;
+
+/// The kinds of errors latched by the Memory Controller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum McErrorKind {
+    /// An access to an address that does not decode to external memory.
+    DecodeError,
+    /// An access that violates the TrustZone security settings.
+    TrustZoneViolation,
+    /// An access to a protected security carveout.
+    CarveoutViolation,
+    /// An access that could not be translated by the SMMU.
+    SmmuTranslation,
+    /// An error type which is not known, with the raw type value.
+    Other(u32),
+}
+
+/// An error latched by the Memory Controller, decoded from `MC_ERR_STATUS_0`
+/// and `MC_ERR_ADR_0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct McError {
+    /// The kind of the error.
+    pub kind: McErrorKind,
+    /// The ID of the memory client which caused the error.
+    pub client_id: u8,
+    /// Whether the faulting access was a write.
+    pub write: bool,
+    /// Whether the faulting access was a secure access.
+    pub secure: bool,
+    /// The faulting physical address.
+    pub address: u64,
+}
+
+/// Gets the last error that was latched by the Memory Controller, if any.
+///
+/// The error stays latched until [`clear_error`] is called.
+///
+/// [`clear_error`]: fn.clear_error.html
+pub fn last_error() -> Option<McError> {
+    let controller = unsafe { &*REGISTERS };
+
+    let status = controller.MC_ERR_STATUS_0.get();
+    let kind = match (status >> MC_ERR_STATUS_TYPE_SHIFT) & MC_ERR_STATUS_TYPE_MASK {
+        0 => return None,
+        2 => McErrorKind::DecodeError,
+        3 => McErrorKind::TrustZoneViolation,
+        4 => McErrorKind::CarveoutViolation,
+        6 => McErrorKind::SmmuTranslation,
+        other => McErrorKind::Other(other),
+    };
+
+    let address_hi = (status >> MC_ERR_STATUS_ADR_HI_SHIFT) & MC_ERR_STATUS_ADR_HI_MASK;
+
+    Some(McError {
+        kind,
+        client_id: (status & MC_ERR_STATUS_CLIENT_ID_MASK) as u8,
+        write: status & MC_ERR_STATUS_RW != 0,
+        secure: status & MC_ERR_STATUS_SECURITY != 0,
+        address: ((address_hi as u64) << 32) | controller.MC_ERR_ADR_0.get() as u64,
+    })
+}
+
+/// Clears the error interrupt status of the Memory Controller so that the
+/// next error can be latched.
+pub fn clear_error() {
+    let controller = unsafe { &*REGISTERS };
+
+    // The interrupt status bits are cleared by writing ones.
+    controller
+        .MC_INTSTATUS_0
+        .set(controller.MC_INTSTATUS_0.get());
+}
 
 /// Configures the Memory Controller TSEC carveout.
 pub fn config_tsec_carveout(bom: u32, size_mb: u32, lock: bool) {
@@ -40,85 +221,70 @@ pub fn config_carveout() {
     controller.MC_MTS_CARVEOUT_ADR_HI_0.set(0);
     controller.MC_MTS_CARVEOUT_REG_CTRL_0.set(1);
 
-    controller.MC_SECURITY_CARVEOUT1_BOM_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_BOM_HI_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_SIZE_128KB_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CA0_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CA1_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CA2_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CA3_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CA4_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CFIA0_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CFIA1_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CFIA2_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CFIA3_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CFIA4_0.set(0);
-    controller.MC_SECURITY_CARVEOUT1_CFG0_0.set(0x4000006);
-
-    controller.MC_SECURITY_CARVEOUT3_BOM_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_BOM_HI_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_SIZE_128KB_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_CA0_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_CA1_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_CA2_0.set(0x3000000);
-    controller.MC_SECURITY_CARVEOUT3_CA3_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_CA4_0.set(0x300);
-    controller.MC_SECURITY_CARVEOUT3_CFIA0_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_CFIA1_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_CFIA2_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_CFIA3_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_CFIA4_0.set(0);
-    controller.MC_SECURITY_CARVEOUT3_CFG0_0.set(0x4401E7E);
-
-    controller.MC_SECURITY_CARVEOUT4_BOM_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_BOM_HI_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_SIZE_128KB_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CA0_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CA1_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CA2_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CA3_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CA4_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CFIA0_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CFIA1_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CFIA2_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CFIA3_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CFIA4_0.set(0);
-    controller.MC_SECURITY_CARVEOUT4_CFG0_0.set(0x8F);
-
-    controller.MC_SECURITY_CARVEOUT5_BOM_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_BOM_HI_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_SIZE_128KB_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CA0_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CA1_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CA2_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CA3_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CA4_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CFIA0_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CFIA1_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CFIA2_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CFIA3_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CFIA4_0.set(0);
-    controller.MC_SECURITY_CARVEOUT5_CFG0_0.set(0x8F);
+    configure_carveout(
+        CarveoutId::Carveout1,
+        0,
+        0,
+        ClientAccess {
+            config: 0x4000006,
+            ..ClientAccess::NONE
+        },
+        false,
+    )
+    .unwrap();
+
+    configure_carveout(
+        CarveoutId::Carveout3,
+        0,
+        0,
+        ClientAccess {
+            config: 0x4401E7E,
+            allowed: [0, 0, 0x3000000, 0, 0x300],
+            ..ClientAccess::NONE
+        },
+        false,
+    )
+    .unwrap();
+
+    configure_carveout(
+        CarveoutId::Carveout4,
+        0,
+        0,
+        ClientAccess {
+            config: 0x8E,
+            ..ClientAccess::NONE
+        },
+        true,
+    )
+    .unwrap();
+
+    configure_carveout(
+        CarveoutId::Carveout5,
+        0,
+        0,
+        ClientAccess {
+            config: 0x8E,
+            ..ClientAccess::NONE
+        },
+        true,
+    )
+    .unwrap();
 }
 
 /// Finalizes the Memory Controller carveout configuration.
 pub fn finalize_carveout() {
-    let controller = unsafe { &*REGISTERS };
-
-    controller.MC_SECURITY_CARVEOUT2_BOM_0.set(0x8002_0000);
-    controller.MC_SECURITY_CARVEOUT2_BOM_HI_0.set(0);
-    controller.MC_SECURITY_CARVEOUT2_SIZE_128KB_0.set(2);
-    controller.MC_SECURITY_CARVEOUT2_CA0_0.set(0);
-    controller.MC_SECURITY_CARVEOUT2_CA1_0.set(0);
-    controller.MC_SECURITY_CARVEOUT2_CA2_0.set(0x3000000);
-    controller.MC_SECURITY_CARVEOUT2_CA3_0.set(0);
-    controller.MC_SECURITY_CARVEOUT2_CA4_0.set(0x300);
-    controller.MC_SECURITY_CARVEOUT2_CFIA0_0.set(0);
-    controller.MC_SECURITY_CARVEOUT2_CFIA1_0.set(0);
-    controller.MC_SECURITY_CARVEOUT2_CFIA2_0.set(0);
-    controller.MC_SECURITY_CARVEOUT2_CFIA3_0.set(0);
-    controller.MC_SECURITY_CARVEOUT2_CFIA4_0.set(0);
-    controller.MC_SECURITY_CARVEOUT2_CFG0_0.set(0x440167E);
+    configure_carveout(
+        CarveoutId::Carveout2,
+        0x8002_0000,
+        2 * CARVEOUT_GRANULARITY_KB,
+        ClientAccess {
+            config: 0x440167E,
+            allowed: [0, 0, 0x3000000, 0, 0x300],
+            ..ClientAccess::NONE
+        },
+        false,
+    )
+    .unwrap();
 }
 
 /// Enables AHB redirecting.
@@ -171,3 +337,133 @@ pub fn enable_mc() {
 
     disable_ahb_redirect();
 }
+
+/// The size of the EMC register block.
+const EMC_REGISTERS_SIZE: u32 = 0x1000;
+
+/// The offset of the `EMC_INTSTATUS_0` register.
+const EMC_INTSTATUS: u32 = 0x0;
+
+/// The clock change completion bit in `EMC_INTSTATUS_0`.
+const EMC_INTSTATUS_CLKCHANGE_COMPLETE: u32 = 1 << 4;
+
+/// The offset of the `EMC_TIMING_CONTROL_0` register.
+const EMC_TIMING_CONTROL: u32 = 0x28;
+
+/// The offset of the `EMC_EMC_STATUS_0` register.
+const EMC_STATUS: u32 = 0x2B4;
+
+/// The stalled timing update bit in `EMC_EMC_STATUS_0`.
+const EMC_STATUS_TIMING_UPDATE_STALLED: u32 = 1 << 23;
+
+/// The time in microseconds to wait for the EMC to complete a clock change.
+const EMC_CLKCHANGE_TIMEOUT: u32 = 1000;
+
+fn emc_register(offset: u32) -> &'static ReadWrite<u32> {
+    assert!(
+        offset < EMC_REGISTERS_SIZE && offset & 3 == 0,
+        "Invalid EMC register given!"
+    );
+
+    unsafe { &*((EMC + offset) as *const ReadWrite<u32>) }
+}
+
+fn emc_wait(offset: u32, mask: u32, value: u32) -> Result<(), EmcError> {
+    let start = get_microseconds();
+    while emc_register(offset).get() & mask != value {
+        if has_elapsed(start, get_microseconds(), EMC_CLKCHANGE_TIMEOUT) {
+            return Err(EmcError::Timeout);
+        }
+    }
+
+    Ok(())
+}
+
+/// Errors that may occur when switching the EMC frequency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmcError {
+    /// A register offset of the timing table is out of range for its block.
+    InvalidRegister,
+    /// The EMC did not acknowledge the timing update or clock change in time.
+    Timeout,
+}
+
+/// A timing table that describes an EMC frequency.
+///
+/// Timing tables are specific to the DRAM and the board, so these values are
+/// expected to come from the vendor tables of the target device.
+#[derive(Clone, Copy, Debug)]
+pub struct EmcTiming<'a> {
+    /// The value of `CLK_RST_CONTROLLER_CLK_SOURCE_EMC_0` which selects the
+    /// clock source and divisor of the new frequency.
+    pub clk_source_emc: u32,
+    /// Pairs of register offsets into the EMC block and values for the
+    /// shadowed EMC burst registers.
+    pub emc_burst: &'a [(u32, u32)],
+    /// Pairs of register offsets into the MC block and values for the
+    /// shadowed MC arbitration registers.
+    pub mc_burst: &'a [(u32, u32)],
+}
+
+/// Switches the DRAM to the frequency described by `table`.
+///
+/// This programs the shadowed EMC and MC timing registers and then changes the
+/// EMC clock, which makes the EMC put the DRAM into self-refresh, switch the
+/// clock and latch the new timings in a single handshake.
+///
+/// NOTE: Periodic training and DLL reconfiguration are not performed, so
+/// `table` must describe a frequency that doesn't depend on them.
+///
+/// # Safety
+///
+/// An invalid timing table makes DRAM inaccessible, which will most likely
+/// crash the system. This must not be called while the caller executes from
+/// or accesses DRAM.
+pub unsafe fn set_emc_frequency(table: &EmcTiming<'_>) -> Result<(), EmcError> {
+    let controller = &*REGISTERS;
+    let car = &*car::REGISTERS;
+
+    let mc_size = core::mem::size_of::<Registers>() as u32;
+    if table
+        .emc_burst
+        .iter()
+        .any(|&(offset, _)| offset >= EMC_REGISTERS_SIZE || offset & 3 != 0)
+        || table
+            .mc_burst
+            .iter()
+            .any(|&(offset, _)| offset >= mc_size || offset & 3 != 0)
+    {
+        return Err(EmcError::InvalidRegister);
+    }
+
+    // Make sure that no previous timing update is still pending.
+    emc_wait(EMC_STATUS, EMC_STATUS_TIMING_UPDATE_STALLED, 0)?;
+
+    // Program the EMC shadow registers, which are latched on the clock change.
+    for &(offset, value) in table.emc_burst {
+        emc_register(offset).set(value);
+    }
+
+    // Program the MC arbitration registers and latch them.
+    for &(offset, value) in table.mc_burst {
+        (&*((MC + offset) as *const ReadWrite<u32>)).set(value);
+    }
+    controller.MC_TIMING_CONTROL_0.set(1);
+
+    // Clear a stale completion status and kick off the clock change.
+    emc_register(EMC_INTSTATUS).set(EMC_INTSTATUS_CLKCHANGE_COMPLETE);
+    car.CLK_RST_CONTROLLER_CLK_SOURCE_EMC_0
+        .set(table.clk_source_emc);
+
+    // Wait for the EMC to finish the handshake with the new clock.
+    emc_wait(
+        EMC_INTSTATUS,
+        EMC_INTSTATUS_CLKCHANGE_COMPLETE,
+        EMC_INTSTATUS_CLKCHANGE_COMPLETE,
+    )?;
+    emc_register(EMC_INTSTATUS).set(EMC_INTSTATUS_CLKCHANGE_COMPLETE);
+
+    // Latch any non-shadowed timings that depend on the new clock.
+    emc_register(EMC_TIMING_CONTROL).set(1);
+    emc_wait(EMC_STATUS, EMC_STATUS_TIMING_UPDATE_STALLED, 0)
+}
@@ -449,3 +449,27 @@ register_structs! {
 }
 
 assert_eq_size!(Registers, [u8; 0xD80]);
+
+/// The offset of the first generalized security carveout register group.
+pub(crate) const MC_SECURITY_CARVEOUT1_OFFSET: u32 = 0xC08;
+
+/// The distance between two generalized security carveout register groups.
+pub(crate) const MC_SECURITY_CARVEOUT_STRIDE: u32 = 0x50;
+
+register_structs! {
+    /// Representation of a generalized security carveout register group.
+    ///
+    /// Each of the `MC_SECURITY_CARVEOUT{N}_*` groups shares this layout.
+    #[allow(non_snake_case)]
+    pub CarveoutRegisters {
+        (0x00 => pub CFG0: ReadWrite<u32>),
+        (0x04 => pub BOM: ReadWrite<u32>),
+        (0x08 => pub BOM_HI: ReadWrite<u32>),
+        (0x0C => pub SIZE_128KB: ReadWrite<u32>),
+        (0x10 => pub CA: [ReadWrite<u32>; 5]),
+        (0x24 => pub CFIA: [ReadWrite<u32>; 5]),
+        (0x38 => @END),
+    }
+}
+
+assert_eq_size!(CarveoutRegisters, [u8; 0x38]);
@@ -16,7 +16,11 @@ pub mod pcie {
     pub const PCIE_A3: u32 = BASE + 0x1F00_0000;
 }
 
-// TODO: IRAM
+/// Start of the internal RAM.
+pub const IRAM: u32 = 0x4000_0000;
+
+/// Start of the external DRAM.
+pub const DRAM: u32 = 0x8000_0000;
 
 /// Start address of the Host1x register block.
 pub const HOST1X: u32 = 0x5000_0000;
@@ -109,6 +113,12 @@ pub mod gpu {
     pub const GART: u32 = BASE;
 }
 
+/// Start of the Arbitration Semaphores register block.
+pub const ARB_SEMA: u32 = 0x6000_1000;
+
+/// Start of the Legacy Interrupt Controller register block.
+pub const ICTLR: u32 = 0x6000_4000;
+
 /// Start of the TMR register block.
 pub const TMR: u32 = 0x6000_5000;
 
@@ -153,6 +163,9 @@ pub mod gpio {
 /// Start of the Exception Vectors register block.
 pub const EXCEPTION_VECTORS: u32 = 0x6000_F000;
 
+/// Address of the BPMP reset vector within the Exception Vectors register block.
+pub const EVP_COP_RESET_VECTOR: u32 = EXCEPTION_VECTORS + 0x200;
+
 /// Start of the IPATCH register block.
 pub const IPATCH: u32 = 0x6001_DC00;
 
@@ -321,6 +334,9 @@ pub const ATOMICS: u32 = 0x7001_6000;
 /// Start of the MC register block.
 pub const MC: u32 = 0x7001_9000;
 
+/// Start of the EMC register block.
+pub const EMC: u32 = 0x7001_B000;
+
 /// Start of the SATA register block.
 pub const SATA: u32 = 0x7002_0000;
 
@@ -350,3 +366,79 @@ pub const USB2: u32 = 0x7D00_4000;
 
 /// Start of the boot code.
 pub const IROM: u32 = 0x0010_0000;
+
+/// The coarse windows of the physical address space.
+///
+/// This can be used to reason about which memory an address belongs to, for
+/// example when validating buffers that are handed to DMA engines which can
+/// only access specific windows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Region {
+    /// The boot code, 96kB starting at [`IROM`].
+    ///
+    /// [`IROM`]: constant.IROM.html
+    Irom,
+    /// The internal RAM, 256kB starting at [`IRAM`].
+    ///
+    /// [`IRAM`]: constant.IRAM.html
+    Iram,
+    /// The memory-mapped I/O registers, between [`HOST1X`] and [`DRAM`].
+    ///
+    /// [`HOST1X`]: constant.HOST1X.html
+    /// [`DRAM`]: constant.DRAM.html
+    Mmio,
+    /// The external DRAM, the remainder of the 32-bit address space starting at [`DRAM`].
+    ///
+    /// [`DRAM`]: constant.DRAM.html
+    Dram,
+}
+
+impl Region {
+    /// Gets the start address of the region.
+    pub const fn base(self) -> u32 {
+        match self {
+            Region::Irom => IROM,
+            Region::Iram => IRAM,
+            Region::Mmio => HOST1X,
+            Region::Dram => DRAM,
+        }
+    }
+
+    /// Gets the size of the region in bytes.
+    pub const fn size(self) -> u32 {
+        match self {
+            Region::Irom => 0x1_8000,
+            Region::Iram => 0x4_0000,
+            Region::Mmio => DRAM - HOST1X,
+            Region::Dram => 0u32.wrapping_sub(DRAM),
+        }
+    }
+
+    /// Finds the region that contains the given address.
+    ///
+    /// Returns `None` for addresses that don't belong to any known region.
+    pub fn of(address: u32) -> Option<Self> {
+        [Region::Irom, Region::Iram, Region::Mmio, Region::Dram]
+            .iter()
+            .copied()
+            .find(|region| region.contains(address))
+    }
+
+    /// Indicates whether the given address is within the region.
+    pub const fn contains(self, address: u32) -> bool {
+        // Compare against the size so that regions which end at the top
+        // of the address space don't overflow.
+        address >= self.base() && address - self.base() < self.size()
+    }
+
+    /// Gets the offset of the given address from the start of the region.
+    ///
+    /// Returns `None` if the address is not within the region.
+    pub const fn offset_of(self, address: u32) -> Option<u32> {
+        if self.contains(address) {
+            Some(address - self.base())
+        } else {
+            None
+        }
+    }
+}
@@ -28,11 +28,26 @@
 //! Many of the configuration methods on a [`PinGrP`] are actually considered `unsafe` because
 //! wrong usage of them can cause permanent damage to the hardware which is at the user's risk.
 //!
+//! # Sleep and Resume
+//!
+//! The whole Pinmux configuration can be captured with [`save_state`] before entering LP0
+//! and written back with [`restore_state`] on resume. As most pads are parked on LP0 entry,
+//! [`unpark_all`] completes the recovery by releasing them from the parked state.
+//!
+//! # Pad Control
+//!
+//! Drive strengths and slew rates of whole pad groups, such as the SDMMC pads,
+//! are configured through the [`padctl`] submodule instead.
+//!
 //! [`Gpio`]: ../gpio/struct.Gpio.html
 //! [`PinGrP`]: enum.PinGrP.html
+//! [`save_state`]: fn.save_state.html
+//! [`restore_state`]: fn.restore_state.html
+//! [`unpark_all`]: fn.unpark_all.html
 
 // Inspired by https://github.com/NVIDIA/tegra-pinmux-scripts.
 
+pub mod padctl;
 mod registers;
 
 use enum_primitive::FromPrimitive;
@@ -40,6 +55,19 @@ use tock_registers::{interfaces::*, registers::*};
 
 pub use crate::pinmux::registers::*;
 
+/// Errors that may occur when configuring a [`PinGrP`].
+///
+/// [`PinGrP`]: enum.PinGrP.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PinmuxError {
+    /// The Pin Group is reserved and must not be configured.
+    ReservedPin,
+    /// The Pin Function cannot be selected for the Pin Group.
+    UnsupportedFunction,
+    /// The setting is not implemented by the pad group.
+    UnsupportedSetting,
+}
+
 /// Pin Groups on the Tegra X1 SoC that can be customized and variably configured.
 ///
 /// Many drivers of the `libtegra` crate depend on proper Pin Multiplexing settings
@@ -482,7 +510,10 @@ enum_from_primitive! {
 #[derive(Debug)]
 struct SocPinGrP(PinGrP, [PinFunction; 4]);
 
-const SOC_PINS: [SocPinGrP; 165] = [
+/// The number of 32-bit registers in the contiguous Pinmux register window.
+pub const PINMUX_REGISTER_COUNT: usize = 165;
+
+const SOC_PINS: [SocPinGrP; PINMUX_REGISTER_COUNT] = [
     SocPinGrP(
         PinGrP::Sdmmc1ClkPm0,
         [
@@ -1971,9 +2002,40 @@ const SOC_PINS: [SocPinGrP; 165] = [
 ];
 
 impl PinGrP {
+    /// Gets the four SFIO Pin Functions this Pin Group can be multiplexed to.
+    ///
+    /// The index of a function in the array corresponds to its mux value.
+    pub fn supported_functions(&self) -> [PinFunction; 4] {
+        // Reserved pads have no entry in the SoC pin table.
+        if self == &PinGrP::Reserved {
+            return [PinFunction::Reserved; 4];
+        }
+
+        SOC_PINS[self.clone() as usize].1
+    }
+
+    /// Checks whether a given Pin Function can be selected for this Pin Group.
+    ///
+    /// A function for which this returns `true` can be passed to [`PinGrP::set_function`]
+    /// without causing a panic. The `Rsvd0` through `Rsvd3` functions, which select a mux
+    /// value directly, are supported on all pads except for reserved ones.
+    ///
+    /// [`PinGrP::set_function`]: #method.set_function
+    pub fn supports(&self, function: PinFunction) -> bool {
+        if self == &PinGrP::Reserved
+            || function == PinFunction::Default
+            || function == PinFunction::Reserved
+        {
+            return false;
+        }
+
+        function >= PinFunction::Rsvd0 || self.supported_functions().contains(&function)
+    }
+
     /// Configures a given Pin Function for this Pin Group.
     ///
     /// Applicable to all pads, but only certain functions may be supported per pad.
+    /// Use [`PinGrP::supports`] to check for that beforehand.
     ///
     /// # Panics
     ///
@@ -1984,6 +2046,8 @@ impl PinGrP {
     /// Playing around with Pin Multiplexing settings can irreparably damage your hardware,
     /// please make sure that you know exactly what you are doing before calling this
     /// function.
+    ///
+    /// [`PinGrP::supports`]: #method.supports
     pub unsafe fn set_function(&self, function: PinFunction) {
         // Avoid setting of reserved pins.
         if function == PinFunction::Default
@@ -1993,28 +2057,58 @@ impl PinGrP {
             return;
         }
 
-        // Compute the register offset that corresponds to this pin.
-        let pin = self.clone() as usize;
-        let register = &*((PINMUX_BASE + (pin * 4) as u32) as *const ReadWrite<u32>);
+        // Compute the corresponding mux value.
+        let mux = self.mux_value(function).expect("Invalid mux value!");
+
+        self.write_mux(mux);
+    }
+
+    /// Configures a given Pin Function for this Pin Group, if supported.
+    ///
+    /// This is the fallible counterpart to [`PinGrP::set_function`], which returns an
+    /// error instead of panicking when the function is not supported on this pad or
+    /// when this pad is reserved. The pad is left untouched in that case.
+    ///
+    /// # Safety
+    ///
+    /// Playing around with Pin Multiplexing settings can irreparably damage your hardware,
+    /// please make sure that you know exactly what you are doing before calling this
+    /// function.
+    ///
+    /// [`PinGrP::set_function`]: #method.set_function
+    pub unsafe fn try_set_function(&self, function: PinFunction) -> Result<(), PinmuxError> {
+        if self == &PinGrP::Reserved {
+            return Err(PinmuxError::ReservedPin);
+        }
+        if !self.supports(function) {
+            return Err(PinmuxError::UnsupportedFunction);
+        }
 
         // Compute the corresponding mux value.
-        let mux = if function >= PinFunction::Rsvd0 {
-            (function as u32 - PinFunction::Rsvd0 as u32) & 3
+        let mux = self
+            .mux_value(function)
+            .ok_or(PinmuxError::UnsupportedFunction)?;
+
+        self.write_mux(mux);
+
+        Ok(())
+    }
+
+    fn mux_value(&self, function: PinFunction) -> Option<u32> {
+        if function >= PinFunction::Rsvd0 {
+            Some((function as u32 - PinFunction::Rsvd0 as u32) & 3)
         } else {
-            let mut i = 0;
-            for func in &SOC_PINS[pin].1 {
-                if *func == function {
-                    break;
-                }
-                i += 1;
-            }
-
-            if i > 3 {
-                panic!("Invalid mux value!");
-            }
-
-            i
-        };
+            self.supported_functions()
+                .iter()
+                .position(|func| *func == function)
+                .map(|i| i as u32)
+        }
+    }
+
+    unsafe fn write_mux(&self, mux: u32) {
+        // Compute the register offset that corresponds to this pin.
+        let pin = self.clone() as u32;
+        let register = &*((PINMUX_BASE + (pin * 4)) as *const ReadWrite<u32>);
 
         // Set the bits accordingly.
         let mut value = register.get();
@@ -2402,3 +2496,56 @@ impl PinGrP {
         register.set(value);
     }
 }
+
+/// Captures the contents of all Pinmux registers into `buf`.
+///
+/// The state can be written back through [`restore_state`].
+///
+/// [`restore_state`]: fn.restore_state.html
+pub fn save_state(buf: &mut [u32; PINMUX_REGISTER_COUNT]) {
+    for (pin, value) in buf.iter_mut().enumerate() {
+        let register = unsafe { &*((PINMUX_BASE + (pin * 4) as u32) as *const ReadWrite<u32>) };
+        *value = register.get();
+    }
+}
+
+/// Restores the contents of all Pinmux registers from a state captured by [`save_state`].
+///
+/// Reserved registers within the Pinmux window are left untouched.
+///
+/// # Safety
+///
+/// Playing around with Pin Multiplexing settings can irreparably damage your hardware,
+/// please make sure that the state was captured on the same board before calling this
+/// function.
+///
+/// [`save_state`]: fn.save_state.html
+pub unsafe fn restore_state(buf: &[u32; PINMUX_REGISTER_COUNT]) {
+    for (pin, value) in buf.iter().enumerate() {
+        if SOC_PINS[pin].0 == PinGrP::Reserved {
+            continue;
+        }
+
+        let register = &*((PINMUX_BASE + (pin * 4) as u32) as *const ReadWrite<u32>);
+        register.set(*value);
+    }
+}
+
+/// Releases all Pin Groups from the Parking state they were put into on LP0 entry.
+///
+/// This is the last step of Pinmux recovery on LP0 exit, after [`restore_state`].
+///
+/// # Safety
+///
+/// Playing around with Pin Multiplexing settings can irreparably damage your hardware,
+/// please make sure that you know exactly what you are doing before calling this
+/// function.
+///
+/// [`restore_state`]: fn.restore_state.html
+pub unsafe fn unpark_all() {
+    for soc_pin in SOC_PINS.iter() {
+        if soc_pin.0 != PinGrP::Reserved && soc_pin.0.get_park() == PinPark::Parked {
+            soc_pin.0.set_park(PinPark::Normal);
+        }
+    }
+}
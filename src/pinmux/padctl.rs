@@ -0,0 +1,155 @@
+//! Pad Control groups of the Tegra X1 APB Misc block.
+//!
+//! Unlike the per-pin Pinmux registers, the `CFGPADCTRL` registers configure
+//! electrical characteristics like drive strength and slew rate for whole groups
+//! of pads at once. Interfaces like SDMMC depend on these settings to be properly
+//! calibrated before high-speed modes can be used.
+
+use tock_registers::{interfaces::*, registers::ReadWrite};
+
+use crate::apb::misc::REGISTERS;
+use crate::pinmux::PinmuxError;
+
+/// Bit layout of the drive strength and slew rate fields of a Pad Control register.
+struct Layout {
+    /// The offset of the DRVDN field.
+    drvdn_offset: u32,
+    /// The offset of the DRVUP field.
+    drvup_offset: u32,
+    /// The width of the DRVDN and DRVUP fields.
+    drive_width: u32,
+    /// Whether the pad group has SLWR and SLWF fields.
+    slew: bool,
+}
+
+impl Layout {
+    const SDMMC: Self = Layout {
+        drvdn_offset: 12,
+        drvup_offset: 20,
+        drive_width: 7,
+        slew: true,
+    };
+
+    const EMMC: Self = Layout {
+        drvdn_offset: 2,
+        drvup_offset: 8,
+        drive_width: 6,
+        slew: false,
+    };
+}
+
+/// Slew rate settings for the rising and falling edges of a pad group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlewRate {
+    /// The fastest edges.
+    Fastest = 0,
+    Fast = 1,
+    Slow = 2,
+    /// The slowest edges.
+    Slowest = 3,
+}
+
+/// Pad Control groups on the Tegra X1 SoC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PadGroup {
+    /// The pads of the SDMMC1 controller.
+    Sdmmc1,
+    /// The pads of the SDMMC2 controller.
+    Emmc2,
+    /// The pads of the SDMMC3 controller.
+    Sdmmc3,
+    /// The pads of the SDMMC4 controller.
+    Emmc4,
+}
+
+impl PadGroup {
+    fn get_register(self) -> &'static ReadWrite<u32> {
+        let gp = unsafe { &(*REGISTERS).gp };
+
+        match self {
+            PadGroup::Sdmmc1 => &gp.APB_MISC_GP_SDMMC1_PAD_CFGPADCTRL_0,
+            PadGroup::Emmc2 => &gp.APB_MISC_GP_EMMC2_PAD_CFGPADCTRL_0,
+            PadGroup::Sdmmc3 => &gp.APB_MISC_GP_SDMMC3_PAD_CFGPADCTRL_0,
+            PadGroup::Emmc4 => &gp.APB_MISC_GP_EMMC4_PAD_CFGPADCTRL_0,
+        }
+    }
+
+    fn get_layout(self) -> Layout {
+        match self {
+            PadGroup::Sdmmc1 | PadGroup::Sdmmc3 => Layout::SDMMC,
+            PadGroup::Emmc2 | PadGroup::Emmc4 => Layout::EMMC,
+        }
+    }
+
+    /// Gets the maximum drive code that is supported by the pad group.
+    pub fn max_drive_code(self) -> u32 {
+        (1 << self.get_layout().drive_width) - 1
+    }
+
+    /// Extracts the currently configured pull-up and pull-down drive codes from
+    /// this pad group, in that order.
+    pub fn get_drive_codes(self) -> (u32, u32) {
+        let layout = self.get_layout();
+        let value = self.get_register().get();
+
+        let mask = self.max_drive_code();
+        (
+            (value >> layout.drvup_offset) & mask,
+            (value >> layout.drvdn_offset) & mask,
+        )
+    }
+
+    /// Configures the pull-up (DRVUP) and pull-down (DRVDN) drive codes of this pad group.
+    ///
+    /// # Panics
+    ///
+    /// Panics when a drive code exceeds [`PadGroup::max_drive_code`].
+    ///
+    /// # Safety
+    ///
+    /// Playing around with Pad Control settings can irreparably damage your hardware,
+    /// please make sure that you know exactly what you are doing before calling this
+    /// function.
+    pub unsafe fn set_drive_codes(self, drvup: u32, drvdn: u32) {
+        let layout = self.get_layout();
+        let register = self.get_register();
+
+        let mask = self.max_drive_code();
+        assert!(drvup <= mask && drvdn <= mask, "Invalid drive code!");
+
+        // Replace both drive codes in a single write.
+        let mut value = register.get();
+        value &= !((mask << layout.drvup_offset) | (mask << layout.drvdn_offset));
+        value |= (drvup << layout.drvup_offset) | (drvdn << layout.drvdn_offset);
+        register.set(value);
+    }
+
+    /// Configures the slew rates for rising (SLWR) and falling (SLWF) edges of this pad group.
+    ///
+    /// Returns [`PinmuxError::UnsupportedSetting`] for pad groups without slew rate control.
+    ///
+    /// # Safety
+    ///
+    /// Playing around with Pad Control settings can irreparably damage your hardware,
+    /// please make sure that you know exactly what you are doing before calling this
+    /// function.
+    pub unsafe fn set_slew_rates(
+        self,
+        rising: SlewRate,
+        falling: SlewRate,
+    ) -> Result<(), PinmuxError> {
+        if !self.get_layout().slew {
+            return Err(PinmuxError::UnsupportedSetting);
+        }
+
+        let register = self.get_register();
+
+        // SLWR and SLWF occupy the two topmost bit pairs.
+        let mut value = register.get();
+        value &= !(0xF << 28);
+        value |= (rising as u32) << 28 | (falling as u32) << 30;
+        register.set(value);
+
+        Ok(())
+    }
+}
@@ -46,16 +46,48 @@
 //! }
 //! ```
 //!
+//! The current state of a partition can be queried through [`is_powered`].
+//!
+//! A partition remains isolated by clamps after it was powered, until these are
+//! removed through [`remove_clamp`]. [`ungate_partition`] does both steps at once.
+//!
+//! # Reboot and Power-Off
+//!
+//! The SoC can be reset through the [`reboot`] function, which asserts the main software
+//! reset of the PMC. A full system power-down is requested from the PMIC that is passed to
+//! the [`poweroff`] function.
+//!
+//! The cause of the last reset is latched by the PMC and can be queried through the
+//! [`reset_reason`] function. It persists across resets until it is explicitly cleared
+//! with [`clear_reset_reason`].
+//!
 //! [`Partition`]: enum.Partition.html
 //! [`powergate_partition`]: fn.powergate_partition.html
+//! [`is_powered`]: fn.is_powered.html
+//! [`remove_clamp`]: fn.remove_clamp.html
+//! [`ungate_partition`]: fn.ungate_partition.html
+//! [`reboot`]: fn.reboot.html
+//! [`poweroff`]: fn.poweroff.html
+//! [`reset_reason`]: fn.reset_reason.html
+//! [`clear_reset_reason`]: fn.clear_reset_reason.html
 
-use crate::timer::usleep;
+use crate::{
+    pmic::{self, Max77620},
+    timer::usleep,
+};
+use enum_primitive::FromPrimitive;
 use tock_registers::interfaces::*;
 
 pub use registers::*;
 
 mod registers;
 
+/// The main software reset bit in `APBDEV_PMC_CNTRL_0`.
+const PMC_CNTRL_MAIN_RST: u32 = 1 << 4;
+
+/// The reset source field in `APBDEV_PMC_RST_STATUS_0`.
+const PMC_RST_STATUS_RST_SOURCE_MASK: u32 = 0x7;
+
 enum_from_primitive! {
     /// Enumeration over power-gated PMC partitions.
     #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -87,19 +119,48 @@ enum_from_primitive! {
     }
 }
 
+enum_from_primitive! {
+    /// Enumeration over the reset sources latched by the PMC.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[repr(u32)]
+    pub enum ResetReason {
+        /// A cold boot after the system was powered on.
+        PowerOn = 0,
+        /// A reset triggered by an expired watchdog timer.
+        Watchdog = 1,
+        /// A reset triggered by the thermal sensor.
+        Sensor = 2,
+        /// A reset triggered through the main software reset of the PMC.
+        SoftwareReset = 3,
+        /// A wake-up from the LP0 (Deep Sleep) state.
+        Lp0 = 4,
+        /// A reset triggered by the Always-On thermal alert generator.
+        Aotag = 5,
+    }
+}
+
+/// Checks whether a given partition is currently powered.
+pub fn is_powered(partition: Partition) -> bool {
+    let pmc = unsafe { &*REGISTERS };
+
+    (pmc.APBDEV_PMC_PWRGATE_STATUS_0.get() & (1 << partition as u32)) != 0
+}
+
 /// Toggles power gating for a given partition.
+///
+/// Nothing is done if the partition already is in the desired state, see [`is_powered`].
+///
+/// [`is_powered`]: fn.is_powered.html
 pub fn powergate_partition(partition: Partition, enable: bool) -> Result<(), ()> {
     let pmc = unsafe { &*REGISTERS };
 
-    let partition_id = partition as u32;
-    let partition_mask = 1 << partition_id;
-    let desired_state = (enable as u32) << partition_id;
-
     // Check if the partition already has the desired state.
-    if (pmc.APBDEV_PMC_PWRGATE_STATUS_0.get() & partition_mask) == desired_state {
+    if is_powered(partition) == enable {
         return Ok(());
     }
 
+    let partition_id = partition as u32;
+
     // Wait for the power gating controller to enter idle state.
     let mut i = 5001;
     while (pmc.APBDEV_PMC_PWRGATE_TOGGLE_0.get() & 0x100) != 0 {
@@ -117,7 +178,33 @@ pub fn powergate_partition(partition: Partition, enable: bool) -> Result<(), ()>
     // Wait for the changes to take effect.
     i = 5001;
     while i > 0 {
-        if (pmc.APBDEV_PMC_PWRGATE_STATUS_0.get() & partition_mask) == desired_state {
+        if is_powered(partition) == enable {
+            return Ok(());
+        }
+
+        usleep(1);
+        i -= 1;
+    }
+
+    Err(())
+}
+
+/// Removes the clamps which isolate a given partition from the rest of the SoC.
+///
+/// A partition stays isolated after it was powered until its clamps are removed.
+/// An error is returned if the clamps are not released in time.
+pub fn remove_clamp(partition: Partition) -> Result<(), ()> {
+    let pmc = unsafe { &*REGISTERS };
+
+    let partition_mask = 1 << partition as u32;
+
+    // Request the clamps to be removed.
+    pmc.APBDEV_PMC_REMOVE_CLAMPING_CMD_0.set(partition_mask);
+
+    // Wait for the changes to take effect.
+    let mut i = 5001;
+    while i > 0 {
+        if (pmc.APBDEV_PMC_CLAMP_STATUS_0.get() & partition_mask) == 0 {
             return Ok(());
         }
 
@@ -127,3 +214,69 @@ pub fn powergate_partition(partition: Partition, enable: bool) -> Result<(), ()>
 
     Err(())
 }
+
+/// Powers a given partition and removes its clamps, so that it is ready for use.
+///
+/// This combines [`powergate_partition`] and [`remove_clamp`].
+///
+/// [`powergate_partition`]: fn.powergate_partition.html
+/// [`remove_clamp`]: fn.remove_clamp.html
+pub fn ungate_partition(partition: Partition) -> Result<(), ()> {
+    powergate_partition(partition, true)?;
+    remove_clamp(partition)
+}
+
+/// Gets the source of the last system reset, as latched by the PMC.
+///
+/// An error is returned if the PMC reports a reset source which is not known.
+pub fn reset_reason() -> Result<ResetReason, ()> {
+    let pmc = unsafe { &*REGISTERS };
+
+    let source = pmc.APBDEV_PMC_RST_STATUS_0.get() & PMC_RST_STATUS_RST_SOURCE_MASK;
+    ResetReason::from_u32(source).ok_or(())
+}
+
+/// Clears the latched reset source so that the next reset can be told apart.
+pub fn clear_reset_reason() {
+    let pmc = unsafe { &*REGISTERS };
+
+    pmc.APBDEV_PMC_RST_STATUS_0
+        .set(pmc.APBDEV_PMC_RST_STATUS_0.get() & !PMC_RST_STATUS_RST_SOURCE_MASK);
+}
+
+/// Reboots the SoC by asserting the main software reset of the PMC.
+pub fn reboot() -> ! {
+    let pmc = unsafe { &*REGISTERS };
+
+    // Assert the main reset and read back the register to commit the write.
+    pmc.APBDEV_PMC_CNTRL_0
+        .set(pmc.APBDEV_PMC_CNTRL_0.get() | PMC_CNTRL_MAIN_RST);
+    pmc.APBDEV_PMC_CNTRL_0.get();
+
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+/// Powers the system off by triggering the power-down sequence of the given `pmic`.
+///
+/// See [`Max77620::request_power_off`] for the requirements of this function. It only
+/// returns when the power-off request could not be sent to the PMIC, with the error
+/// that occurred.
+///
+/// [`Max77620::request_power_off`]: ../pmic/struct.Max77620.html#method.request_power_off
+pub fn poweroff(pmic: &Max77620) -> pmic::Error {
+    let pmc = unsafe { &*REGISTERS };
+
+    // Request the PMIC to power the system down.
+    if let Err(error) = pmic.request_power_off() {
+        return error;
+    }
+
+    // Dummy read to make sure all prior writes have been committed.
+    pmc.APBDEV_PMC_CNTRL_0.get();
+
+    loop {
+        core::hint::spin_loop();
+    }
+}
@@ -0,0 +1,80 @@
+//! Driver for the Maxim MAX77620 Power Management IC.
+//!
+//! # Description
+//!
+//! The MAX77620 is the PMIC that is commonly paired with the Tegra X1, e.g. on the
+//! Nintendo Switch and the Jetson TX1. It is controlled over I²C and supplies the
+//! rails of the system, which is why a full power-down has to be requested from it
+//! rather than from the PMC.
+//!
+//! The PMIC is not hard-coded to a specific bus, a [`Max77620`] is created for the
+//! [`I2c`] controller and the address it is wired to on the board:
+//!
+//! ```no_run
+//! use libtegra::{i2c::I2c, pmic::{Max77620, MAX77620_I2C_ADDRESS}};
+//!
+//! let pmic = Max77620::new(&I2c::C5, MAX77620_I2C_ADDRESS);
+//!
+//! I2c::C5.init();
+//! pmic.request_power_off().unwrap();
+//! ```
+//!
+//! [`Max77620`]: struct.Max77620.html
+//! [`I2c`]: ../i2c/struct.I2c.html
+
+use crate::i2c::{self, I2c};
+
+/// The default I²C address of the MAX77620 PMIC.
+pub const MAX77620_I2C_ADDRESS: u32 = 0x3C;
+
+/// The ONOFFCNFG1 register of the MAX77620 PMIC.
+const MAX77620_REG_ONOFFCNFG1: u8 = 0x41;
+
+/// The software power-off bit in the ONOFFCNFG1 register of the MAX77620 PMIC.
+const MAX77620_ONOFFCNFG1_PWR_OFF: u8 = 1 << 1;
+
+/// Errors that may occur when communicating with the PMIC.
+#[derive(Clone, Copy, Debug)]
+pub enum Error {
+    /// The PMIC could not be reached over I²C.
+    Bus(i2c::Error),
+}
+
+impl From<i2c::Error> for Error {
+    fn from(error: i2c::Error) -> Self {
+        Error::Bus(error)
+    }
+}
+
+/// Representation of a MAX77620 PMIC on an I²C bus.
+#[derive(Debug)]
+pub struct Max77620 {
+    // The I2C controller the PMIC is attached to.
+    i2c: &'static I2c,
+    // The I²C address of the PMIC.
+    address: u32,
+}
+
+impl Max77620 {
+    /// Creates a new PMIC instance that is reachable at `address` through `i2c`.
+    pub const fn new(i2c: &'static I2c, address: u32) -> Self {
+        Max77620 { i2c, address }
+    }
+
+    /// Requests the PMIC to trigger its power-down sequence.
+    ///
+    /// The [`I2c`] controller must be initialized beforehand. The power is cut
+    /// asynchronously, so the caller should not expect to continue for long after
+    /// this returned successfully.
+    ///
+    /// [`I2c`]: ../i2c/struct.I2c.html
+    pub fn request_power_off(&self) -> Result<(), Error> {
+        self.i2c.write_byte(
+            self.address,
+            MAX77620_REG_ONOFFCNFG1,
+            MAX77620_ONOFFCNFG1_PWR_OFF,
+        )?;
+
+        Ok(())
+    }
+}
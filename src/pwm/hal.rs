@@ -1,9 +1,15 @@
+use core::convert::Infallible;
+
 use embedded_hal::PwmPin;
+use embedded_hal_1::pwm::{ErrorType, SetDutyCycle};
 
 use super::PwmChannel;
 
+/// The number of discrete pulse width steps supported by the hardware.
+const DUTY_STEPS: u16 = 256;
+
 impl PwmPin for PwmChannel {
-    type Duty = f32;
+    type Duty = u16;
 
     fn disable(&mut self) {
         PwmChannel::disable(self);
@@ -13,16 +19,34 @@ impl PwmPin for PwmChannel {
         PwmChannel::enable(self);
     }
 
-    fn get_duty(&self) -> f32 {
-        PwmChannel::get_duty(self)
+    fn get_duty(&self) -> u16 {
+        (PwmChannel::get_duty(self) * DUTY_STEPS as f32) as u16
     }
 
-    fn get_max_duty(&self) -> f32 {
-        PwmChannel::get_max_duty(self)
+    fn get_max_duty(&self) -> u16 {
+        (PwmChannel::get_max_duty(self) * DUTY_STEPS as f32) as u16
     }
 
     #[allow(unused_must_use)]
-    fn set_duty(&mut self, duty: f32) {
-        PwmChannel::set_pulse_width(self, duty);
+    fn set_duty(&mut self, duty: u16) {
+        PwmChannel::set_pulse_width(self, duty as f32 / DUTY_STEPS as f32);
+    }
+}
+
+impl ErrorType for PwmChannel {
+    type Error = Infallible;
+}
+
+impl SetDutyCycle for PwmChannel {
+    fn max_duty_cycle(&self) -> u16 {
+        (PwmChannel::get_max_duty(self) * DUTY_STEPS as f32) as u16
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        // Clamp the duty cycle so the conversion always yields a valid pulse width.
+        let duty = duty.min(self.max_duty_cycle());
+        PwmChannel::set_pulse_width(self, duty as f32 / DUTY_STEPS as f32).unwrap();
+
+        Ok(())
     }
 }
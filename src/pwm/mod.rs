@@ -13,9 +13,28 @@ mod registers;
 #[cfg(feature = "hal")]
 mod hal;
 
+use core::sync::atomic::{AtomicBool, Ordering};
+
 pub use crate::pwm::registers::*;
 use tock_registers::interfaces::*;
 
+/// The pulse width value which corresponds to a duty cycle of 100%.
+const PULSE_WIDTH_MAX: u32 = 256;
+
+/// Whether the outputs of the PWM channels 0 through 3 are inverted.
+///
+/// The controller has no hardware polarity control, so inversion is emulated by
+/// programming the complementary pulse width. This is kept outside of
+/// [`PwmChannel`] so that all copies of the public constants agree on it.
+///
+/// [`PwmChannel`]: struct.PwmChannel.html
+static INVERTED: [AtomicBool; 4] = [
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+];
+
 /// Representation of a Pulse Width Modulator channel.
 ///
 /// NOTE: It is expected that the PWM [`Clock`] is brought up before using any of the functionality
@@ -24,6 +43,8 @@ use tock_registers::interfaces::*;
 /// [`Clock`]: ../car/struct.Clock.html
 #[derive(Debug)]
 pub struct PwmChannel {
+    // The index of the channel into the table of output polarities.
+    index: usize,
     // A pointer to the PWM device registers in memory.
     registers: *const Registers,
 }
@@ -32,26 +53,50 @@ pub struct PwmChannel {
 impl PwmChannel {
     /// Representation of the PWM 0 channel.
     pub const PWM_0: Self = PwmChannel {
+        index: 0,
         registers: PWM_0_REGISTERS,
     };
 
     /// Representation of the PWM 1 channel.
     pub const PWM_1: Self = PwmChannel {
+        index: 1,
         registers: PWM_1_REGISTERS,
     };
 
     /// Representation of the PWM 2 channel.
     pub const PWM_2: Self = PwmChannel {
+        index: 2,
         registers: PWM_2_REGISTERS,
     };
 
     /// Representation of the PWM 3 channel.
     pub const PWM_3: Self = PwmChannel {
+        index: 3,
         registers: PWM_3_REGISTERS,
     };
 }
 
 impl PwmChannel {
+    fn is_inverted(&self) -> bool {
+        INVERTED[self.index].load(Ordering::Relaxed)
+    }
+
+    fn read_pulse_width(&self) -> u32 {
+        let controller = unsafe { &*self.registers };
+
+        controller
+            .PWM_CONTROLLER_PWM_CSR_0
+            .read(PWM_CONTROLLER_PWM_CSR_0::PWM_0)
+    }
+
+    fn write_pulse_width(&self, pulse_width: u32) {
+        let controller = unsafe { &*self.registers };
+
+        controller
+            .PWM_CONTROLLER_PWM_CSR_0
+            .modify(PWM_CONTROLLER_PWM_CSR_0::PWM_0.val(pulse_width));
+    }
+
     /// Enables pulse generation mechanism of this channel.
     pub fn enable(&self) {
         let controller = unsafe { &*self.registers };
@@ -70,20 +115,88 @@ impl PwmChannel {
             .modify(PWM_CONTROLLER_PWM_CSR_0::ENB::CLEAR);
     }
 
-    /// Configures the pulse width of the channel.
+    /// Configures the output frequency of the channel.
     ///
-    /// The argument is the desired duty cycle as a float value, representing a percentage
-    /// ranging from 0.0 (0%) to 1.0 (100%).
-    pub fn set_pulse_width(&self, duty: f32) -> Result<(), ()> {
+    /// The PWM output frequency is derived from the PWM source clock of the [`Clock`] as
+    /// `pwm_clock_hz / (256 * (PFM + 1))`, where `PFM` is the 13-bit frequency divider that
+    /// is computed and programmed by this method. `pwm_clock_hz` must be the rate that the
+    /// PWM source clock is currently running at.
+    ///
+    /// Returns an error if `target_hz` cannot be represented with the given source clock.
+    ///
+    /// [`Clock`]: ../car/struct.Clock.html
+    pub fn set_frequency(&self, pwm_clock_hz: u32, target_hz: u32) -> Result<(), ()> {
         let controller = unsafe { &*self.registers };
 
+        let divider = Self::compute_divider(pwm_clock_hz, target_hz)?;
+        controller
+            .PWM_CONTROLLER_PWM_CSR_0
+            .modify(PWM_CONTROLLER_PWM_CSR_0::PFM.val(divider));
+
+        Ok(())
+    }
+
+    fn compute_divider(pwm_clock_hz: u32, target_hz: u32) -> Result<u32, ()> {
+        if target_hz == 0 {
+            return Err(());
+        }
+
+        // Calculate the frequency divider, each PWM period spans 256 source clock cycles.
+        let divider = pwm_clock_hz / 256 / target_hz;
+        if divider == 0 || divider > PWM_CONTROLLER_PWM_CSR_0::PFM.mask + 1 {
+            return Err(());
+        }
+
+        Ok(divider - 1)
+    }
+
+    fn compute_pulse_width(&self, duty: f32) -> Result<u32, ()> {
         if !(0.0..=1.0).contains(&duty) {
             return Err(());
         }
 
-        controller
-            .PWM_CONTROLLER_PWM_CSR_0
-            .modify(PWM_CONTROLLER_PWM_CSR_0::PWM_0.val((duty * 256.0) as u32));
+        let pulse_width = (duty * PULSE_WIDTH_MAX as f32) as u32;
+        if self.is_inverted() {
+            Ok(PULSE_WIDTH_MAX - pulse_width)
+        } else {
+            Ok(pulse_width)
+        }
+    }
+
+    /// Configures the output frequency and the pulse width of the channel at once.
+    ///
+    /// This works like [`PwmChannel::set_frequency`] followed by
+    /// [`PwmChannel::set_pulse_width`], but both settings are validated upfront and
+    /// committed with a single register write, so no glitch can be observed on the
+    /// output in between. Nothing is changed when an error is returned.
+    ///
+    /// [`PwmChannel::set_frequency`]: #method.set_frequency
+    /// [`PwmChannel::set_pulse_width`]: #method.set_pulse_width
+    pub fn apply(&self, pwm_clock_hz: u32, target_hz: u32, duty: f32) -> Result<(), ()> {
+        let controller = unsafe { &*self.registers };
+
+        let divider = Self::compute_divider(pwm_clock_hz, target_hz)?;
+        let pulse_width = self.compute_pulse_width(duty)?;
+        controller.PWM_CONTROLLER_PWM_CSR_0.modify(
+            PWM_CONTROLLER_PWM_CSR_0::PFM.val(divider)
+                + PWM_CONTROLLER_PWM_CSR_0::PWM_0.val(pulse_width),
+        );
+
+        Ok(())
+    }
+
+    /// Configures the pulse width of the channel.
+    ///
+    /// The argument is the desired duty cycle as a float value, representing a percentage
+    /// ranging from 0.0 (0%) to 1.0 (100%).
+    ///
+    /// When the output is inverted through [`PwmChannel::set_inverted`], the duty cycle
+    /// denotes the fraction of the period during which the output is low.
+    ///
+    /// [`PwmChannel::set_inverted`]: #method.set_inverted
+    pub fn set_pulse_width(&self, duty: f32) -> Result<(), ()> {
+        let pulse_width = self.compute_pulse_width(duty)?;
+        self.write_pulse_width(pulse_width);
 
         Ok(())
     }
@@ -92,12 +205,27 @@ impl PwmChannel {
     ///
     /// The returned duty cycle is a float value from 0.0 (0%) to 1.0 (100%).
     pub fn get_duty(&self) -> f32 {
-        let controller = unsafe { &*self.registers };
+        let mut pulse_width = self.read_pulse_width();
+        if self.is_inverted() {
+            pulse_width = PULSE_WIDTH_MAX.saturating_sub(pulse_width);
+        }
 
-        let pulse_width = controller
-            .PWM_CONTROLLER_PWM_CSR_0
-            .read(PWM_CONTROLLER_PWM_CSR_0::PWM_0) as f32;
-        pulse_width / 256.0
+        pulse_width as f32 / PULSE_WIDTH_MAX as f32
+    }
+
+    /// Configures whether the output of the channel is active-low.
+    ///
+    /// The controller has no hardware polarity control, so this is done by programming
+    /// the complementary pulse width. The currently configured duty cycle is preserved
+    /// and converted to the new polarity.
+    pub fn set_inverted(&self, invert: bool) {
+        // Plain loads and stores are used since the BPMP has no atomic swap.
+        if self.is_inverted() != invert {
+            INVERTED[self.index].store(invert, Ordering::Relaxed);
+
+            let pulse_width = self.read_pulse_width();
+            self.write_pulse_width(PULSE_WIDTH_MAX.saturating_sub(pulse_width));
+        }
     }
 
     /// Returns the max duty cycle that is possible to set.
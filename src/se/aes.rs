@@ -1,11 +1,12 @@
-use byteorder::{ByteOrder, LE};
+use byteorder::{ByteOrder, BE, LE};
 use tock_registers::{fields::FieldValue, interfaces::*};
 
-use crate::arm;
 use crate::se::constants::*;
 use crate::se::core::*;
 use crate::se::registers::*;
-use crate::se::utils::trigger_single_block_operation;
+use crate::se::utils::{
+    trigger_buffer_operation, trigger_in_place_operation, trigger_single_block_operation,
+};
 
 macro_rules! init_aes {
     ($registers:ident, $encrypt:expr, $dest:ident) => {
@@ -50,6 +51,25 @@ pub enum Mode {
 }
 
 impl Mode {
+    /// Gets the AES mode that corresponds to a key of `len` bytes, if any.
+    pub fn from_key_size(len: usize) -> Option<Self> {
+        match len {
+            aes::KEY_SIZE_128 => Some(Mode::Aes128),
+            aes::KEY_SIZE_192 => Some(Mode::Aes192),
+            aes::KEY_SIZE_256 => Some(Mode::Aes256),
+            _ => None,
+        }
+    }
+
+    /// Gets the size of the keys used by this mode in bytes.
+    pub fn key_size(self) -> usize {
+        match self {
+            Mode::Aes128 => aes::KEY_SIZE_128,
+            Mode::Aes192 => aes::KEY_SIZE_192,
+            Mode::Aes256 => aes::KEY_SIZE_256,
+        }
+    }
+
     pub(crate) fn get_field_value(self) -> FieldValue<u32, SE_CONFIG_0::Register> {
         match self {
             Mode::Aes128 => SE_CONFIG_0::ENC_MODE::Aes128 + SE_CONFIG_0::DEC_MODE::Aes128,
@@ -59,6 +79,56 @@ impl Mode {
     }
 }
 
+/// The portion of an AES-CTR IV that is incremented as the counter.
+///
+/// The counter always occupies the least significant bits of the big-endian IV,
+/// the remaining bits form a nonce that is left untouched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CounterWidth {
+    /// The low 32 bits form the counter, wrapping around without carrying into the nonce.
+    Bits32,
+    /// The low 64 bits form the counter, wrapping around without carrying into the nonce.
+    Bits64,
+    /// The whole 128-bit IV forms the counter.
+    Bits128,
+}
+
+impl CounterWidth {
+    /// Gets the size of the counter in bytes.
+    fn size(self) -> usize {
+        match self {
+            CounterWidth::Bits32 => 4,
+            CounterWidth::Bits64 => 8,
+            CounterWidth::Bits128 => aes::BLOCK_SIZE,
+        }
+    }
+
+    /// Gets the number of blocks that can be processed with `counter` before
+    /// the counter portion wraps around, if it can wrap into a nonce at all.
+    fn blocks_until_wrap(self, counter: &[u8; aes::BLOCK_SIZE]) -> Option<u64> {
+        if self == CounterWidth::Bits128 {
+            return None;
+        }
+
+        let size = self.size();
+        let value = BE::read_uint128(&counter[aes::BLOCK_SIZE - size..], size);
+        let blocks = (1u128 << (size * 8)) - value;
+
+        Some(blocks.min(u64::MAX as u128) as u64)
+    }
+
+    /// Advances the counter portion of `counter` by `blocks`, wrapping around
+    /// within its width.
+    fn advance(self, counter: &mut [u8; aes::BLOCK_SIZE], blocks: u64) {
+        let size = self.size();
+        let portion = &mut counter[aes::BLOCK_SIZE - size..];
+
+        let mask = u128::MAX >> ((aes::BLOCK_SIZE - size) * 8);
+        let value = BE::read_uint128(portion, size).wrapping_add(blocks as u128) & mask;
+        BE::write_uint128(portion, value, size);
+    }
+}
+
 #[inline(always)]
 fn configure_aes_cmac(regs: &Registers, slot: u32, enc: bool) {
     aes_config!(regs, slot, enc, CLEAR, InitAesOut, Memory, Top, SET);
@@ -218,17 +288,8 @@ pub fn set_encrypted_key(
             + SE_CRYPTO_KEYTABLE_DST_0::DST_WORD_QUAD::Keys03,
     );
 
-    // Ensure cache coherency so the SE sees the correct data.
-    unsafe {
-        arm::cache::flush_data_cache(key, key.len());
-        #[cfg(target_arch = "aarch64")]
-        cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::ISH);
-    }
-
-    // Prepare the linked lists and kick off the operation.
-    let source_ll = LinkedList::from(key);
-    let mut destination_ll = LinkedList::default();
-    start_normal_operation(registers, &source_ll, &mut destination_ll)
+    // Kick off the operation.
+    trigger_buffer_operation(registers, key, &mut [])
 }
 
 pub fn do_cmac_operation(
@@ -271,10 +332,8 @@ pub fn do_cmac_operation(
         // Load in the number of blocks to generate.
         registers.SE_CRYPTO_LAST_BLOCK_0.set((nblocks - 1) as u32);
 
-        // Prepare the linked lists and kick off the operation.
-        let source_ll = LinkedList::from(&source[..aligned_size]);
-        let mut destination_ll = LinkedList::default();
-        start_normal_operation(registers, &source_ll, &mut destination_ll)?;
+        // Kick off the operation.
+        trigger_buffer_operation(registers, &source[..aligned_size], &mut [])?;
 
         // Select the updated IV value after that.
         registers
@@ -303,17 +362,8 @@ pub fn do_cmac_operation(
             *x ^= *y;
         }
 
-        // Ensure data cache coherency to get the correct output data.
-        unsafe {
-            arm::cache::flush_data_cache(&last_block[..], last_block.len());
-            #[cfg(target_arch = "aarch64")]
-            cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::ISH);
-        }
-
-        // Prepare the linked lists and kick off the operation.
-        let source_ll = LinkedList::from(&last_block[..]);
-        let mut destination_ll = LinkedList::default();
-        start_normal_operation(registers, &source_ll, &mut destination_ll)?;
+        // Kick off the operation.
+        trigger_buffer_operation(registers, &last_block, &mut [])?;
     }
 
     // Copy back the output into the destination buffer.
@@ -333,27 +383,50 @@ pub fn do_ecb_operation(
     // Configure an AES-ECB operation to memory.
     init_aes!(registers, encrypt, Memory);
     configure_aes_ecb(registers, slot, encrypt);
-    if encrypt {
-        registers.SE_CONFIG_0.modify(mode.get_field_value());
-    }
+    registers.SE_CONFIG_0.modify(mode.get_field_value());
 
     // Execute the operation.
     trigger_single_block_operation(registers, &source[..], &mut destination[..])
 }
 
-pub fn do_cbc_operation(
+pub fn do_ecb_buffer_operation(
     registers: &Registers,
     encrypt: bool,
     slot: u32,
     source: &[u8],
     destination: &mut [u8],
-    iv: &[u8; aes::BLOCK_SIZE],
     mode: Mode,
 ) -> Result<(), OperationError> {
-    // Determine the amount of blocks to generate.
-    let nblocks = source.len() / aes::BLOCK_SIZE;
-    let aligned_size = nblocks * aes::BLOCK_SIZE;
+    // Configure an AES-ECB operation to memory.
+    init_aes!(registers, encrypt, Memory);
+    configure_aes_ecb(registers, slot, encrypt);
+    registers.SE_CONFIG_0.modify(mode.get_field_value());
+
+    // Split the buffers into the largest chunks a single operation can process.
+    let chunk_size = aes::MAX_BLOCKS_PER_OPERATION * aes::BLOCK_SIZE;
+    for (source, destination) in source
+        .chunks(chunk_size)
+        .zip(destination.chunks_mut(chunk_size))
+    {
+        // Load in the number of blocks to process.
+        let nblocks = source.len() / aes::BLOCK_SIZE;
+        registers.SE_CRYPTO_LAST_BLOCK_0.set((nblocks - 1) as u32);
+
+        // Kick off the operation.
+        trigger_buffer_operation(registers, source, destination)?;
+    }
+
+    Ok(())
+}
 
+fn prepare_cbc_operation(
+    registers: &Registers,
+    encrypt: bool,
+    slot: u32,
+    nblocks: usize,
+    iv: &[u8; aes::BLOCK_SIZE],
+    mode: Mode,
+) {
     // Configure an AES-CBC operation to memory.
     init_aes!(registers, encrypt, Memory);
     if encrypt {
@@ -368,14 +441,9 @@ pub fn do_cbc_operation(
 
     // Load in the number of blocks to process.
     registers.SE_CRYPTO_LAST_BLOCK_0.set((nblocks - 1) as u32);
-
-    // Prepare the linked lists and kick off the operation.
-    let source_ll = LinkedList::from(&source[..aligned_size]);
-    let mut destination_ll = LinkedList::from(destination as &[u8]);
-    start_normal_operation(registers, &source_ll, &mut destination_ll)
 }
 
-pub fn do_ctr_operation(
+pub fn do_cbc_operation(
     registers: &Registers,
     encrypt: bool,
     slot: u32,
@@ -388,6 +456,37 @@ pub fn do_ctr_operation(
     let nblocks = source.len() / aes::BLOCK_SIZE;
     let aligned_size = nblocks * aes::BLOCK_SIZE;
 
+    prepare_cbc_operation(registers, encrypt, slot, nblocks, iv, mode);
+
+    // Kick off the operation.
+    trigger_buffer_operation(registers, &source[..aligned_size], destination)
+}
+
+pub fn do_cbc_in_place_operation(
+    registers: &Registers,
+    encrypt: bool,
+    slot: u32,
+    buffer: &mut [u8],
+    iv: &[u8; aes::BLOCK_SIZE],
+    mode: Mode,
+) -> Result<(), OperationError> {
+    // Determine the amount of blocks to generate.
+    let nblocks = buffer.len() / aes::BLOCK_SIZE;
+    let aligned_size = nblocks * aes::BLOCK_SIZE;
+
+    prepare_cbc_operation(registers, encrypt, slot, nblocks, iv, mode);
+
+    // Kick off the operation.
+    trigger_in_place_operation(registers, &mut buffer[..aligned_size])
+}
+
+fn prepare_ctr_operation(
+    registers: &Registers,
+    encrypt: bool,
+    slot: u32,
+    iv: &[u8; aes::BLOCK_SIZE],
+    mode: Mode,
+) {
     // XXX: Nintendo does it. I have no idea why this needs to happen.
     registers.SE_SPARE_0.set(1);
 
@@ -398,22 +497,34 @@ pub fn do_ctr_operation(
 
     // Initialize the counter.
     set_counter(registers, iv);
+}
+
+pub fn do_ctr_operation(
+    registers: &Registers,
+    encrypt: bool,
+    slot: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    iv: &[u8; aes::BLOCK_SIZE],
+    mode: Mode,
+) -> Result<(), OperationError> {
+    // Determine the amount of blocks to generate.
+    let nblocks = source.len() / aes::BLOCK_SIZE;
+    let aligned_size = nblocks * aes::BLOCK_SIZE;
+
+    prepare_ctr_operation(registers, encrypt, slot, iv, mode);
 
     // Process all aligned blocks first.
     if aligned_size > 0 {
         // Load in the number of blocks to process.
         registers.SE_CRYPTO_LAST_BLOCK_0.set((nblocks - 1) as u32);
 
-        // Prepare the linked lists and kick off the operation.
-        let source_ll = LinkedList::from(&source[..aligned_size]);
-        let mut destination_ll = LinkedList::from(&destination[..aligned_size]);
-        start_normal_operation(registers, &source_ll, &mut destination_ll)?;
-
-        // On AArch64, synchronize the data between the SE and the CPU.
-        #[cfg(target_arch = "aarch64")]
-        unsafe {
-            cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::ISH);
-        }
+        // Kick off the operation.
+        trigger_buffer_operation(
+            registers,
+            &source[..aligned_size],
+            &mut destination[..aligned_size],
+        )?;
     }
 
     // Process the last unaligned block, if necessary.
@@ -423,3 +534,160 @@ pub fn do_ctr_operation(
         &mut destination[aligned_size..],
     )
 }
+
+pub fn do_ctr_operation_with_width(
+    registers: &Registers,
+    slot: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    iv: &[u8; aes::BLOCK_SIZE],
+    mode: Mode,
+    width: CounterWidth,
+) -> Result<(), OperationError> {
+    let mut counter = *iv;
+    let mut offset = 0;
+
+    // The hardware always increments the whole IV, so the data is split up at
+    // the points where the counter portion would carry into the nonce.
+    while offset < source.len() {
+        let remaining = source.len() - offset;
+        let size = match width.blocks_until_wrap(&counter) {
+            Some(blocks) if blocks < remaining.div_ceil(aes::BLOCK_SIZE) as u64 => {
+                blocks as usize * aes::BLOCK_SIZE
+            }
+            _ => remaining,
+        };
+
+        do_ctr_operation(
+            registers,
+            true,
+            slot,
+            &source[offset..offset + size],
+            &mut destination[offset..offset + size],
+            &counter,
+            mode,
+        )?;
+
+        width.advance(&mut counter, (size / aes::BLOCK_SIZE) as u64);
+        offset += size;
+    }
+
+    Ok(())
+}
+
+pub fn do_ctr_in_place_operation(
+    registers: &Registers,
+    encrypt: bool,
+    slot: u32,
+    buffer: &mut [u8],
+    iv: &[u8; aes::BLOCK_SIZE],
+    mode: Mode,
+) -> Result<(), OperationError> {
+    // Determine the amount of blocks to generate.
+    let nblocks = buffer.len() / aes::BLOCK_SIZE;
+    let aligned_size = nblocks * aes::BLOCK_SIZE;
+
+    prepare_ctr_operation(registers, encrypt, slot, iv, mode);
+
+    // Process all aligned blocks first.
+    if aligned_size > 0 {
+        // Load in the number of blocks to process.
+        registers.SE_CRYPTO_LAST_BLOCK_0.set((nblocks - 1) as u32);
+
+        // Kick off the operation.
+        trigger_in_place_operation(registers, &mut buffer[..aligned_size])?;
+    }
+
+    // Process the last unaligned block, if necessary. The tail is copied out
+    // first since the single block operation needs distinct buffers.
+    let tail = &mut buffer[aligned_size..];
+    let mut block = [0; aes::BLOCK_SIZE];
+    block[..tail.len()].copy_from_slice(tail);
+    trigger_single_block_operation(registers, &block[..tail.len()], tail)
+}
+
+pub fn do_cbc_cts_operation(
+    registers: &Registers,
+    encrypt: bool,
+    slot: u32,
+    source: &[u8],
+    destination: &mut [u8],
+    iv: &[u8; aes::BLOCK_SIZE],
+    mode: Mode,
+) -> Result<(), OperationError> {
+    // Determine the amount of blocks and the size of the final, partial block.
+    let nblocks = source.len().div_ceil(aes::BLOCK_SIZE);
+    if nblocks == 1 {
+        return do_cbc_operation(registers, encrypt, slot, source, destination, iv, mode);
+    }
+    let head_size = (nblocks - 2) * aes::BLOCK_SIZE;
+    let tail_size = source.len() - (nblocks - 1) * aes::BLOCK_SIZE;
+    let (tail_start, tail_end) = (head_size + aes::BLOCK_SIZE, source.len());
+
+    // Process all blocks before the last two in plain CBC mode.
+    let mut iv = *iv;
+    if head_size > 0 {
+        do_cbc_operation(
+            registers,
+            encrypt,
+            slot,
+            &source[..head_size],
+            &mut destination[..head_size],
+            &iv,
+            mode,
+        )?;
+
+        // Chain the last ciphertext block into the remaining ones.
+        let chain = if encrypt { &*destination } else { source };
+        iv.copy_from_slice(&chain[head_size - aes::BLOCK_SIZE..head_size]);
+    }
+
+    let mut stolen = [0; aes::BLOCK_SIZE];
+    let mut block = [0; aes::BLOCK_SIZE];
+    if encrypt {
+        // Encrypt the second to last block, whose ciphertext is partially stolen.
+        do_cbc_operation(
+            registers,
+            true,
+            slot,
+            &source[head_size..tail_start],
+            &mut stolen,
+            &iv,
+            mode,
+        )?;
+
+        // Encrypt the zero-padded final block, chained to the stolen ciphertext.
+        let mut last = [0; aes::BLOCK_SIZE];
+        last[..tail_size].copy_from_slice(&source[tail_start..tail_end]);
+        do_cbc_operation(registers, true, slot, &last, &mut block, &stolen, mode)?;
+
+        // Swap the last two blocks and truncate the stolen one.
+        destination[head_size..tail_start].copy_from_slice(&block);
+        destination[tail_start..tail_end].copy_from_slice(&stolen[..tail_size]);
+    } else {
+        // Decrypt the full final block, which yields the padded final plaintext
+        // XORed with the stolen ciphertext.
+        do_cbc_operation(
+            registers,
+            false,
+            slot,
+            &source[head_size..tail_start],
+            &mut block,
+            &[0; aes::BLOCK_SIZE],
+            mode,
+        )?;
+
+        // Reconstruct the stolen ciphertext and recover the final plaintext.
+        stolen.copy_from_slice(&block);
+        stolen[..tail_size].copy_from_slice(&source[tail_start..tail_end]);
+        for (i, byte) in destination[tail_start..tail_end].iter_mut().enumerate() {
+            *byte = block[i] ^ stolen[i];
+        }
+
+        // Decrypt the second to last block from the reconstructed ciphertext.
+        do_cbc_operation(registers, false, slot, &stolen, &mut block, &iv, mode)?;
+        destination[head_size..tail_start].copy_from_slice(&block);
+    }
+
+    Ok(())
+}
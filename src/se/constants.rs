@@ -28,6 +28,32 @@ pub mod aes {
     pub const MAX_KEY_SIZE: usize = 32;
 
     pub const IV_SIZE: usize = 16;
+
+    pub const MAX_BLOCKS_PER_OPERATION: usize = 1 << 20;
+}
+
+/// Layout of a saved Security Engine context.
+pub mod context {
+    use super::{aes, rsa};
+
+    pub const RANDOM_SIZE: usize = 16;
+    pub const STICKY_BITS_SIZE: usize = 32;
+    pub const AES_KEYSLOT_SIZE: usize = 4 * aes::BLOCK_SIZE;
+    pub const RSA_KEYSLOT_SIZE: usize = 2 * rsa::SIZE;
+
+    pub const STICKY_BITS_OFFSET: usize = RANDOM_SIZE;
+    pub const AES_KEYSLOTS_OFFSET: usize = STICKY_BITS_OFFSET + STICKY_BITS_SIZE;
+    pub const RSA_KEYSLOTS_OFFSET: usize =
+        AES_KEYSLOTS_OFFSET + AES_KEYSLOT_SIZE * aes::KEY_SLOT_COUNT;
+    pub const KNOWN_PATTERN_OFFSET: usize =
+        RSA_KEYSLOTS_OFFSET + RSA_KEYSLOT_SIZE * rsa::KEY_SLOT_COUNT;
+
+    pub const KNOWN_PATTERN: [u8; 16] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E,
+        0x0F,
+    ];
+
+    pub const SIZE: usize = KNOWN_PATTERN_OFFSET + KNOWN_PATTERN.len();
 }
 
 /// Data sizes related to RNG.
@@ -41,6 +67,31 @@ pub mod rng {
     pub const RESEED_INTERVAL: u32 = 70_000 + 1;
 }
 
+/// Initial hash values of the truncated SHA-512 variants (FIPS 180-4, 5.3.6).
+pub mod sha {
+    pub const SHA512_224_IV: [u64; 8] = [
+        0x8C3D37C819544DA2,
+        0x73E1996689DCD4D6,
+        0x1DFAB7AE32FF9C82,
+        0x679DD514582F9FCF,
+        0x0F6D2B697BD44DA8,
+        0x77E36F7304C48942,
+        0x3F9D85A86A1D36C8,
+        0x1112E6AD91D692A1,
+    ];
+
+    pub const SHA512_256_IV: [u64; 8] = [
+        0x22312194FC2BF72C,
+        0x9F555FA3C84C64C2,
+        0x2393B86B6F53B151,
+        0x963877195940EABD,
+        0x96283EE2A88EFFE3,
+        0xBE5E1E2553863992,
+        0x2B0199FC2C85B8AA,
+        0x0EB72DDC81C52CA2,
+    ];
+}
+
 /// Data sizes related to RSA.
 pub mod rsa {
     pub const KEY_SLOT_PART_COUNT: usize = 2;
@@ -0,0 +1,294 @@
+use core::convert::TryInto;
+
+use byteorder::{ByteOrder, LE};
+use tock_registers::{fields::FieldValue, interfaces::*};
+
+use crate::pmc;
+use crate::se::aes::{clear_key_iv, clear_keyslot, do_ecb_operation, set_key, Mode};
+use crate::se::constants::*;
+use crate::se::core::*;
+use crate::se::registers::*;
+use crate::se::rng;
+use crate::se::utils::{flush_buffer, flush_buffers, verify_bytes};
+
+/// The AES keyslot that temporarily holds the SRK while a context is restored.
+const SRK_SCRATCH_KEYSLOT: u32 = aes::KEY_SLOT_COUNT as u32 - 1;
+
+fn save_blocks(
+    registers: &Registers,
+    config: FieldValue<u32, SE_CTX_SAVE_CONFIG_0::Register>,
+    source: &[u8],
+    destination: &mut [u8],
+) -> Result<(), OperationError> {
+    // Select the data to be saved.
+    registers.SE_CTX_SAVE_CONFIG_0.write(config);
+
+    // Load in the number of blocks to process.
+    let nblocks = (destination.len() / aes::BLOCK_SIZE).max(1);
+    registers.SE_CRYPTO_LAST_BLOCK_0.set((nblocks - 1) as u32);
+
+    // Make the data coherent so it is seen correctly by the SE.
+    flush_buffers(source, destination);
+
+    // Prepare the linked lists and kick off the operation.
+    let source_ll = if source.is_empty() {
+        LinkedList::default()
+    } else {
+        LinkedList::from(source)
+    };
+    let mut destination_ll = if destination.is_empty() {
+        LinkedList::default()
+    } else {
+        LinkedList::from(destination as &[u8])
+    };
+    start_context_save_operation(registers, &source_ll, &mut destination_ll)?;
+
+    // Ensure data cache coherency so that CPU sees the correct data.
+    flush_buffer(destination);
+
+    Ok(())
+}
+
+pub fn save(registers: &Registers, buffer: &mut [u8]) -> Result<(), OperationError> {
+    // Generate a fresh SRK to encrypt the context with.
+    rng::generate_srk(registers)?;
+
+    // Generate the random block that leads the encrypted context.
+    let mut random = [0; context::RANDOM_SIZE];
+    rng::generate_random(registers, &mut random)?;
+
+    // Configure the hardware to encrypt the context into memory.
+    registers.SE_CONFIG_0.write(
+        SE_CONFIG_0::ENC_MODE::Aes128
+            + SE_CONFIG_0::DEC_MODE::Aes128
+            + SE_CONFIG_0::ENC_ALG::Aes
+            + SE_CONFIG_0::DEC_ALG::Nop
+            + SE_CONFIG_0::DESTINATION::Memory,
+    );
+
+    // Save the random block.
+    save_blocks(
+        registers,
+        SE_CTX_SAVE_CONFIG_0::SOURCE::Mem,
+        &random,
+        &mut buffer[..context::STICKY_BITS_OFFSET],
+    )?;
+
+    // Save the sticky bits.
+    let sticky_bits = &mut buffer[context::STICKY_BITS_OFFSET..context::AES_KEYSLOTS_OFFSET];
+    for (quad, block) in sticky_bits.chunks_mut(aes::BLOCK_SIZE).enumerate() {
+        save_blocks(
+            registers,
+            SE_CTX_SAVE_CONFIG_0::SOURCE::StickyBits
+                + SE_CTX_SAVE_CONFIG_0::STICKY_WORD_QUAD.val(quad as u32),
+            &[],
+            block,
+        )?;
+    }
+
+    // Save the keys and IVs of all AES keyslots.
+    let aes_keyslots = &mut buffer[context::AES_KEYSLOTS_OFFSET..context::RSA_KEYSLOTS_OFFSET];
+    for (slot, keyslot) in aes_keyslots
+        .chunks_mut(context::AES_KEYSLOT_SIZE)
+        .enumerate()
+    {
+        for (quad, block) in keyslot.chunks_mut(aes::BLOCK_SIZE).enumerate() {
+            save_blocks(
+                registers,
+                SE_CTX_SAVE_CONFIG_0::SOURCE::AesKeyTable
+                    + SE_CTX_SAVE_CONFIG_0::AES_KEY_INDEX.val(slot as u32)
+                    + SE_CTX_SAVE_CONFIG_0::AES_WORD_QUAD.val(quad as u32),
+                &[],
+                block,
+            )?;
+        }
+    }
+
+    // Save the exponents and moduli of all RSA keyslots.
+    let rsa_keyslots = &mut buffer[context::RSA_KEYSLOTS_OFFSET..context::KNOWN_PATTERN_OFFSET];
+    for (index, key) in rsa_keyslots.chunks_mut(rsa::SIZE).enumerate() {
+        for (quad, block) in key.chunks_mut(aes::BLOCK_SIZE).enumerate() {
+            save_blocks(
+                registers,
+                SE_CTX_SAVE_CONFIG_0::SOURCE::RsaKeyTable
+                    + SE_CTX_SAVE_CONFIG_0::RSA_KEY_INDEX.val(index as u32)
+                    + SE_CTX_SAVE_CONFIG_0::RSA_WORD_QUAD.val(quad as u32),
+                &[],
+                block,
+            )?;
+        }
+    }
+
+    // Save the known pattern that terminates the context.
+    save_blocks(
+        registers,
+        SE_CTX_SAVE_CONFIG_0::SOURCE::Mem,
+        &context::KNOWN_PATTERN,
+        &mut buffer[context::KNOWN_PATTERN_OFFSET..],
+    )?;
+
+    // Save the SRK into the PMC secure scratch registers.
+    save_blocks(registers, SE_CTX_SAVE_CONFIG_0::SOURCE::Srk, &[], &mut [])
+}
+
+fn load_srk(registers: &Registers, slot: u32) {
+    let pmc = unsafe { &*pmc::REGISTERS };
+
+    // Read the SRK back from the PMC secure scratch registers.
+    let mut srk = [0; aes::KEY_SIZE_128];
+    LE::write_u32(&mut srk[0x0..], pmc.APBDEV_PMC_SECURE_SCRATCH4_0.get());
+    LE::write_u32(&mut srk[0x4..], pmc.APBDEV_PMC_SECURE_SCRATCH5_0.get());
+    LE::write_u32(&mut srk[0x8..], pmc.APBDEV_PMC_SECURE_SCRATCH6_0.get());
+    LE::write_u32(&mut srk[0xC..], pmc.APBDEV_PMC_SECURE_SCRATCH7_0.get());
+
+    set_key(registers, slot, &srk);
+    srk.fill(0);
+}
+
+fn decrypt_block(
+    registers: &Registers,
+    slot: u32,
+    buffer: &[u8],
+    offset: usize,
+    output: &mut [u8; aes::BLOCK_SIZE],
+) -> Result<(), OperationError> {
+    let block = buffer[offset..offset + aes::BLOCK_SIZE].try_into().unwrap();
+    do_ecb_operation(registers, false, slot, block, output, Mode::Aes128)?;
+
+    // Undo the CBC chaining with the previous ciphertext block.
+    if offset != 0 {
+        let previous = &buffer[offset - aes::BLOCK_SIZE..offset];
+        for (byte, chained) in output.iter_mut().zip(previous) {
+            *byte ^= chained;
+        }
+    }
+
+    Ok(())
+}
+
+fn restore_aes_keyslot(registers: &Registers, slot: u32, keyslot: &[u8]) {
+    for (i, word) in keyslot.chunks(aes::BLOCK_SIZE >> 2).enumerate() {
+        // Select the next key or IV word in the keyslot.
+        registers.SE_CRYPTO_KEYTABLE_ADDR_0.write(
+            SE_CRYPTO_KEYTABLE_ADDR_0::KEYIV_KEY_SLOT.val(slot)
+                + SE_CRYPTO_KEYTABLE_ADDR_0::KEYIV_WORD.val(i as u32),
+        );
+
+        // Fill the word in the keyslot.
+        registers.SE_CRYPTO_KEYTABLE_DATA_0.set(LE::read_u32(word));
+    }
+}
+
+fn restore_keytables(
+    registers: &Registers,
+    slot: u32,
+    buffer: &[u8],
+    block: &mut [u8; aes::BLOCK_SIZE],
+    keyslot: &mut [u8; context::AES_KEYSLOT_SIZE],
+    srk_keyslot: &mut [u8; context::AES_KEYSLOT_SIZE],
+) -> Result<(), OperationError> {
+    // Ensure that the context decrypts correctly before touching the key tables.
+    decrypt_block(
+        registers,
+        slot,
+        buffer,
+        context::KNOWN_PATTERN_OFFSET,
+        block,
+    )?;
+    if !verify_bytes(block, &context::KNOWN_PATTERN) {
+        return Err(OperationError::MalformedBuffer);
+    }
+
+    // Decrypt the contents of the keyslot holding the SRK up front, so
+    // they can be restored after all other keyslots.
+    let srk_offset = context::AES_KEYSLOTS_OFFSET + slot as usize * context::AES_KEYSLOT_SIZE;
+    for (quad, chunk) in srk_keyslot.chunks_mut(aes::BLOCK_SIZE).enumerate() {
+        decrypt_block(
+            registers,
+            slot,
+            buffer,
+            srk_offset + quad * aes::BLOCK_SIZE,
+            chunk.try_into().unwrap(),
+        )?;
+    }
+
+    // Restore the keys and IVs of all remaining AES keyslots.
+    for i in (0..aes::KEY_SLOT_COUNT as u32).filter(|&i| i != slot) {
+        let offset = context::AES_KEYSLOTS_OFFSET + i as usize * context::AES_KEYSLOT_SIZE;
+        for (quad, chunk) in keyslot.chunks_mut(aes::BLOCK_SIZE).enumerate() {
+            decrypt_block(
+                registers,
+                slot,
+                buffer,
+                offset + quad * aes::BLOCK_SIZE,
+                chunk.try_into().unwrap(),
+            )?;
+        }
+
+        restore_aes_keyslot(registers, i, keyslot);
+    }
+
+    // Restore the exponents and moduli of all RSA keyslots.
+    for index in 0..(rsa::KEY_SLOT_COUNT * rsa::KEY_SLOT_PART_COUNT) as u32 {
+        let offset = context::RSA_KEYSLOTS_OFFSET + index as usize * rsa::SIZE;
+        for quad in 0..rsa::SIZE / aes::BLOCK_SIZE {
+            decrypt_block(
+                registers,
+                slot,
+                buffer,
+                offset + quad * aes::BLOCK_SIZE,
+                block,
+            )?;
+
+            for (i, word) in block.chunks(aes::BLOCK_SIZE >> 2).enumerate() {
+                // Select the keyslot word.
+                registers.SE_RSA_KEYTABLE_ADDR_0.write(
+                    SE_RSA_KEYTABLE_ADDR_0::INPUT_MODE::FromRegister
+                        + SE_RSA_KEYTABLE_ADDR_0::KEY_SLOT.val(index >> 1)
+                        + SE_RSA_KEYTABLE_ADDR_0::EXPMOD_SEL.val(index & 1)
+                        + SE_RSA_KEYTABLE_ADDR_0::WORD_ADDR.val((quad * 4 + i) as u32),
+                );
+
+                // Fill the word in the keyslot.
+                registers.SE_RSA_KEYTABLE_DATA_0.set(LE::read_u32(word));
+            }
+        }
+    }
+
+    // Finally, replace the SRK with the original contents of its keyslot.
+    restore_aes_keyslot(registers, slot, srk_keyslot);
+
+    Ok(())
+}
+
+pub fn restore(registers: &Registers, buffer: &[u8]) -> Result<(), OperationError> {
+    let slot = SRK_SCRATCH_KEYSLOT;
+    let mut block = [0; aes::BLOCK_SIZE];
+    let mut keyslot = [0; context::AES_KEYSLOT_SIZE];
+    let mut srk_keyslot = [0; context::AES_KEYSLOT_SIZE];
+
+    // Load the SRK that was used to encrypt the context.
+    load_srk(registers, slot);
+
+    let res = restore_keytables(
+        registers,
+        slot,
+        buffer,
+        &mut block,
+        &mut keyslot,
+        &mut srk_keyslot,
+    );
+
+    // Never leave the SRK behind in the keyslot when the restore failed.
+    if res.is_err() {
+        clear_keyslot(registers, slot);
+        clear_key_iv(registers, slot);
+    }
+
+    // Don't leave decrypted key material behind on the stack.
+    block.fill(0);
+    keyslot.fill(0);
+    srk_keyslot.fill(0);
+
+    res
+}
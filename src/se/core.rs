@@ -123,6 +123,10 @@ pub enum OperationError {
     /// A given source or destination buffer could not be used to construct a SE Linked List
     /// because it was malformed or had an incorrect size.
     MalformedBuffer,
+    /// An RSA signature did not match the padded digest of the signed message.
+    InvalidSignature,
+    /// An RSA key has a modulus or exponent size that is not supported by the SE.
+    InvalidKeySize,
 }
 
 /// Waits for the Security Engine to enter idle state before starting the next operation.
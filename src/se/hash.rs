@@ -35,10 +35,8 @@ macro_rules! gen_sha_impl {
                 init_sha!(engine, [<Sha $size>]);
                 hash::set_source_size(engine, source.len() as u32);
 
-                // Prepare the linked lists and kick off the operation.
-                let source_ll = LinkedList::from(source);
-                let mut destination_ll = LinkedList::default();
-                start_normal_operation(engine, &source_ll, &mut destination_ll)?;
+                // Kick off the operation.
+                utils::trigger_buffer_operation(engine, source, &mut [])?;
 
                 // Read and copy back the resulting hash.
                 hash::read_result(engine, output, true);
@@ -52,6 +50,49 @@ macro_rules! gen_sha_impl {
     };
 }
 
+macro_rules! gen_sha512_t_impl {
+    ($size:tt, $iv:expr) => {
+        ::paste::paste! {
+            #[doc = "Calculates a SHA512/" $size " hash over a given buffer of data."]
+            pub fn [<calculate_sha512_ $size>](
+                &self,
+                source: &[u8],
+                output: &mut [u8; $size >> 3],
+            ) -> Result<(), OperationError> {
+                let engine = unsafe { &*self.registers };
+
+                // Configure the hardware for SHA512 hashing from a custom initial hash.
+                init_sha!(engine, Sha512);
+                hash::set_initial_hash(engine, &$iv);
+                hash::set_source_size(engine, source.len() as u32);
+
+                // Kick off the operation.
+                utils::trigger_buffer_operation(engine, source, &mut [])?;
+
+                // Read and copy back the truncated hash.
+                hash::read_result(engine, output, true);
+
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Loads a SHA512 initial hash value into the result registers.
+///
+/// This disables the hardware-provided initial hash, so the engine continues
+/// from the given state instead.
+pub fn set_initial_hash(registers: &Registers, iv: &[u64; 8]) {
+    registers
+        .SE_SHA_CONFIG_0
+        .write(SE_SHA_CONFIG_0::HW_INIT_HASH::CLEAR);
+
+    for (i, word) in iv.iter().enumerate() {
+        registers.SE_HASH_RESULT_0[i << 1].set((word >> 32) as u32);
+        registers.SE_HASH_RESULT_0[(i << 1) + 1].set(*word as u32);
+    }
+}
+
 pub fn set_source_size(registers: &Registers, size: u32) {
     // Set the message size.
     registers.SE_SHA_MSG_LENGTH_0[0].set(size << 3);
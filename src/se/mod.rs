@@ -53,12 +53,30 @@
 //!
 //! - [`SecurityEngine::initialize_rng`]
 //!
+//! - [`SecurityEngine::reseed_rng`]
+//!
+//! - [`SecurityEngine::rng_healthy`]
+//!
 //! - [`SecurityEngine::generate_random`]
 //!
 //! - [`SecurityEngine::set_random_key`]
 //!
+//! - [`SecurityEngine::generate_ephemeral_key`]
+//!
 //! - [`SecurityEngine::generate_srk`]
 //!
+//! ## Context Save
+//!
+//! The contents of the key tables are lost when the Security Engine is powered down, for
+//! example across LP0 sleep cycles. To retain them, the full context can be saved to memory,
+//! encrypted with a Storage Root Key (SRK) that is generated for this purpose and held in
+//! the always-on PMC secure scratch registers. Only the key tables are restored, the sticky
+//! bits of the saved context are not.
+//!
+//! - [`SecurityEngine::save_context`]
+//!
+//! - [`SecurityEngine::restore_context`]
+//!
 //! ## AES
 //!
 //! The AES APIs of the Security Engine expose the primitives to do cryptographic operations
@@ -79,18 +97,36 @@
 //!
 //! - [`SecurityEngine::aes_cmac`]
 //!
+//! - [`SecurityEngine::aes_cmac_verify`]
+//!
 //! - [`SecurityEngine::aes_ecb_encrypt`]
 //!
 //! - [`SecurityEngine::aes_ecb_decrypt`]
 //!
+//! - [`SecurityEngine::aes_ecb_encrypt_buffer`]
+//!
+//! - [`SecurityEngine::aes_ecb_decrypt_buffer`]
+//!
 //! - [`SecurityEngine::aes_cbc_encrypt`]
 //!
 //! - [`SecurityEngine::aes_cbc_decrypt`]
 //!
+//! - [`SecurityEngine::aes_cbc_cts_encrypt`]
+//!
+//! - [`SecurityEngine::aes_cbc_cts_decrypt`]
+//!
 //! - [`SecurityEngine::aes_ctr_encrypt`]
 //!
 //! - [`SecurityEngine::aes_ctr_decrypt`]
 //!
+//! - [`SecurityEngine::aes_cbc_encrypt_in_place`]
+//!
+//! - [`SecurityEngine::aes_cbc_decrypt_in_place`]
+//!
+//! - [`SecurityEngine::aes_ctr_crypt_in_place`]
+//!
+//! - [`SecurityEngine::aes_ctr_crypt_with_width`]
+//!
 //! ## RSA
 //!
 //! Similarly to the AES APIs, the Security Engine also features asymmetric encryptions using
@@ -103,6 +139,8 @@
 //!
 //! - [`SecurityEngine::rsa_modular_exponentiate`]
 //!
+//! - [`SecurityEngine::rsa_verify_pkcs1_sha256`]
+//!
 //! ## Hashing
 //!
 //! The Security Engine supports various hashing algorithms from the SHA1 and SHA2 family
@@ -118,31 +156,52 @@
 //!
 //! - [`SecurityEngine::calculate_sha512`]
 //!
+//! - [`SecurityEngine::calculate_sha512_224`]
+//!
+//! - [`SecurityEngine::calculate_sha512_256`]
+//!
 //! [`trigger_operation`]: fn.trigger_operation.html
 //! [`SecurityEngine::initialize_rng`]: struct.SecurityEngine.html#method.initialize_rng
+//! [`SecurityEngine::reseed_rng`]: struct.SecurityEngine.html#method.reseed_rng
+//! [`SecurityEngine::rng_healthy`]: struct.SecurityEngine.html#method.rng_healthy
 //! [`SecurityEngine::generate_random`]: struct.SecurityEngine.html#method.generate_random
 //! [`SecurityEngine::set_random_key`]: struct.SecurityEngine.html#method.set_random_key
+//! [`SecurityEngine::generate_ephemeral_key`]: struct.SecurityEngine.html#method.generate_ephemeral_key
 //! [`SecurityEngine::generate_srk`]: struct.SecurityEngine.html#method.generate_srk
+//! [`SecurityEngine::save_context`]: struct.SecurityEngine.html#method.save_context
+//! [`SecurityEngine::restore_context`]: struct.SecurityEngine.html#method.restore_context
 //! [`SecurityEngine::fill_aes_keyslot`]: struct.SecurityEngine.html#method.fill_aes_keyslot
 //! [`SecurityEngine::get_aes_key`]: struct.SecurityEngine.html#method.get_aes_key
 //! [`SecurityEngine::clear_aes_keyslot`]: struct.SecurityEngine.html#method.clear_aes_keyslot
 //! [`SecurityEngine::clear_aes_key_iv`]: struct.SecurityEngine.html#method.clear_aes_key_iv
 //! [`SecurityEngine::set_encrypted_aes_key`]: struct.SecurityEngine.html#method.set_encrypted_aes_key
 //! [`SecurityEngine::aes_cmac`]: struct.SecurityEngine.html#method.aes_cmac
+//! [`SecurityEngine::aes_cmac_verify`]: struct.SecurityEngine.html#method.aes_cmac_verify
 //! [`SecurityEngine::aes_ecb_encrypt`]: struct.SecurityEngine.html#method.aes_ecb_encrypt
 //! [`SecurityEngine::aes_ecb_decrypt`]: struct.SecurityEngine.html#method.aes_ecb_decrypt
+//! [`SecurityEngine::aes_ecb_encrypt_buffer`]: struct.SecurityEngine.html#method.aes_ecb_encrypt_buffer
+//! [`SecurityEngine::aes_ecb_decrypt_buffer`]: struct.SecurityEngine.html#method.aes_ecb_decrypt_buffer
 //! [`SecurityEngine::aes_cbc_encrypt`]: struct.SecurityEngine.html#method.aes_cbc_encrypt
 //! [`SecurityEngine::aes_cbc_decrypt`]: struct.SecurityEngine.html#method.aes_cbc_decrypt
+//! [`SecurityEngine::aes_cbc_cts_encrypt`]: struct.SecurityEngine.html#method.aes_cbc_cts_encrypt
+//! [`SecurityEngine::aes_cbc_cts_decrypt`]: struct.SecurityEngine.html#method.aes_cbc_cts_decrypt
 //! [`SecurityEngine::aes_ctr_encrypt`]: struct.SecurityEngine.html#method.aes_ctr_encrypt
 //! [`SecurityEngine::aes_ctr_decrypt`]: struct.SecurityEngine.html#method.aes_ctr_decrypt
+//! [`SecurityEngine::aes_cbc_encrypt_in_place`]: struct.SecurityEngine.html#method.aes_cbc_encrypt_in_place
+//! [`SecurityEngine::aes_cbc_decrypt_in_place`]: struct.SecurityEngine.html#method.aes_cbc_decrypt_in_place
+//! [`SecurityEngine::aes_ctr_crypt_in_place`]: struct.SecurityEngine.html#method.aes_ctr_crypt_in_place
+//! [`SecurityEngine::aes_ctr_crypt_with_width`]: struct.SecurityEngine.html#method.aes_ctr_crypt_with_width
 //! [`SecurityEngine::fill_rsa_keyslot`]: struct.SecurityEngine.html#method.fill_rsa_keyslot
 //! [`SecurityEngine::clear_rsa_keyslot`]: struct.SecurityEngine.html#method.clear_rsa_keyslot
 //! [`SecurityEngine::rsa_modular_exponentiate`]: struct.SecurityEngine.html#method.rsa_modular_exponentiate
+//! [`SecurityEngine::rsa_verify_pkcs1_sha256`]: struct.SecurityEngine.html#method.rsa_verify_pkcs1_sha256
 //! [`SecurityEngine::calculate_sha1`]: struct.SecurityEngine.html#method.calculate_sha1
 //! [`SecurityEngine::calculate_sha224`]: struct.SecurityEngine.html#method.calculate_sha224
 //! [`SecurityEngine::calculate_sha256`]: struct.SecurityEngine.html#method.calculate_sha256
 //! [`SecurityEngine::calculate_sha384`]: struct.SecurityEngine.html#method.calculate_sha384
 //! [`SecurityEngine::calculate_sha512`]: struct.SecurityEngine.html#method.calculate_sha512
+//! [`SecurityEngine::calculate_sha512_224`]: struct.SecurityEngine.html#method.calculate_sha512_224
+//! [`SecurityEngine::calculate_sha512_256`]: struct.SecurityEngine.html#method.calculate_sha512_256
 //! [`SecurityEngine::lock`]: struct.SecurityEngine.html#method.lock
 //! [`SecurityEngine::unlock`]: struct.SecurityEngine.html#method.unlock
 //! [`SecurityEngine::lock_per_key`]: struct.SecurityEngine.html#method.lock_per_key
@@ -152,6 +211,7 @@
 mod aes;
 #[allow(dead_code)]
 mod constants;
+mod context;
 mod core;
 #[macro_use]
 mod hash;
@@ -166,8 +226,17 @@ use tock_registers::interfaces::*;
 
 pub use self::core::*;
 use crate::arm;
-pub use aes::Mode as AesMode;
+use crate::car::Clock;
+pub use aes::{CounterWidth as AesCounterWidth, Mode as AesMode};
 pub use registers::*;
+pub use utils::verify_bytes;
+
+/// The size of a saved Security Engine context in bytes.
+///
+/// See [`SecurityEngine::save_context`] for details.
+///
+/// [`SecurityEngine::save_context`]: struct.SecurityEngine.html#method.save_context
+pub const CONTEXT_SIZE: usize = constants::context::SIZE;
 
 /// Representation of the Security Engine used for cryptographic operations.
 pub struct SecurityEngine {
@@ -195,6 +264,46 @@ impl SecurityEngine {
 }
 
 impl SecurityEngine {
+    /// Creates a Security Engine instance from an arbitrary register base, with an
+    /// empty RSA keyslot cache.
+    ///
+    /// This is useful for Security Engine MMIO windows that are mapped to a location
+    /// other than the one in the [`memory_map`] or for mocked register blocks.
+    ///
+    /// # Safety
+    ///
+    /// `base` must point to a valid Security Engine register block that remains
+    /// accessible for the lifetime of the returned instance.
+    ///
+    /// [`memory_map`]: ../memory_map/index.html
+    pub const unsafe fn from_base(base: *const Registers) -> Self {
+        SecurityEngine {
+            registers: base,
+            rsa_keyslot_cache: [rsa::KeyInfo::new(); constants::rsa::KEY_SLOT_COUNT],
+        }
+    }
+}
+
+impl SecurityEngine {
+    /// Brings up the device clock of the Security Engine.
+    ///
+    /// NOTE: This must be done before the SE is accessed from a cold state,
+    /// otherwise the SoC will hang itself on the first register access.
+    /// On T210B01, SE1 and SE2 share this clock.
+    pub fn enable_clock(&self) {
+        Clock::SE.enable();
+    }
+
+    /// Shuts down the device clock of the Security Engine and puts it into reset.
+    ///
+    /// This makes the SE inaccessible until
+    /// [`SecurityEngine::enable_clock`] is called again.
+    ///
+    /// [`SecurityEngine::enable_clock`]: #method.enable_clock
+    pub fn disable_clock(&self) {
+        Clock::SE.disable();
+    }
+
     /// Locks the SE down for use from the Secure World.
     ///
     /// Only TrustZone clients can access the SE anymore afterwards.
@@ -293,15 +402,42 @@ impl SecurityEngine {
     /// Fills a given keyslot with the supplied AES key.
     ///
     /// This must be done prior to any encryptions using this slot.
+    ///
+    /// NOTE: Keyslots don't record the size of their key. Operations using the slot
+    /// must select the matching [`AesMode`], as obtained by [`AesMode::from_key_size`].
+    ///
+    /// [`AesMode`]: enum.AesMode.html
+    /// [`AesMode::from_key_size`]: enum.AesMode.html#method.from_key_size
     pub fn fill_aes_keyslot(&self, slot: u32, key: &[u8]) {
         assert!(slot < constants::aes::KEY_SLOT_COUNT as u32);
-        assert_eq!((key.len() % constants::aes::BLOCK_SIZE) >> 2, 0);
-        assert!(key.len() <= constants::aes::MAX_KEY_SIZE);
+        assert!(AesMode::from_key_size(key.len()).is_some());
 
         let engine = unsafe { &*self.registers };
         aes::set_key(engine, slot, key)
     }
 
+    /// Restricts the access to a given AES keyslot.
+    ///
+    /// `read` allows the key to be read back, `write` allows the key to be
+    /// overwritten and `use_key` allows the key to be used for cryptographic
+    /// operations. Access to the IVs of the keyslot is left untouched.
+    ///
+    /// NOTE: The access bits are sticky, a permission that was revoked cannot be
+    /// granted again until the Security Engine is reset.
+    pub fn set_keyslot_access(&self, slot: u32, read: bool, write: bool, use_key: bool) {
+        assert!(slot < constants::aes::KEY_SLOT_COUNT as u32);
+
+        let engine = unsafe { &*self.registers };
+
+        // Configure the hardware to restrict access to the keyslot.
+        engine.SE_CRYPTO_KEYTABLE_ACCESS_0[slot as usize].modify(
+            SE_CRYPTO_KEYTABLE_ACCESS_0::KEY_READ.val(read as u32)
+                + SE_CRYPTO_KEYTABLE_ACCESS_0::KEY_UPDATE.val(write as u32)
+                + SE_CRYPTO_KEYTABLE_ACCESS_0::KEY_USE.val(use_key as u32),
+        );
+        engine.SE_CRYPTO_KEYTABLE_ACCESS_0[slot as usize].get(); // Confirm the write.
+    }
+
     /// Copies a previously loaded AES key out of a given keyslot.
     pub fn get_aes_key(&self, slot: u32, key: &mut [u8]) {
         assert!(slot < constants::aes::KEY_SLOT_COUNT as u32);
@@ -368,6 +504,25 @@ impl SecurityEngine {
         aes::do_cmac_operation(engine, slot, source, destination, mode)
     }
 
+    /// Calculates an AES-CMAC over `source` and compares it against `expected`.
+    ///
+    /// The comparison is done in constant time using [`verify_bytes`], so no
+    /// timing information about the calculated MAC is leaked to the caller.
+    ///
+    /// [`verify_bytes`]: fn.verify_bytes.html
+    pub fn aes_cmac_verify(
+        &self,
+        slot: u32,
+        source: &[u8],
+        expected: &[u8],
+        mode: AesMode,
+    ) -> Result<bool, OperationError> {
+        let mut mac = [0; constants::aes::BLOCK_SIZE];
+        self.aes_cmac(slot, source, &mut mac, mode)?;
+
+        Ok(verify_bytes(&mac, expected))
+    }
+
     /// Encrypts a block of data from `source` to `destination` using AES-ECB.
     pub fn aes_ecb_encrypt(
         &self,
@@ -402,6 +557,61 @@ impl SecurityEngine {
         aes::do_ecb_operation(engine, false, slot, source, destination, mode)
     }
 
+    /// Encrypts a buffer of data from `source` to `destination` using AES-ECB.
+    ///
+    /// Unlike [`SecurityEngine::aes_ecb_encrypt`], this processes any number of
+    /// blocks with as few hardware operations as possible. Both buffers must be
+    /// of the same size, which must be a multiple of the AES block size, otherwise
+    /// [`OperationError::MalformedBuffer`] is returned.
+    ///
+    /// [`SecurityEngine::aes_ecb_encrypt`]: #method.aes_ecb_encrypt
+    /// [`OperationError::MalformedBuffer`]: enum.OperationError.html#variant.MalformedBuffer
+    pub fn aes_ecb_encrypt_buffer(
+        &self,
+        slot: u32,
+        source: &[u8],
+        destination: &mut [u8],
+        mode: AesMode,
+    ) -> Result<(), OperationError> {
+        self.aes_ecb_buffer(true, slot, source, destination, mode)
+    }
+
+    /// Decrypts a buffer of data from `source` to `destination` using AES-ECB.
+    ///
+    /// See [`SecurityEngine::aes_ecb_encrypt_buffer`] for details.
+    ///
+    /// [`SecurityEngine::aes_ecb_encrypt_buffer`]: #method.aes_ecb_encrypt_buffer
+    pub fn aes_ecb_decrypt_buffer(
+        &self,
+        slot: u32,
+        source: &[u8],
+        destination: &mut [u8],
+        mode: AesMode,
+    ) -> Result<(), OperationError> {
+        self.aes_ecb_buffer(false, slot, source, destination, mode)
+    }
+
+    fn aes_ecb_buffer(
+        &self,
+        encrypt: bool,
+        slot: u32,
+        source: &[u8],
+        destination: &mut [u8],
+        mode: AesMode,
+    ) -> Result<(), OperationError> {
+        assert!(slot < constants::aes::KEY_SLOT_COUNT as u32);
+        if source.len() != destination.len() || source.len() & (constants::aes::BLOCK_SIZE - 1) != 0
+        {
+            return Err(OperationError::MalformedBuffer);
+        }
+        if source.is_empty() {
+            return Ok(());
+        }
+
+        let engine = unsafe { &*self.registers };
+        aes::do_ecb_buffer_operation(engine, encrypt, slot, source, destination, mode)
+    }
+
     /// Encrypts data from `source` to `destination` using AES-CBC.
     pub fn aes_cbc_encrypt(
         &self,
@@ -442,7 +652,108 @@ impl SecurityEngine {
         aes::do_cbc_operation(engine, false, slot, source, destination, iv, mode)
     }
 
+    /// Encrypts data from `source` to `destination` using AES-CBC, continuing the
+    /// stream from a previous call.
+    ///
+    /// Unlike [`SecurityEngine::aes_cbc_encrypt`], `iv` is updated to the last
+    /// ciphertext block after the operation, so that a large buffer can be processed
+    /// in several chunks by passing the same `iv` to every call.
+    ///
+    /// [`SecurityEngine::aes_cbc_encrypt`]: #method.aes_cbc_encrypt
+    pub fn aes_cbc_encrypt_update(
+        &self,
+        slot: u32,
+        source: &[u8],
+        destination: &mut [u8],
+        iv: &mut [u8; constants::aes::BLOCK_SIZE],
+        mode: AesMode,
+    ) -> Result<(), OperationError> {
+        self.aes_cbc_encrypt(slot, source, destination, iv, mode)?;
+
+        // The last ciphertext block is chained into the next operation.
+        if let Some(block) = destination.rchunks_exact(constants::aes::BLOCK_SIZE).next() {
+            iv.copy_from_slice(block);
+        }
+
+        Ok(())
+    }
+
+    /// Decrypts data from `source` to `destination` using AES-CBC, continuing the
+    /// stream from a previous call.
+    ///
+    /// This is the counterpart to [`SecurityEngine::aes_cbc_encrypt_update`].
+    ///
+    /// [`SecurityEngine::aes_cbc_encrypt_update`]: #method.aes_cbc_encrypt_update
+    pub fn aes_cbc_decrypt_update(
+        &self,
+        slot: u32,
+        source: &[u8],
+        destination: &mut [u8],
+        iv: &mut [u8; constants::aes::BLOCK_SIZE],
+        mode: AesMode,
+    ) -> Result<(), OperationError> {
+        self.aes_cbc_decrypt(slot, source, destination, iv, mode)?;
+
+        // The last ciphertext block is chained into the next operation.
+        if let Some(block) = source.rchunks_exact(constants::aes::BLOCK_SIZE).next() {
+            iv.copy_from_slice(block);
+        }
+
+        Ok(())
+    }
+
+    /// Encrypts data from `source` to `destination` using AES-CBC with ciphertext
+    /// stealing.
+    ///
+    /// Unlike [`SecurityEngine::aes_cbc_encrypt`], `source` may be of any length of
+    /// at least one block. The ciphertext has the same length as the plaintext, with
+    /// the last two blocks being swapped unconditionally (CBC-CS3).
+    ///
+    /// [`SecurityEngine::aes_cbc_encrypt`]: #method.aes_cbc_encrypt
+    pub fn aes_cbc_cts_encrypt(
+        &self,
+        slot: u32,
+        source: &[u8],
+        destination: &mut [u8],
+        iv: &[u8; constants::aes::BLOCK_SIZE],
+        mode: AesMode,
+    ) -> Result<(), OperationError> {
+        assert!(slot < constants::aes::KEY_SLOT_COUNT as u32);
+        assert!(source.len() >= constants::aes::BLOCK_SIZE);
+        assert_eq!(source.len(), destination.len());
+
+        let engine = unsafe { &*self.registers };
+        aes::do_cbc_cts_operation(engine, true, slot, source, destination, iv, mode)
+    }
+
+    /// Decrypts data from `source` to `destination` using AES-CBC with ciphertext
+    /// stealing.
+    ///
+    /// This is the counterpart to [`SecurityEngine::aes_cbc_cts_encrypt`].
+    ///
+    /// [`SecurityEngine::aes_cbc_cts_encrypt`]: #method.aes_cbc_cts_encrypt
+    pub fn aes_cbc_cts_decrypt(
+        &self,
+        slot: u32,
+        source: &[u8],
+        destination: &mut [u8],
+        iv: &[u8; constants::aes::BLOCK_SIZE],
+        mode: AesMode,
+    ) -> Result<(), OperationError> {
+        assert!(slot < constants::aes::KEY_SLOT_COUNT as u32);
+        assert!(source.len() >= constants::aes::BLOCK_SIZE);
+        assert_eq!(source.len(), destination.len());
+
+        let engine = unsafe { &*self.registers };
+        aes::do_cbc_cts_operation(engine, false, slot, source, destination, iv, mode)
+    }
+
     /// Encrypts data from `source` to `destination` using AES-CTR.
+    ///
+    /// The whole 128-bit `iv` is incremented as a big-endian counter for every block,
+    /// see [`SecurityEngine::aes_ctr_crypt_with_width`] for narrower counters.
+    ///
+    /// [`SecurityEngine::aes_ctr_crypt_with_width`]: #method.aes_ctr_crypt_with_width
     pub fn aes_ctr_encrypt(
         &self,
         slot: u32,
@@ -462,6 +773,11 @@ impl SecurityEngine {
     }
 
     /// Decrypts data from `source` to `destination` using AES-CTR.
+    ///
+    /// The whole 128-bit `iv` is incremented as a big-endian counter for every block,
+    /// see [`SecurityEngine::aes_ctr_crypt_with_width`] for narrower counters.
+    ///
+    /// [`SecurityEngine::aes_ctr_crypt_with_width`]: #method.aes_ctr_crypt_with_width
     pub fn aes_ctr_decrypt(
         &self,
         slot: u32,
@@ -480,6 +796,95 @@ impl SecurityEngine {
         aes::do_ctr_operation(engine, false, slot, source, destination, iv, mode)
     }
 
+    /// Encrypts or decrypts data from `source` to `destination` using AES-CTR with
+    /// a counter of the given `width`.
+    ///
+    /// Only the least significant bits of the big-endian `iv` that are covered by
+    /// `width` are incremented, the counter wraps around within them without carrying
+    /// into the remaining bits of the nonce. This is required by formats which split
+    /// the IV into a nonce and a counter of fixed size.
+    pub fn aes_ctr_crypt_with_width(
+        &self,
+        slot: u32,
+        source: &[u8],
+        destination: &mut [u8],
+        iv: &[u8; constants::aes::BLOCK_SIZE],
+        mode: AesMode,
+        width: AesCounterWidth,
+    ) -> Result<(), OperationError> {
+        assert!(slot < constants::aes::KEY_SLOT_COUNT as u32);
+        assert_eq!(source.len(), destination.len());
+        if source.is_empty() {
+            return Ok(());
+        }
+
+        let engine = unsafe { &*self.registers };
+        aes::do_ctr_operation_with_width(engine, slot, source, destination, iv, mode, width)
+    }
+
+    /// Encrypts `buffer` in place using AES-CBC.
+    ///
+    /// This saves a second buffer of the same size when encrypting large
+    /// regions of memory.
+    pub fn aes_cbc_encrypt_in_place(
+        &self,
+        slot: u32,
+        buffer: &mut [u8],
+        iv: &[u8; constants::aes::BLOCK_SIZE],
+        mode: AesMode,
+    ) -> Result<(), OperationError> {
+        assert!(slot < constants::aes::KEY_SLOT_COUNT as u32);
+        assert_eq!(buffer.len() % constants::aes::BLOCK_SIZE, 0);
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let engine = unsafe { &*self.registers };
+        aes::do_cbc_in_place_operation(engine, true, slot, buffer, iv, mode)
+    }
+
+    /// Decrypts `buffer` in place using AES-CBC.
+    ///
+    /// This is the counterpart to [`SecurityEngine::aes_cbc_encrypt_in_place`].
+    ///
+    /// [`SecurityEngine::aes_cbc_encrypt_in_place`]: #method.aes_cbc_encrypt_in_place
+    pub fn aes_cbc_decrypt_in_place(
+        &self,
+        slot: u32,
+        buffer: &mut [u8],
+        iv: &[u8; constants::aes::BLOCK_SIZE],
+        mode: AesMode,
+    ) -> Result<(), OperationError> {
+        assert!(slot < constants::aes::KEY_SLOT_COUNT as u32);
+        assert_eq!(buffer.len() % constants::aes::BLOCK_SIZE, 0);
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let engine = unsafe { &*self.registers };
+        aes::do_cbc_in_place_operation(engine, false, slot, buffer, iv, mode)
+    }
+
+    /// Encrypts or decrypts `buffer` in place using AES-CTR.
+    ///
+    /// As both directions of AES-CTR are the same operation, a single method
+    /// serves for both. `buffer` may be of any length.
+    pub fn aes_ctr_crypt_in_place(
+        &self,
+        slot: u32,
+        buffer: &mut [u8],
+        iv: &[u8; constants::aes::BLOCK_SIZE],
+        mode: AesMode,
+    ) -> Result<(), OperationError> {
+        assert!(slot < constants::aes::KEY_SLOT_COUNT as u32);
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let engine = unsafe { &*self.registers };
+        aes::do_ctr_in_place_operation(engine, true, slot, buffer, iv, mode)
+    }
+
     /// Clears all data out of a given RSA keyslot.
     pub fn clear_rsa_keyslot(&mut self, slot: u32) {
         assert!(slot < constants::rsa::KEY_SLOT_COUNT as u32);
@@ -494,22 +899,45 @@ impl SecurityEngine {
     /// Fills the RSA keyslot using the supplied modulus and exponent data.
     ///
     /// This must be done prior to any RSA operations using the selected slot.
-    pub fn fill_rsa_keyslot(&mut self, slot: u32, modulus: &[u8], exponent: &[u8]) {
+    ///
+    /// Both the modulus and the exponent are expected as big-endian byte strings,
+    /// i.e. with the most significant byte first, as they are commonly stored in
+    /// certificates and key files. The modulus must be 512, 1024, 1536 or 2048 bits
+    /// in size and the exponent must be a non-empty multiple of 4 bytes that does not
+    /// exceed the modulus in length, a public exponent of `65537` would therefore be
+    /// passed as `[0x00, 0x01, 0x00, 0x01]`. [`OperationError::InvalidKeySize`] is
+    /// returned and the keyslot is left untouched otherwise.
+    ///
+    /// [`OperationError::InvalidKeySize`]: enum.OperationError.html#variant.InvalidKeySize
+    pub fn fill_rsa_keyslot(
+        &mut self,
+        slot: u32,
+        modulus: &[u8],
+        exponent: &[u8],
+    ) -> Result<(), OperationError> {
         assert!(slot < constants::rsa::KEY_SLOT_COUNT as u32);
-        assert!(modulus.len() <= constants::rsa::SIZE);
-        assert!(exponent.len() <= constants::rsa::SIZE);
+        if !rsa::is_valid_key_size(modulus.len(), exponent.len()) {
+            return Err(OperationError::InvalidKeySize);
+        }
 
         // Cache the infos about the key slot.
         self.rsa_keyslot_cache[slot as usize].update(modulus.len(), exponent.len());
 
         let engine = unsafe { &*self.registers };
-        rsa::fill_keyslot(engine, slot, modulus, exponent)
+        rsa::fill_keyslot(engine, slot, modulus, exponent);
+
+        Ok(())
     }
 
     /// Computes the modular exponentiation of `source ^ exponent (mod n)`.
     ///
     /// Exponent and modulus should have already been loaded into a keyslot prior
     /// to calling this method.
+    ///
+    /// Like the key material, `source` is expected in big-endian byte order and the
+    /// result is written to `destination` in big-endian byte order as well. The
+    /// conversion to the little-endian representation used by the hardware is done
+    /// internally, so callers must not reverse any of the buffers themselves.
     pub fn rsa_modular_exponentiate(
         &self,
         slot: u32,
@@ -549,6 +977,42 @@ impl SecurityEngine {
         )
     }
 
+    /// Verifies an RSASSA-PKCS1-v1_5 `signature` over `message` using SHA-256.
+    ///
+    /// The public key must have already been loaded into the given keyslot prior
+    /// to calling this method. The signature is decrypted using the public exponent,
+    /// the EMSA-PKCS1-v1_5 padding is validated and the embedded digest is compared
+    /// against the SHA-256 hash of `message` in constant time.
+    ///
+    /// Signatures whose length does not match the byte length of the modulus
+    /// in the keyslot are rejected with [`OperationError::InvalidSignature`].
+    pub fn rsa_verify_pkcs1_sha256(
+        &self,
+        slot: u32,
+        signature: &[u8],
+        message: &[u8],
+    ) -> Result<(), OperationError> {
+        assert!(slot < constants::rsa::KEY_SLOT_COUNT as u32);
+
+        // The signature must cover the full modulus, otherwise only the low
+        // bytes of the decrypted message would be compared.
+        let modulus_len = self.rsa_keyslot_cache[slot as usize].modulus_len();
+        if modulus_len == 0 || signature.len() != modulus_len {
+            return Err(OperationError::InvalidSignature);
+        }
+
+        // Decrypt the signature to obtain the encoded message.
+        let mut encoded = [0; constants::rsa::SIZE];
+        let encoded = &mut encoded[..signature.len()];
+        self.rsa_modular_exponentiate(slot, signature, encoded)?;
+
+        // Calculate the digest of the message to compare against.
+        let mut digest = [0; 0x20];
+        self.calculate_sha256(message, &mut digest)?;
+
+        rsa::verify_pkcs1_sha256(encoded, &digest)
+    }
+
     /// Initializes the RNG (Random Numer Generator).
     ///
     /// Calling this function is a prerequisite for all functions that use random
@@ -558,6 +1022,25 @@ impl SecurityEngine {
         rng::initialize(engine)
     }
 
+    /// Forces the RNG to gather fresh entropy and reseed the DRBG.
+    ///
+    /// Services that pull large amounts of random data should call this
+    /// periodically, in addition to the hardware reseed interval.
+    pub fn reseed_rng(&self) -> Result<(), OperationError> {
+        let engine = unsafe { &*self.registers };
+        rng::reseed(engine)
+    }
+
+    /// Checks whether the RNG is operating on a healthy entropy source.
+    ///
+    /// This is the case when the entropy source is enabled, the DRBG reseed
+    /// counter is not exhausted and the engine reports no errors. When this
+    /// returns `false`, generated random data must not be trusted.
+    pub fn rng_healthy(&self) -> bool {
+        let engine = unsafe { &*self.registers };
+        rng::is_healthy(engine)
+    }
+
     /// Uses the RNG to fill the given buffer with random bytes.
     pub fn generate_random(&self, output: &mut [u8]) -> Result<(), OperationError> {
         // Opt out if the buffer has no capacity for data.
@@ -569,12 +1052,53 @@ impl SecurityEngine {
         rng::generate_random(engine, output)
     }
 
+    /// Securely wipes the contents of the given buffer.
+    ///
+    /// The buffer is first overwritten with random data by the RNG, which is written
+    /// to memory directly by the Security Engine. It is then cleared with volatile
+    /// writes that the compiler cannot elide and flushed from the data cache, so that
+    /// the zeroes are committed to memory when this method returns.
+    pub fn secure_zero(&self, buffer: &mut [u8]) -> Result<(), OperationError> {
+        // Destroy the previous contents in memory through the engine.
+        self.generate_random(buffer)?;
+
+        // Clear the buffer in a way that won't be optimized away.
+        for byte in buffer.iter_mut() {
+            unsafe { ::core::ptr::write_volatile(byte, 0) };
+        }
+        ::core::sync::atomic::compiler_fence(::core::sync::atomic::Ordering::SeqCst);
+
+        // Commit the zeroes to memory.
+        unsafe {
+            arm::cache::flush_data_cache(buffer, buffer.len());
+            #[cfg(target_arch = "aarch64")]
+            cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::ISH);
+        }
+
+        Ok(())
+    }
+
     /// Fills a given key slot with a random key generated by the RNG.
     pub fn set_random_key(&self, slot: u32) -> Result<(), OperationError> {
         let engine = unsafe { &*self.registers };
         rng::set_random_key(engine, slot)
     }
 
+    /// Fills a given key slot with a random key that can be used, but not read back.
+    ///
+    /// Read access to the keyslot is revoked before the key is generated, so there
+    /// is no point in time at which the key could be extracted. The keyslot stays
+    /// writable, so that the key can be replaced or cleared later on.
+    ///
+    /// NOTE: The revoked read permission cannot be granted again until the Security
+    /// Engine is reset, see [`SecurityEngine::set_keyslot_access`].
+    ///
+    /// [`SecurityEngine::set_keyslot_access`]: #method.set_keyslot_access
+    pub fn generate_ephemeral_key(&self, slot: u32) -> Result<(), OperationError> {
+        self.set_keyslot_access(slot, false, true, true);
+        self.set_random_key(slot)
+    }
+
     /// Performs a hardware operation to generate the Storage Root Key (SRK).
     ///
     /// NOTE: Different entropy sources will lead to different results.
@@ -583,12 +1107,57 @@ impl SecurityEngine {
         rng::generate_srk(engine)
     }
 
+    /// Saves the full context of the Security Engine to `buffer`, which must be
+    /// [`CONTEXT_SIZE`] bytes in size.
+    ///
+    /// The context covers the sticky bits and the contents of all AES and RSA keyslots
+    /// and is encrypted with a freshly generated SRK, which is stored in the PMC secure
+    /// scratch registers afterwards. This requires the RNG to be initialized.
+    ///
+    /// [`CONTEXT_SIZE`]: constant.CONTEXT_SIZE.html
+    pub fn save_context(&self, buffer: &mut [u8]) -> Result<(), OperationError> {
+        if buffer.len() != CONTEXT_SIZE {
+            return Err(OperationError::MalformedBuffer);
+        }
+
+        let engine = unsafe { &*self.registers };
+        context::save(engine, buffer)
+    }
+
+    /// Restores the AES and RSA keyslots from a context that was previously saved
+    /// through [`SecurityEngine::save_context`].
+    ///
+    /// The SRK is read back from the PMC secure scratch registers and temporarily
+    /// loaded into the last AES keyslot to decrypt the context, which therefore must
+    /// not be locked against writes. The keyslot is restored to its saved contents
+    /// afterwards. An error is returned if the context is not [`CONTEXT_SIZE`] bytes
+    /// in size or fails to decrypt with the SRK.
+    ///
+    /// # Sticky bits
+    ///
+    /// The sticky bits that are part of the context are **not** restored. Keyslot
+    /// permissions and other locks that were in place when the context was saved
+    /// must be re-applied by the caller after the restore.
+    ///
+    /// [`SecurityEngine::save_context`]: #method.save_context
+    /// [`CONTEXT_SIZE`]: constant.CONTEXT_SIZE.html
+    pub fn restore_context(&self, buffer: &[u8]) -> Result<(), OperationError> {
+        if buffer.len() != CONTEXT_SIZE {
+            return Err(OperationError::MalformedBuffer);
+        }
+
+        let engine = unsafe { &*self.registers };
+        context::restore(engine, buffer)
+    }
+
     // Generate the Hashing API.
     gen_sha_impl!(1, 20);
     gen_sha_impl!(224);
     gen_sha_impl!(256);
     gen_sha_impl!(384);
     gen_sha_impl!(512);
+    gen_sha512_t_impl!(224, constants::sha::SHA512_224_IV);
+    gen_sha512_t_impl!(256, constants::sha::SHA512_256_IV);
 }
 
 // Safety: The driver waits until previous operations have completed unconditionally
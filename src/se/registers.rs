@@ -191,6 +191,22 @@ register_bitfields! {
         HW_INIT_HASH OFFSET(0) NUMBITS(1) []
     ],
 
+    pub SE_CRYPTO_KEYTABLE_ACCESS_0 [
+        KEY_USE OFFSET(6) NUMBITS(1) [],
+
+        UIV_UPDATE OFFSET(5) NUMBITS(1) [],
+
+        OIV_UPDATE OFFSET(4) NUMBITS(1) [],
+
+        UIV_READ OFFSET(3) NUMBITS(1) [],
+
+        OIV_READ OFFSET(2) NUMBITS(1) [],
+
+        KEY_UPDATE OFFSET(1) NUMBITS(1) [],
+
+        KEY_READ OFFSET(0) NUMBITS(1) []
+    ],
+
     pub SE_CRYPTO_CONFIG_0 [
         MEMIF OFFSET(31) NUMBITS(1) [
             Ahb = 0,
@@ -398,7 +414,7 @@ register_structs! {
         (0x0214 => pub SE_SHA_MSG_LEFT_0: [ReadWrite<u32>; 0x4]),
         (0x0224 => _reserved1),
         (0x0280 => pub SE_CRYPTO_SECURITY_PERKEY_0: ReadWrite<u32>),
-        (0x0284 => pub SE_CRYPTO_KEYTABLE_ACCESS_0: [ReadWrite<u32>; 0x10]),
+        (0x0284 => pub SE_CRYPTO_KEYTABLE_ACCESS_0: [ReadWrite<u32, SE_CRYPTO_KEYTABLE_ACCESS_0::Register>; 0x10]),
         (0x02C4 => _reserved2),
         (0x0304 => pub SE_CRYPTO_CONFIG_0: ReadWrite<u32, SE_CRYPTO_CONFIG_0::Register>),
         (0x0308 => pub SE_CRYPTO_LINEAR_CTR_0: [ReadWrite<u32>; 0x4]),
@@ -1,7 +1,7 @@
 use crate::se::constants::*;
 use crate::se::core::*;
 use crate::se::registers::*;
-use crate::se::utils::trigger_single_block_operation;
+use crate::se::utils::{trigger_buffer_operation, trigger_single_block_operation};
 
 use tock_registers::interfaces::*;
 
@@ -60,6 +60,42 @@ pub fn initialize(registers: &Registers) -> Result<(), OperationError> {
     start_normal_operation(registers, &source_ll, &mut destination_ll)
 }
 
+pub fn reseed(registers: &Registers) -> Result<(), OperationError> {
+    // Configure the RNG to gather fresh entropy.
+    init_rng!(registers, Memory, ForceReseed);
+
+    // Only process a single RNG block to trigger the DRBG reseed.
+    registers.SE_CRYPTO_LAST_BLOCK_0.set(0);
+
+    let buffer = [0; aes::BLOCK_SIZE];
+
+    // Prepare the linked lists and kick off the operation.
+    let source_ll = LinkedList::from(&buffer[..]);
+    let mut destination_ll = LinkedList::default();
+    start_normal_operation(registers, &source_ll, &mut destination_ll)?;
+
+    // Acknowledge an exhausted reseed counter, if any.
+    registers
+        .SE_INT_STATUS_0
+        .write(SE_INT_STATUS_0::RESEED_CNTR_EXHAUSTED::SET);
+
+    Ok(())
+}
+
+pub fn is_healthy(registers: &Registers) -> bool {
+    // The entropy source must be enabled to feed the DRBG.
+    let entropy_enabled = registers
+        .SE_RNG_SRC_CONFIG_0
+        .is_set(SE_RNG_SRC_CONFIG_0::RO_ENTROPY_SOURCE);
+
+    // The DRBG must not have run out of reseeds without being reseeded.
+    let exhausted = registers
+        .SE_INT_STATUS_0
+        .is_set(SE_INT_STATUS_0::RESEED_CNTR_EXHAUSTED);
+
+    entropy_enabled && !exhausted && registers.SE_ERR_STATUS_0.get() == 0
+}
+
 pub fn set_random_key(registers: &Registers, slot: u32) -> Result<(), OperationError> {
     // Configure the RNG.
     init_rng!(registers, KeyTable, Normal);
@@ -122,10 +158,8 @@ pub fn generate_random(registers: &Registers, output: &mut [u8]) -> Result<(), O
         // Load in the number of blocks to generate.
         registers.SE_CRYPTO_LAST_BLOCK_0.set((nblocks - 1) as u32);
 
-        // Prepare the linked lists and kick off the operation.
-        let source_ll = LinkedList::default();
-        let mut destination_ll = LinkedList::from(&output[..aligned_size]);
-        start_normal_operation(registers, &source_ll, &mut destination_ll)?;
+        // Kick off the operation.
+        trigger_buffer_operation(registers, &[], &mut output[..aligned_size])?;
     }
 
     // Generate a single unaligned block, if necessary.
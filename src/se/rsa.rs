@@ -4,11 +4,20 @@ use tock_registers::{fields::FieldValue, interfaces::*};
 use crate::se::constants::*;
 use crate::se::core::*;
 use crate::se::registers::*;
+use crate::se::utils::verify_bytes;
+
+/// The ASN.1 DER-encoded `DigestInfo` prefix for SHA-256 digests, as
+/// specified in RFC 8017, section 9.2.
+const SHA256_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0D, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
 
 #[derive(Clone, Copy)]
 pub struct KeyInfo {
     modulus_size: u32,
     exponent_size: u32,
+    modulus_len: usize,
 }
 
 impl KeyInfo {
@@ -16,20 +25,44 @@ impl KeyInfo {
         KeyInfo {
             modulus_size: 0,
             exponent_size: 0,
+            modulus_len: 0,
         }
     }
 
     pub fn update(&mut self, modulus_size: usize, exponent_size: usize) {
         self.modulus_size = (modulus_size / 64 - 1) as u32;
         self.exponent_size = (exponent_size / 4) as u32;
+        self.modulus_len = modulus_size;
+    }
+
+    /// Gets the byte length of the loaded modulus, or `0` if no key is loaded.
+    pub fn modulus_len(&self) -> usize {
+        self.modulus_len
     }
 
     pub fn reset(&mut self) {
         self.modulus_size = 0;
         self.exponent_size = 0;
+        self.modulus_len = 0;
     }
 }
 
+/// Checks whether a modulus and an exponent of the given byte lengths can be
+/// loaded into an RSA keyslot.
+pub fn is_valid_key_size(modulus_size: usize, exponent_size: usize) -> bool {
+    let valid_modulus = matches!(
+        modulus_size,
+        rsa::DIGEST_SIZE_512
+            | rsa::DIGEST_SIZE_1024
+            | rsa::DIGEST_SIZE_1536
+            | rsa::DIGEST_SIZE_2048
+    );
+    let valid_exponent =
+        exponent_size != 0 && exponent_size & 3 == 0 && exponent_size <= modulus_size;
+
+    valid_modulus && valid_exponent
+}
+
 fn clear_keyslot_impl(
     registers: &Registers,
     slot: u32,
@@ -137,3 +170,28 @@ pub fn encrypt(
 
     Ok(())
 }
+
+pub fn verify_pkcs1_sha256(encoded: &[u8], digest: &[u8; 0x20]) -> Result<(), OperationError> {
+    // The padding string must be at least 8 bytes long.
+    let padding_len = 3 + 8 + SHA256_DIGEST_INFO.len() + digest.len();
+    if encoded.len() < padding_len || encoded.len() > rsa::SIZE {
+        return Err(OperationError::InvalidSignature);
+    }
+
+    // Construct the expected EMSA-PKCS1-v1_5 encoded message.
+    let mut expected = [0xFF; rsa::SIZE];
+    let expected = &mut expected[..encoded.len()];
+    let digest_info_start = encoded.len() - digest.len() - SHA256_DIGEST_INFO.len();
+    expected[0] = 0x00;
+    expected[1] = 0x01;
+    expected[digest_info_start - 1] = 0x00;
+    expected[digest_info_start..encoded.len() - digest.len()].copy_from_slice(&SHA256_DIGEST_INFO);
+    expected[encoded.len() - digest.len()..].copy_from_slice(digest);
+
+    // Compare the full encoded message so no information about the mismatch leaks.
+    if verify_bytes(encoded, expected) {
+        Ok(())
+    } else {
+        Err(OperationError::InvalidSignature)
+    }
+}
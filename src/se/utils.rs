@@ -54,3 +54,115 @@ pub fn trigger_single_block_operation(
 
     Ok(())
 }
+
+/// Makes the given buffers coherent before they are handed to the SE.
+///
+/// Both buffers are flushed from the data cache so that the SE reads the
+/// latest input data and no dirty lines of the output buffer can be evicted
+/// over the results of the operation later on.
+pub fn flush_buffers(source: &[u8], destination: &[u8]) {
+    unsafe {
+        arm::cache::flush_data_cache(source, source.len());
+        arm::cache::flush_data_cache(destination, destination.len());
+        #[cfg(target_arch = "aarch64")]
+        cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::ISH);
+    }
+}
+
+/// Makes the results of an SE operation in the given buffer visible to the CPU.
+///
+/// The buffer is cleaned and invalidated rather than only invalidated, so that
+/// unrelated data sharing its first or last cache line is not discarded.
+pub fn flush_buffer(destination: &[u8]) {
+    unsafe {
+        #[cfg(target_arch = "aarch64")]
+        cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::ISH);
+        arm::cache::flush_data_cache(destination, destination.len());
+        #[cfg(target_arch = "aarch64")]
+        cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::ISH);
+    }
+}
+
+/// Triggers a regular SE operation from `source` into `destination`.
+///
+/// Either of the buffers may be empty when the operation doesn't read
+/// from or write to memory. Data cache maintenance is taken care of.
+pub fn trigger_buffer_operation(
+    engine: &Registers,
+    source: &[u8],
+    destination: &mut [u8],
+) -> Result<(), OperationError> {
+    // Make the data coherent so it is seen correctly by the SE.
+    flush_buffers(source, destination);
+
+    // Prepare the linked lists and kick off the operation.
+    let source_ll = if source.is_empty() {
+        LinkedList::default()
+    } else {
+        LinkedList::from(source)
+    };
+    let mut destination_ll = if destination.is_empty() {
+        LinkedList::default()
+    } else {
+        LinkedList::from(destination as &[u8])
+    };
+    start_normal_operation(engine, &source_ll, &mut destination_ll)?;
+
+    // Ensure data cache coherency so that CPU sees the correct data.
+    flush_buffer(destination);
+
+    Ok(())
+}
+
+/// Triggers a regular SE operation which reads from and writes to the same `buffer`.
+///
+/// The SE fetches every block before the result is written back, so the
+/// input may be overwritten in place. As both DMA streams target the same
+/// memory, the buffer is made coherent once before and once after the
+/// operation instead of being treated as two separate regions.
+pub fn trigger_in_place_operation(
+    engine: &Registers,
+    buffer: &mut [u8],
+) -> Result<(), OperationError> {
+    if buffer.is_empty() {
+        return Ok(());
+    }
+
+    // Write back the input data and evict the lines the SE will overwrite.
+    unsafe {
+        arm::cache::flush_data_cache(buffer, buffer.len());
+        #[cfg(target_arch = "aarch64")]
+        cortex_a::asm::barrier::dsb(cortex_a::asm::barrier::ISH);
+    }
+
+    // Both linked lists describe the very same memory region.
+    let source_ll = LinkedList::from(buffer as &[u8]);
+    let mut destination_ll = LinkedList::from(buffer as &[u8]);
+    start_normal_operation(engine, &source_ll, &mut destination_ll)?;
+
+    // Ensure data cache coherency so that CPU sees the correct data.
+    flush_buffer(buffer);
+
+    Ok(())
+}
+
+/// Compares two byte slices for equality in constant time.
+///
+/// The time taken by this function only depends on the lengths of the slices
+/// and not on their contents or the position of the first mismatching byte,
+/// which makes it suitable for checking MACs, signatures and other secrets.
+/// Slices of different lengths are never considered equal.
+pub fn verify_bytes(lhs: &[u8], rhs: &[u8]) -> bool {
+    if lhs.len() != rhs.len() {
+        return false;
+    }
+
+    // Accumulate the differences of all bytes without branching on their values.
+    let mut difference = 0;
+    for (a, b) in lhs.iter().zip(rhs.iter()) {
+        difference |= a ^ b;
+    }
+
+    // Prevent the compiler from turning the accumulation into an early-exit loop.
+    unsafe { core::ptr::read_volatile(&difference) == 0 }
+}
@@ -1,9 +1,9 @@
 use embedded_hal::blocking::spi::{Transfer, Write};
 
-use super::Spi;
+use super::{Spi, SpiError};
 
 impl Write<u8> for Spi {
-    type Error = ();
+    type Error = SpiError;
 
     fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
         Spi::write(self, words)
@@ -11,11 +11,10 @@ impl Write<u8> for Spi {
 }
 
 impl Transfer<u8> for Spi {
-    type Error = ();
+    type Error = SpiError;
 
     fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
-        Spi::write(self, words)?;
-        Spi::read(self, words)?;
+        Spi::transfer(self, words)?;
         Ok(words)
     }
 }
@@ -8,20 +8,60 @@ mod hal;
 use core::{convert::TryInto, marker::Sync};
 
 pub use crate::spi::registers::*;
-use crate::timer::usleep;
+use crate::{
+    car::Clock,
+    gpio::{Config, Gpio, Level},
+    timer::{get_microseconds, has_elapsed, usleep},
+};
 
 use tock_registers::interfaces::*;
 
+/// The base timeout in microseconds for the controller to become ready.
+const READY_TIMEOUT_BASE: u32 = 1000;
+
+/// The additional timeout in microseconds granted per byte of a transfer.
+///
+/// This accounts for the slowest SPI clocks the controller can be configured to.
+const READY_TIMEOUT_PER_BYTE: u32 = 100;
+
+/// Calculates the timeout in microseconds for a transfer of `size` bytes.
+const fn ready_timeout(size: usize) -> u32 {
+    READY_TIMEOUT_BASE + size as u32 * READY_TIMEOUT_PER_BYTE
+}
+
+/// SPI errors that may occur during transfers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpiError {
+    /// The TX or RX FIFO overflowed during a transfer.
+    FifoOverflow,
+    /// The TX or RX FIFO ran empty during a transfer.
+    FifoUnderrun,
+    /// The controller didn't become ready in time.
+    Timeout,
+    /// The length of a buffer is not a multiple of 4 bytes.
+    Misaligned,
+    /// The controller clock cannot be configured to the requested frequency.
+    UnsupportedFrequency,
+}
+
 /// Representation of an SPI.
 ///
 /// NOTE: Instances of this structure should never be created manually.
 /// Refer to the public constants this structure holds, which represent
 /// the controllers 1 through 4.
+///
+/// Slaves whose chip-select is wired to a GPIO instead of the dedicated
+/// chip-select pin of the controller can be driven through an instance
+/// created with [`Spi::with_gpio_cs`].
+///
+/// [`Spi::with_gpio_cs`]: #method.with_gpio_cs
 // TODO: SPI device clocks?
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Spi {
     // A pointer to the SPI device registers in memory.
     registers: *const Registers,
+    // An optional GPIO that acts as an active-low chip-select.
+    chip_select: Option<Gpio>,
 }
 
 // Definitions of known SPIs.
@@ -29,47 +69,112 @@ impl Spi {
     /// Representation of the SPI 1 controller.
     pub const SPI_1: Self = Spi {
         registers: SPI_1_REGISTERS,
+        chip_select: None,
     };
 
     /// Representation of the SPI 2 controller.
     pub const SPI_2: Self = Spi {
         registers: SPI_2_REGISTERS,
+        chip_select: None,
     };
 
     /// Representation of the SPI 3 controller.
     pub const SPI_3: Self = Spi {
         registers: SPI_3_REGISTERS,
+        chip_select: None,
     };
 
     /// Representation of the SPI 4 controller.
     pub const SPI_4: Self = Spi {
         registers: SPI_4_REGISTERS,
+        chip_select: None,
     };
 
     /// Representation of the SPI 5 controller.
     pub const SPI_5: Self = Spi {
         registers: SPI_5_REGISTERS,
+        chip_select: None,
     };
 
     /// Representation of the SPI 6 controller.
     pub const SPI_6: Self = Spi {
         registers: SPI_6_REGISTERS,
+        chip_select: None,
     };
 
     /// Representation of the QSPI controller.
     pub const QSPI: Self = Spi {
         registers: QSPI_REGISTERS,
+        chip_select: None,
     };
 }
 
 impl Spi {
+    /// Creates a copy of this controller that drives the given GPIO as an
+    /// active-low chip-select around each transaction.
+    ///
+    /// The GPIO is configured as an output by [`Spi::init`], the dedicated
+    /// chip-select pin of the controller is kept deasserted in that case.
+    ///
+    /// [`Spi::init`]: #method.init
+    pub const fn with_gpio_cs(self, gpio: Gpio) -> Self {
+        Spi {
+            registers: self.registers,
+            chip_select: Some(gpio),
+        }
+    }
+
+    /// Gets the device clock which drives the controller, if it is known.
+    fn clock(&self) -> Option<&'static Clock> {
+        match self.registers {
+            SPI_1_REGISTERS => Some(&Clock::SPI_1),
+            SPI_2_REGISTERS => Some(&Clock::SPI_2),
+            SPI_3_REGISTERS => Some(&Clock::SPI_3),
+            SPI_4_REGISTERS => Some(&Clock::SPI_4),
+            _ => None,
+        }
+    }
+
+    /// Runs a transaction with the GPIO chip-select asserted, if configured.
+    fn with_chip_select<F>(&self, transaction: F) -> Result<(), SpiError>
+    where
+        F: FnOnce() -> Result<(), SpiError>,
+    {
+        if let Some(gpio) = self.chip_select {
+            gpio.write(Level::Low);
+        }
+
+        let res = transaction();
+
+        if let Some(gpio) = self.chip_select {
+            gpio.write(Level::High);
+        }
+
+        res
+    }
+
     /// Waits for the SPI Controller to complete all transactions.
-    fn wait_until_ready(&self) {
+    ///
+    /// The timeout is derived from the `size` in bytes of the pending transfer
+    /// and an error is returned when the controller doesn't become ready in time.
+    fn wait_until_ready(&self, size: usize) -> Result<(), SpiError> {
+        self.wait_until_ready_for(ready_timeout(size))
+    }
+
+    /// Waits up to `timeout` microseconds for the SPI Controller to complete
+    /// all transactions.
+    fn wait_until_ready_for(&self, timeout: u32) -> Result<(), SpiError> {
         let spi = unsafe { &*self.registers };
 
+        let start = get_microseconds();
         while !spi.SPI_TRANSFER_STATUS_0.is_set(SPI_TRANSFER_STATUS_0::RDY) {
             // Wait until all transactions are completed.
+            if has_elapsed(start, get_microseconds(), timeout) {
+                return Err(SpiError::Timeout);
+            }
         }
+
+        Ok(())
     }
 
     /// Clears the error status bits of the [`SPI_FIFO_STATUS_0`] register.
@@ -88,18 +193,48 @@ impl Spi {
         );
     }
 
-    /// Transmits data over SPI in PIO mode.
+    /// Checks the [`SPI_FIFO_STATUS_0`] register for FIFO errors of the last transfer.
+    ///
+    /// The error status bits are cleared when an error is reported.
+    ///
+    /// [`SPI_FIFO_STATUS_0`]: ./SPI_FIFO_STATUS_0/index.html
+    fn check_fifo_status(&self) -> Result<(), SpiError> {
+        let spi = unsafe { &*self.registers };
+
+        if !spi.SPI_FIFO_STATUS_0.is_set(SPI_FIFO_STATUS_0::ERR) {
+            return Ok(());
+        }
+
+        let error = if spi.SPI_FIFO_STATUS_0.is_set(SPI_FIFO_STATUS_0::TX_FIFO_OVF)
+            || spi.SPI_FIFO_STATUS_0.is_set(SPI_FIFO_STATUS_0::RX_FIFO_OVF)
+        {
+            SpiError::FifoOverflow
+        } else {
+            SpiError::FifoUnderrun
+        };
+        self.clear_fifo_status();
+
+        Err(error)
+    }
+
+    /// Flushes the FIFOs and transmits data over SPI in PIO mode.
+    fn pio_send_packet(&self, data: &[u8; 4]) -> Result<(), SpiError> {
+        // Flush the FIFOs.
+        self.flush_fifos()?;
+
+        self.pio_send(data, ready_timeout(data.len()))
+    }
+
+    /// Transmits data over SPI in PIO mode, waiting up to `timeout`
+    /// microseconds for the transaction to complete.
     ///
     /// NOTE: This method is a low-level implementation
     /// of the SPI transmit flow and doesn't validate any
     /// buffer boundaries. This task is delegated to the
     /// caller.
-    fn pio_send_packet(&self, data: &[u8; 4]) -> Result<(), ()> {
+    fn pio_send(&self, data: &[u8; 4], timeout: u32) -> Result<(), SpiError> {
         let spi = unsafe { &*self.registers };
 
-        // Flush the FIFOs.
-        self.flush_fifos();
-
         // Set 8-bit transfers, unpacked mode, most significant bit first.
         spi.SPI_COMMAND_0
             .modify(SPI_COMMAND_0::PACKED::CLEAR + SPI_COMMAND_0::BIT_LEN.val(7));
@@ -130,32 +265,35 @@ impl Spi {
         spi.SPI_COMMAND_0.get();
 
         // Wait for the transaction to complete.
-        self.wait_until_ready();
+        self.wait_until_ready_for(timeout)?;
 
         // Clear the transmit enable bit.
         spi.SPI_COMMAND_0.modify(SPI_COMMAND_0::TX_EN::CLEAR);
 
         // Check for errors.
-        if spi.SPI_FIFO_STATUS_0.is_set(SPI_FIFO_STATUS_0::ERR) {
-            self.clear_fifo_status();
-            return Err(());
-        }
+        self.check_fifo_status()?;
 
         Ok(())
     }
 
-    /// Receives data over SPI in PIO mode.
+    /// Flushes the FIFOs and receives data over SPI in PIO mode.
+    fn pio_receive_packet(&self, data: &mut [u8; 4]) -> Result<(), SpiError> {
+        // Flush the FIFOs.
+        self.flush_fifos()?;
+
+        self.pio_receive(data, ready_timeout(data.len()))
+    }
+
+    /// Receives data over SPI in PIO mode, waiting up to `timeout`
+    /// microseconds for the transaction to complete.
     ///
     /// NOTE: This method is a low-level implementation
     /// of the SPI receive flow and doesn't validate any
     /// buffer boundaries. This task is delegated to the
     /// caller.
-    fn pio_receive_packet(&self, data: &mut [u8; 4]) -> Result<(), ()> {
+    fn pio_receive(&self, data: &mut [u8; 4], timeout: u32) -> Result<(), SpiError> {
         let spi = unsafe { &*self.registers };
 
-        // Flush the FIFOs.
-        self.flush_fifos();
-
         // Set 8-bit transfers, unpacked mode, most significant bit first.
         spi.SPI_COMMAND_0
             .modify(SPI_COMMAND_0::PACKED::CLEAR + SPI_COMMAND_0::BIT_LEN.val(7));
@@ -183,16 +321,13 @@ impl Spi {
         spi.SPI_COMMAND_0.get();
 
         // Wait for the transaction to complete.
-        self.wait_until_ready();
+        self.wait_until_ready_for(timeout)?;
 
         // Clear the receive enable bit.
         spi.SPI_COMMAND_0.modify(SPI_COMMAND_0::RX_EN::CLEAR);
 
         // Check for errors.
-        if spi.SPI_FIFO_STATUS_0.is_set(SPI_FIFO_STATUS_0::ERR) {
-            self.clear_fifo_status();
-            return Err(());
-        }
+        self.check_fifo_status()?;
 
         // Read the data bytes into the buffer.
         *data = spi.SPI_RX_FIFO_0.get().to_le_bytes();
@@ -206,41 +341,88 @@ impl Spi {
     /// Further, it is required to do the respective [`pinmux`] configuration
     /// before calling this method.
     ///
+    /// An error is returned when the controller doesn't become ready in time.
+    ///
     /// [`pinmux`]: ../pinmux
-    pub fn init(&self) {
+    pub fn init(&self) -> Result<(), SpiError> {
         let spi = unsafe { &*self.registers };
 
-        // Set chip-select value to high, 8-bit transfers,
+        // Set master mode, chip-select value to high, 8-bit transfers,
         // unpacked mode and most significant bit first.
         spi.SPI_COMMAND_0.modify(
-            SPI_COMMAND_0::CS_SW_HW::SET
+            SPI_COMMAND_0::MS::MasterMode
+                + SPI_COMMAND_0::CS_SW_HW::SET
                 + SPI_COMMAND_0::CS_SW_VAL::SET
                 + SPI_COMMAND_0::PACKED::CLEAR
                 + SPI_COMMAND_0::BIT_LEN.val(7),
         );
 
         // Flush the FIFOs.
-        self.flush_fifos();
+        self.flush_fifos()?;
 
-        // Enforce chip-select line 0 for now and drive chip-select low.
-        spi.SPI_COMMAND_0
-            .modify(SPI_COMMAND_0::CS_SEL.val(0) + SPI_COMMAND_0::CS_SW_VAL::CLEAR);
+        match self.chip_select {
+            // Keep the slave deselected until the first transaction.
+            Some(gpio) => gpio.config(Config::OutputHigh),
+
+            // Enforce chip-select line 0 for now and drive chip-select low.
+            None => spi
+                .SPI_COMMAND_0
+                .modify(SPI_COMMAND_0::CS_SEL.val(0) + SPI_COMMAND_0::CS_SW_VAL::CLEAR),
+        }
+
+        Ok(())
+    }
+
+    /// Initializes the SPI controller in slave mode.
+    ///
+    /// In this mode, the controller is clocked and selected by an external master,
+    /// data is exchanged through [`Spi::slave_respond`] and [`Spi::slave_receive`].
+    /// A subsequent call to [`Spi::init`] switches the controller back to master mode.
+    ///
+    /// NOTE: It is required to do the respective [`pinmux`] configuration
+    /// before calling this method.
+    ///
+    /// [`Spi::slave_respond`]: #method.slave_respond
+    /// [`Spi::slave_receive`]: #method.slave_receive
+    /// [`Spi::init`]: #method.init
+    /// [`pinmux`]: ../pinmux
+    pub fn init_slave(&self) -> Result<(), SpiError> {
+        let spi = unsafe { &*self.registers };
+
+        // Set slave mode with the chip-select driven by the master, 8-bit transfers,
+        // unpacked mode and most significant bit first.
+        spi.SPI_COMMAND_0.modify(
+            SPI_COMMAND_0::MS::SlaveMode
+                + SPI_COMMAND_0::CS_SW_HW::CLEAR
+                + SPI_COMMAND_0::PACKED::CLEAR
+                + SPI_COMMAND_0::BIT_LEN.val(7),
+        );
+
+        // Flush the FIFOs.
+        self.flush_fifos()
     }
 
     /// Flushes the underlying FIFOs of the SPI.
     ///
     /// NOTE: This method flushes both, TX FIFO and RX FIFO,
     /// so be careful when you use it.
-    pub fn flush_fifos(&self) {
+    ///
+    /// An error is returned when the controller doesn't become ready in time.
+    /// In slave mode, the controller is not waited on since only the external
+    /// master can clock a pending transfer to completion.
+    pub fn flush_fifos(&self) -> Result<(), SpiError> {
         let spi = unsafe { &*self.registers };
 
         // Make sure the controller is in idle state.
-        self.wait_until_ready();
+        if !spi.SPI_COMMAND_0.matches_all(SPI_COMMAND_0::MS::SlaveMode) {
+            self.wait_until_ready(0)?;
+        }
 
         // Issue flush requests for TX FIFO and RX FIFO.
         spi.SPI_FIFO_STATUS_0
             .modify(SPI_FIFO_STATUS_0::RX_FIFO_FLUSH::SET + SPI_FIFO_STATUS_0::TX_FIFO_FLUSH::SET);
 
+        let start = get_microseconds();
         while spi
             .SPI_FIFO_STATUS_0
             .is_set(SPI_FIFO_STATUS_0::RX_FIFO_FLUSH)
@@ -249,38 +431,172 @@ impl Spi {
                 .is_set(SPI_FIFO_STATUS_0::TX_FIFO_FLUSH)
         {
             // Wait for the changes to take effect.
+            if has_elapsed(start, get_microseconds(), READY_TIMEOUT_BASE) {
+                return Err(SpiError::Timeout);
+            }
         }
+
+        Ok(())
     }
 
     /// Reads a buffer of data from a slave over SPI.
     ///
+    /// The length of `data` must be a multiple of 4 bytes. An error is returned when
+    /// a transfer doesn't complete in time or the FIFOs report an error.
+    ///
     /// NOTE: Currently, only PIO mode transfers are supported.
-    pub fn read(&self, data: &mut [u8]) -> Result<(), ()> {
-        if data.len() % 4 != 0 {
-            return Err(());
+    pub fn read(&self, data: &mut [u8]) -> Result<(), SpiError> {
+        if data.len() & 3 != 0 {
+            return Err(SpiError::Misaligned);
         }
 
         // TODO: Add DMA support.
 
-        for chunk in data.chunks_mut(4) {
-            self.pio_receive_packet(chunk.try_into().unwrap())?;
-        }
+        self.with_chip_select(|| {
+            for chunk in data.chunks_mut(4) {
+                self.pio_receive_packet(chunk.try_into().unwrap())?;
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     /// Writes a buffer of data to a slave over SPI.
     ///
+    /// The length of `data` must be a multiple of 4 bytes. An error is returned when
+    /// a transfer doesn't complete in time or the FIFOs report an error.
+    ///
     /// NOTE: Currently, only PIO mode transfers are supported.
-    pub fn write(&self, data: &[u8]) -> Result<(), ()> {
-        if data.len() % 4 != 0 {
-            return Err(());
+    pub fn write(&self, data: &[u8]) -> Result<(), SpiError> {
+        if data.len() & 3 != 0 {
+            return Err(SpiError::Misaligned);
         }
 
         // TODO: Add DMA support.
 
-        for chunk in data.chunks(4) {
-            self.pio_send_packet(chunk.try_into().unwrap())?;
+        self.with_chip_select(|| {
+            for chunk in data.chunks(4) {
+                self.pio_send_packet(chunk.try_into().unwrap())?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Writes a buffer of data to a slave over SPI and reads the response back
+    /// into the same buffer, without deselecting the slave in between.
+    ///
+    /// NOTE: Currently, only PIO mode transfers are supported.
+    pub fn transfer(&self, data: &mut [u8]) -> Result<(), SpiError> {
+        if data.len() & 3 != 0 {
+            return Err(SpiError::Misaligned);
+        }
+
+        self.with_chip_select(|| {
+            for chunk in data.chunks(4) {
+                self.pio_send_packet(chunk.try_into().unwrap())?;
+            }
+            for chunk in data.chunks_mut(4) {
+                self.pio_receive_packet(chunk.try_into().unwrap())?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Writes `tx` to a slave and reads the response into `rx` with the bus
+    /// temporarily clocked at `hz`, without deselecting the slave in between.
+    ///
+    /// The controller clock is reprogrammed to the highest frequency that does not
+    /// exceed `hz` for this single transaction and restored afterwards, which is
+    /// useful for commands that must be issued slower than the rest of the traffic.
+    ///
+    /// The lengths of `tx` and `rx` must be multiples of 4 bytes. An error is returned
+    /// when the clock of the controller cannot be configured to `hz`, when a transfer
+    /// doesn't complete in time or the FIFOs report an error.
+    ///
+    /// NOTE: Only the SPI 1 through 4 controllers support this. Currently, only PIO
+    /// mode transfers are supported.
+    pub fn transfer_at(&self, hz: u32, tx: &[u8], rx: &mut [u8]) -> Result<(), SpiError> {
+        if tx.len() & 3 != 0 || rx.len() & 3 != 0 {
+            return Err(SpiError::Misaligned);
+        }
+
+        let clock = self.clock().ok_or(SpiError::UnsupportedFrequency)?;
+
+        // Don't change the clock under a transfer that is still in flight.
+        self.wait_until_ready(0)?;
+
+        // Save the current configuration and switch to the requested frequency.
+        let config = clock
+            .source_config()
+            .map_err(|_| SpiError::UnsupportedFrequency)?;
+        clock
+            .set_frequency(hz)
+            .map_err(|_| SpiError::UnsupportedFrequency)?;
+
+        let res = self.with_chip_select(|| {
+            for chunk in tx.chunks(4) {
+                self.pio_send_packet(chunk.try_into().unwrap())?;
+            }
+            for chunk in rx.chunks_mut(4) {
+                self.pio_receive_packet(chunk.try_into().unwrap())?;
+            }
+
+            Ok(())
+        });
+
+        // Restore the previous bus speed, even if the transaction failed.
+        clock
+            .restore_source_config(config)
+            .map_err(|_| SpiError::UnsupportedFrequency)?;
+
+        res
+    }
+
+    /// Provides a buffer of data to be clocked out by the external master.
+    ///
+    /// This blocks until the master has read all of the data. An error is returned
+    /// when the master doesn't clock out a 4-byte packet within `timeout` microseconds.
+    ///
+    /// NOTE: The controller must be configured through [`Spi::init_slave`]
+    /// beforehand. Currently, only PIO mode transfers are supported.
+    ///
+    /// [`Spi::init_slave`]: #method.init_slave
+    pub fn slave_respond(&self, tx: &[u8], timeout: u32) -> Result<(), SpiError> {
+        if tx.len() & 3 != 0 {
+            return Err(SpiError::Misaligned);
+        }
+
+        // Flush the FIFOs once, so no data is lost in between packets.
+        self.flush_fifos()?;
+
+        for chunk in tx.chunks(4) {
+            self.pio_send(chunk.try_into().unwrap(), timeout)?;
+        }
+
+        Ok(())
+    }
+
+    /// Receives a buffer of data that is clocked in by the external master.
+    ///
+    /// This blocks until the master has written all of the data. An error is returned
+    /// when the master doesn't clock in a 4-byte packet within `timeout` microseconds.
+    ///
+    /// NOTE: The controller must be configured through [`Spi::init_slave`]
+    /// beforehand. Currently, only PIO mode transfers are supported.
+    ///
+    /// [`Spi::init_slave`]: #method.init_slave
+    pub fn slave_receive(&self, rx: &mut [u8], timeout: u32) -> Result<(), SpiError> {
+        if rx.len() & 3 != 0 {
+            return Err(SpiError::Misaligned);
+        }
+
+        // Flush the FIFOs once, so no data is lost in between packets.
+        self.flush_fifos()?;
+
+        for chunk in rx.chunks_mut(4) {
+            self.pio_receive(chunk.try_into().unwrap(), timeout)?;
         }
 
         Ok(())
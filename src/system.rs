@@ -1,29 +1,29 @@
-//! System-related APIs for identifying Tegra hardware.
+//! System-related APIs for identifying and controlling Tegra hardware.
 //!
 //! Use [`SoC`] to identify the Tegra model, platform and SoC.
 //!
+//! The system can be restarted through [`reboot`], or restarted into USB recovery
+//! mode (RCM) through [`reboot_to_rcm`]. [`shutdown`] powers the system off.
+//!
 //! [`SoC`]: struct.SoC.html
+//! [`reboot`]: fn.reboot.html
+//! [`reboot_to_rcm`]: fn.reboot_to_rcm.html
+//! [`shutdown`]: fn.shutdown.html
 
 // https://github.com/ARM-software/arm-trusted-firmware/blob/master/plat/nvidia/tegra/common/tegra_platform.c
 
-use crate::apb;
+use crate::{
+    apb, pmc,
+    pmic::{self, Max77620},
+};
 
 const JEDEC_NVIDIA_MFID: u32 = 0x6B;
 const JEDEC_NVIDIA_BKID: u32 = 0x03;
 
-use tock_registers::interfaces::*;
-
-unsafe fn get_chip_id() -> (u32, u32, u32, u32, u32) {
-    let hidrev = (*apb::misc::REGISTERS).gp.APB_MISC_GP_HIDREV_0.get();
+/// The bit in `APBDEV_PMC_SCRATCH0_0` which makes the Boot ROM enter RCM.
+const PMC_SCRATCH0_MODE_RCM: u32 = 1 << 1;
 
-    (
-        hidrev >> 20 & 0xF, // pre_si_platform revision.
-        hidrev >> 16 & 0xF, // Chip ID minor revision.
-        hidrev >> 8 & 0xFF, // Chip ID.
-        hidrev >> 4 & 0xF,  // Chip ID major revision.
-        hidrev & 0xF,       // Chip ID family register.
-    )
-}
+use tock_registers::interfaces::*;
 
 /// Errors that may occur when trying to identify the Tegra hardware.
 #[derive(Debug)]
@@ -175,7 +175,7 @@ impl SoC {
     /// Constructs a new `SoC` which gives access to the hardware information of the
     /// underlying device.
     pub fn get() -> Result<Self, Error> {
-        let (pre_si, minor, chip_id, major, family) = unsafe { get_chip_id() };
+        let (pre_si, minor, chip_id, major, family) = apb::misc::hidrev();
 
         Ok(SoC {
             model: Model::derive(chip_id)?,
@@ -230,3 +230,45 @@ impl SoC {
         (self.major << 8 | self.minor) as i32
     }
 }
+
+fn set_rcm_flag(enable: bool) {
+    let pmc = unsafe { &*pmc::REGISTERS };
+
+    let scratch0 = pmc.APBDEV_PMC_SCRATCH0_0.get();
+    if enable {
+        pmc.APBDEV_PMC_SCRATCH0_0
+            .set(scratch0 | PMC_SCRATCH0_MODE_RCM);
+    } else {
+        pmc.APBDEV_PMC_SCRATCH0_0
+            .set(scratch0 & !PMC_SCRATCH0_MODE_RCM);
+    }
+}
+
+/// Reboots the SoC normally.
+///
+/// A pending request to enter RCM that was left behind in the PMC scratch
+/// registers is cleared beforehand, so the Boot ROM continues with a regular
+/// boot from the configured boot device.
+pub fn reboot() -> ! {
+    set_rcm_flag(false);
+    pmc::reboot()
+}
+
+/// Reboots the SoC into USB recovery mode (RCM).
+///
+/// This sets the RCM request bit in `APBDEV_PMC_SCRATCH0_0`, which is sampled by
+/// the Boot ROM after reset, and then asserts the main software reset of the PMC.
+pub fn reboot_to_rcm() -> ! {
+    set_rcm_flag(true);
+    pmc::reboot()
+}
+
+/// Powers the system off through the given `pmic`.
+///
+/// See [`pmc::poweroff`] for the requirements of this function. It only returns
+/// when the power-off request could not be delivered, with the error that occurred.
+///
+/// [`pmc::poweroff`]: ../pmc/fn.poweroff.html
+pub fn shutdown(pmic: &Max77620) -> pmic::Error {
+    pmc::poweroff(pmic)
+}
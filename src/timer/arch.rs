@@ -0,0 +1,70 @@
+//! Abstractions over the ARM Generic Timer.
+//!
+//! # Description
+//!
+//! The ARM architected timer exposes a 64-bit system counter which is driven by the
+//! TSC and increments monotonically at a fixed frequency. It is the highest-resolution
+//! time source available to the CPU Complex and allows for measurements at sub-microsecond
+//! granularity.
+//!
+//! NOTE: The Generic Timer is part of the Cortex-A57/A53 cores and therefore only
+//! available on `aarch64`.
+
+use core::arch::asm;
+
+/// Reads the current value of the physical system counter.
+#[inline]
+pub fn ticks() -> u64 {
+    let ticks: u64;
+    unsafe {
+        // Synchronize the context so the counter is not read speculatively.
+        asm!(
+            "
+            isb
+            mrs {ticks}, cntpct_el0
+            ",
+            ticks = out(reg) ticks,
+            options(nomem, nostack),
+        );
+    }
+
+    ticks
+}
+
+/// Reads the frequency of the system counter in Hz.
+#[inline]
+pub fn frequency() -> u64 {
+    let frequency: u64;
+    unsafe {
+        asm!(
+            "mrs {frequency}, cntfrq_el0",
+            frequency = out(reg) frequency,
+            options(nomem, nostack),
+        );
+    }
+
+    frequency
+}
+
+/// Converts a number of system counter ticks to nanoseconds.
+#[inline]
+pub fn ticks_to_ns(ticks: u64) -> u64 {
+    (ticks as u128 * 1_000_000_000 / frequency() as u128) as u64
+}
+
+/// Converts nanoseconds to a number of system counter ticks, rounding up.
+#[inline]
+pub fn ns_to_ticks(duration: u64) -> u64 {
+    ((duration as u128 * frequency() as u128 + 999_999_999) / 1_000_000_000) as u64
+}
+
+/// Busy-waits for a given duration in nanoseconds.
+///
+/// The effective resolution is bound by the frequency of the system counter.
+#[inline]
+pub fn busy_wait_ns(duration: u64) {
+    let start = ticks();
+    let duration = ns_to_ticks(duration);
+
+    while ticks().wrapping_sub(start) < duration {}
+}
@@ -33,12 +33,128 @@
 //!
 //! usleep(5_000_000); // Delays execution for five seconds.
 //! ```
+//!
+//! Elapsed time can be measured with a [`Stopwatch`].
+//!
+//! ## GT
+//!
+//! On the CPU Complex, the ARM Generic Timer offers the highest resolution.
+//!
+//! ```no_run
+//! # #[cfg(target_arch = "aarch64")]
+//! use libtegra::timer::arch::busy_wait_ns;
+//!
+//! # #[cfg(target_arch = "aarch64")]
+//! busy_wait_ns(500); // Delays execution for 500 nanoseconds.
+//! ```
+//!
+//! ## WDT
+//!
+//! A watchdog resets the SoC unless it is fed periodically.
+//!
+//! ```no_run
+//! use libtegra::timer::watchdog;
+//!
+//! watchdog::start(5_000).unwrap(); // Resets the SoC after five seconds.
+//! watchdog::feed(); // Restarts the countdown.
+//! ```
+//!
+//! [`Stopwatch`]: struct.Stopwatch.html
 
+#[cfg(target_arch = "aarch64")]
+pub mod arch;
 pub mod rtc;
 pub mod timerus;
+pub mod watchdog;
+
+use core::time::Duration;
 
 use tock_registers::interfaces::*;
 
+/// A measurement of the monotonically increasing microsecond counter.
+///
+/// The underlying counter is 32 bits wide and wraps around roughly every 71 minutes.
+/// Durations between two instants are calculated with wrapping arithmetic, so they
+/// are correct across a single wrap of the counter, but not for measurements which
+/// span more than one full period of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Instant(u32);
+
+impl Instant {
+    /// Returns an instant corresponding to "now".
+    #[inline]
+    pub fn now() -> Self {
+        Instant(get_microseconds())
+    }
+
+    /// Returns the amount of time elapsed from another instant to this one.
+    #[inline]
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        Duration::from_micros(self.0.wrapping_sub(earlier.0) as u64)
+    }
+
+    /// Returns the amount of time elapsed since this instant was created.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().duration_since(*self)
+    }
+}
+
+/// A stopwatch for measuring elapsed time with microsecond resolution.
+///
+/// Unlike [`Instant`], the stopwatch accumulates the elapsed time on each call to
+/// [`Stopwatch::lap`], so measurements keep working across any number of wraps of
+/// the 32-bit microsecond counter, as long as [`Stopwatch::lap`] is called at least
+/// once per wrap period of roughly 71 minutes.
+///
+/// ```no_run
+/// use libtegra::timer::Stopwatch;
+///
+/// let mut stopwatch = Stopwatch::start();
+/// // ...
+/// let elapsed = stopwatch.lap(); // Microseconds since start.
+/// ```
+///
+/// [`Instant`]: struct.Instant.html
+/// [`Stopwatch::lap`]: struct.Stopwatch.html#method.lap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stopwatch {
+    // The counter value at the last update of the stopwatch.
+    last: u32,
+    // The accumulated amount of microseconds elapsed since start.
+    elapsed: u64,
+}
+
+impl Stopwatch {
+    /// Creates and starts a new stopwatch.
+    #[inline]
+    pub fn start() -> Self {
+        Stopwatch {
+            last: get_microseconds(),
+            elapsed: 0,
+        }
+    }
+
+    /// Gets the amount of microseconds elapsed since the stopwatch was started.
+    ///
+    /// The result saturates at `u32::MAX` for measurements that exceed it.
+    pub fn lap(&mut self) -> u32 {
+        let now = get_microseconds();
+
+        // Accumulate the time since the last update with wrapping arithmetic.
+        self.elapsed += now.wrapping_sub(self.last) as u64;
+        self.last = now;
+
+        self.elapsed.min(u32::MAX as u64) as u32
+    }
+
+    /// Resets the elapsed time and starts the measurement over.
+    #[inline]
+    pub fn restart(&mut self) {
+        *self = Stopwatch::start();
+    }
+}
+
 /// Reads the current time in seconds.
 #[inline]
 pub fn get_seconds() -> u32 {
@@ -50,7 +166,10 @@ pub fn get_seconds() -> u32 {
 pub fn get_milliseconds() -> u32 {
     let rtc = unsafe { &*rtc::REGISTERS };
 
-    rtc.APBDEV_RTC_MILLI_SECONDS_0.get() + (rtc.APBDEV_RTC_SHADOW_SECONDS_0.get() * 1000)
+    // Wrap around like the hardware counters do instead of overflowing.
+    rtc.APBDEV_RTC_MILLI_SECONDS_0
+        .get()
+        .wrapping_add(rtc.APBDEV_RTC_SHADOW_SECONDS_0.get().wrapping_mul(1000))
 }
 
 /// Reads the current time in microseconds.
@@ -59,26 +178,59 @@ pub fn get_microseconds() -> u32 {
     unsafe { (*timerus::REGISTERS).TIMERUS_CNTR_1US_0.get() }
 }
 
+/// Checks whether more than `duration` ticks of a counter have passed since `start`.
+///
+/// The delta is computed with wrapping arithmetic, so this stays correct when the
+/// counter wraps around between `start` and `now`.
+#[inline(always)]
+pub(crate) fn has_elapsed(start: u32, now: u32, duration: u32) -> bool {
+    now.wrapping_sub(start) > duration
+}
+
 /// Sleeps for a given duration in seconds.
+///
+/// The full duration is waited for even if the counter wraps around in between.
 #[inline]
 pub fn sleep(duration: u32) {
     let start = get_seconds();
 
-    while (get_seconds() - start) <= duration {}
+    while !has_elapsed(start, get_seconds(), duration) {}
 }
 
 /// Sleeps for a given duration in milliseconds.
+///
+/// The full duration is waited for even if the counter wraps around in between.
 #[inline]
 pub fn msleep(duration: u32) {
     let start = get_milliseconds();
 
-    while (get_milliseconds() - start) <= duration {}
+    while !has_elapsed(start, get_milliseconds(), duration) {}
 }
 
 /// Sleeps for a given duration in microseconds.
+///
+/// The full duration is waited for even if the counter wraps around in between.
 #[inline]
 pub fn usleep(duration: u32) {
     let start = get_microseconds();
 
-    while (get_microseconds() - start) <= duration {}
+    while !has_elapsed(start, get_microseconds(), duration) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::has_elapsed;
+
+    #[test]
+    fn has_elapsed_across_counter_wrap() {
+        let start = u32::MAX - 5;
+
+        // Only 10 ticks have passed when the counter wrapped around to 4.
+        assert!(!has_elapsed(start, 4, 10));
+        assert!(has_elapsed(start, 5, 10));
+
+        // Without a wrap, the ordinary comparison is preserved.
+        assert!(!has_elapsed(100, 110, 10));
+        assert!(has_elapsed(100, 111, 10));
+    }
 }
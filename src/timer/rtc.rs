@@ -10,8 +10,17 @@
 //! when the system is in low-power state. If configured, interrupts
 //! triggered by the RTC can cause the system to wake up from a low-power
 //! state.
+//!
+//! # Alarms
+//!
+//! An alarm can be scheduled at an absolute value of the seconds counter
+//! through [`set_alarm`]. Once the counter reaches that value, the alarm
+//! interrupt is raised and remains pending until [`clear_alarm`] is called.
+//!
+//! [`set_alarm`]: fn.set_alarm.html
+//! [`clear_alarm`]: fn.clear_alarm.html
 
-use tock_registers::{register_bitfields, register_structs, registers::*};
+use tock_registers::{interfaces::*, register_bitfields, register_structs, registers::*};
 
 use crate::memory_map::RTC;
 
@@ -225,3 +234,59 @@ register_structs! {
 }
 
 assert_eq_size!(Registers, [u8; 0x3C]);
+
+/// Waits for pending writes to the RTC to complete.
+///
+/// Writes are performed in the 32 kHz clock domain of the RTC, so this must be
+/// called before initiating a new write.
+pub fn wait_idle() {
+    let rtc = unsafe { &*REGISTERS };
+
+    while rtc
+        .APBDEV_RTC_BUSY_0
+        .matches_all(APBDEV_RTC_BUSY_0::STATUS::Busy)
+    {
+        // Wait until the previous write completed.
+    }
+}
+
+/// Schedules an alarm at the absolute value `seconds` of the seconds counter.
+///
+/// This programs the first seconds alarm and unmasks its interrupt, which can
+/// be used to wake up the system from a low-power state.
+pub fn set_alarm(seconds: u32) {
+    let rtc = unsafe { &*REGISTERS };
+
+    // Program the match value of the alarm.
+    wait_idle();
+    rtc.APBDEV_RTC_SECONDS_ALARM0_0
+        .write(APBDEV_RTC_SECONDS_ALARM0_0::SECS_MATCH_VALUE.val(seconds));
+
+    // Acknowledge a stale alarm and enable the interrupt.
+    clear_alarm();
+    wait_idle();
+    rtc.APBDEV_RTC_INTR_MASK_0
+        .modify(APBDEV_RTC_INTR_MASK_0::SEC_ALARM0::SET);
+    wait_idle();
+}
+
+/// Indicates whether the alarm scheduled through [`set_alarm`] has fired.
+///
+/// [`set_alarm`]: fn.set_alarm.html
+pub fn alarm_fired() -> bool {
+    let rtc = unsafe { &*REGISTERS };
+
+    rtc.APBDEV_RTC_INTR_STATUS_0
+        .is_set(APBDEV_RTC_INTR_STATUS_0::SEC_ALARM0)
+}
+
+/// Acknowledges a fired alarm.
+pub fn clear_alarm() {
+    let rtc = unsafe { &*REGISTERS };
+
+    // The status is cleared by writing a 1 to it, leave the other bits untouched.
+    wait_idle();
+    rtc.APBDEV_RTC_INTR_STATUS_0
+        .write(APBDEV_RTC_INTR_STATUS_0::SEC_ALARM0::SET);
+    wait_idle();
+}
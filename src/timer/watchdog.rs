@@ -0,0 +1,184 @@
+//! Driver for the Tegra X1 Watchdog Timers.
+//!
+//! See Chapter 8 in the Tegra X1 Technical Reference Manual for details.
+//!
+//! # Description
+//!
+//! The watchdog timers are clocked by one of the generic timers which run off
+//! the fixed 1MHz time base. Every expiration of the source timer increments
+//! the watchdog counter and the programmed action is taken on its fourth
+//! expiration, unless the watchdog is fed in the meantime.
+//!
+//! This driver uses watchdog 0 with TMR5 as its source and configures it to
+//! trigger a full chip reset through the PMC. This resets the whole SoC and
+//! not only the CPU that was stuck.
+
+use tock_registers::{interfaces::*, register_bitfields, register_structs, registers::*};
+
+use crate::memory_map::TMR;
+
+/// A pointer to the TMR5 register block that can be accessed by dereferencing it.
+pub const TIMER_REGISTERS: *const TimerRegisters = (TMR + 0x60) as *const TimerRegisters;
+
+/// A pointer to the WDT0 register block that can be accessed by dereferencing it.
+pub const REGISTERS: *const Registers = (TMR + 0x100) as *const Registers;
+
+/// The index of the generic timer which is used as the watchdog source.
+const TIMER_ID: u32 = 5;
+
+/// The magic value that must be written to unlock disabling the watchdog.
+const UNLOCK_PATTERN: u32 = 0xC45A;
+
+register_bitfields! {
+    u32,
+
+    /// Bitfields of the `TIMER_TMR_PTV_0` register.
+    pub TIMER_TMR_PTV_0 [
+        /// Enables the timer.
+        EN OFFSET(31) NUMBITS(1) [],
+
+        /// Whether the timer should be reloaded after it expired.
+        PER OFFSET(30) NUMBITS(1) [],
+
+        /// Trigger value, in microseconds.
+        TMR_PTV OFFSET(0) NUMBITS(29) []
+    ],
+
+    /// Bitfields of the `TIMER_TMR_PCR_0` register.
+    pub TIMER_TMR_PCR_0 [
+        /// Clears the timer interrupt when written with `1`.
+        INTR_CLR OFFSET(30) NUMBITS(1) [],
+
+        /// Current count value, in microseconds.
+        TMR_PCV OFFSET(0) NUMBITS(29) []
+    ],
+
+    /// Bitfields of the `TIMER_WDT_CONFIG_0` register.
+    pub TIMER_WDT_CONFIG_0 [
+        /// Whether a full chip reset should be triggered through the PMC on expiration.
+        PMC2CAR_RST_EN OFFSET(15) NUMBITS(1) [],
+
+        /// Whether an interrupt should be raised on the first expiration.
+        INT_EN OFFSET(12) NUMBITS(1) [],
+
+        /// The amount of source timer expirations that make up a watchdog period.
+        PERIOD OFFSET(4) NUMBITS(8) [],
+
+        /// The index of the generic timer that sources the watchdog.
+        TIMER_SOURCE OFFSET(0) NUMBITS(4) []
+    ],
+
+    /// Bitfields of the `TIMER_WDT_STATUS_0` register.
+    pub TIMER_WDT_STATUS_0 [
+        /// The amount of watchdog periods which expired since the watchdog was fed.
+        EXPIRATION_LEVEL OFFSET(12) NUMBITS(2) [],
+
+        /// The current count of the watchdog period.
+        COUNT OFFSET(4) NUMBITS(8) [],
+
+        /// Whether the watchdog is currently enabled.
+        ENABLED OFFSET(0) NUMBITS(1) []
+    ],
+
+    /// Bitfields of the `TIMER_WDT_COMMAND_0` register.
+    pub TIMER_WDT_COMMAND_0 [
+        /// Disables the watchdog counter, requires the unlock pattern to be written.
+        DISABLE_COUNTER OFFSET(1) NUMBITS(1) [],
+
+        /// Starts or restarts the watchdog counter.
+        START_COUNTER OFFSET(0) NUMBITS(1) []
+    ],
+
+    /// Bitfields of the `TIMER_WDT_UNLOCK_PATTERN_0` register.
+    pub TIMER_WDT_UNLOCK_PATTERN_0 [
+        /// The unlock pattern for disabling the watchdog.
+        UNLOCK_PATTERN OFFSET(0) NUMBITS(16) []
+    ]
+}
+
+register_structs! {
+    /// Representation of the generic timer registers.
+    #[allow(non_snake_case)]
+    pub TimerRegisters {
+        (0x0 => pub TIMER_TMR_PTV_0: ReadWrite<u32, TIMER_TMR_PTV_0::Register>),
+        (0x4 => pub TIMER_TMR_PCR_0: ReadWrite<u32, TIMER_TMR_PCR_0::Register>),
+        (0x8 => @END),
+    }
+}
+
+assert_eq_size!(TimerRegisters, [u8; 0x8]);
+
+register_structs! {
+    /// Representation of the watchdog timer registers.
+    #[allow(non_snake_case)]
+    pub Registers {
+        (0x00 => pub TIMER_WDT_CONFIG_0: ReadWrite<u32, TIMER_WDT_CONFIG_0::Register>),
+        (0x04 => pub TIMER_WDT_STATUS_0: ReadOnly<u32, TIMER_WDT_STATUS_0::Register>),
+        (0x08 => pub TIMER_WDT_COMMAND_0: WriteOnly<u32, TIMER_WDT_COMMAND_0::Register>),
+        (0x0C => pub TIMER_WDT_UNLOCK_PATTERN_0: WriteOnly<u32, TIMER_WDT_UNLOCK_PATTERN_0::Register>),
+        (0x10 => @END),
+    }
+}
+
+assert_eq_size!(Registers, [u8; 0x10]);
+
+/// Arms the watchdog to reset the SoC after `timeout_ms` milliseconds.
+///
+/// The watchdog must be fed through [`feed`] before the timeout elapses to
+/// prevent the reset. Returns an error if the timeout is zero or exceeds the
+/// range of the source timer, which is roughly 35 minutes.
+///
+/// [`feed`]: fn.feed.html
+pub fn start(timeout_ms: u32) -> Result<(), ()> {
+    let timer = unsafe { &*TIMER_REGISTERS };
+    let watchdog = unsafe { &*REGISTERS };
+
+    // The reset occurs on the fourth expiration, so program a quarter of the timeout.
+    let period = timeout_ms.checked_mul(1000 / 4).ok_or(())?;
+    if period == 0 || period > TIMER_TMR_PTV_0::TMR_PTV.mask {
+        return Err(());
+    }
+
+    // Configure the source timer to periodically expire.
+    timer.TIMER_TMR_PTV_0.write(
+        TIMER_TMR_PTV_0::EN::SET + TIMER_TMR_PTV_0::PER::SET + TIMER_TMR_PTV_0::TMR_PTV.val(period),
+    );
+
+    // Configure the watchdog to reset the chip and start counting.
+    watchdog.TIMER_WDT_CONFIG_0.write(
+        TIMER_WDT_CONFIG_0::PMC2CAR_RST_EN::SET
+            + TIMER_WDT_CONFIG_0::PERIOD.val(1)
+            + TIMER_WDT_CONFIG_0::TIMER_SOURCE.val(TIMER_ID),
+    );
+    watchdog
+        .TIMER_WDT_COMMAND_0
+        .write(TIMER_WDT_COMMAND_0::START_COUNTER::SET);
+
+    Ok(())
+}
+
+/// Feeds the watchdog, restarting its countdown to the configured timeout.
+pub fn feed() {
+    let watchdog = unsafe { &*REGISTERS };
+
+    watchdog
+        .TIMER_WDT_COMMAND_0
+        .write(TIMER_WDT_COMMAND_0::START_COUNTER::SET);
+}
+
+/// Disarms the watchdog and stops its source timer.
+pub fn stop() {
+    let timer = unsafe { &*TIMER_REGISTERS };
+    let watchdog = unsafe { &*REGISTERS };
+
+    // Unlock and disable the watchdog counter.
+    watchdog
+        .TIMER_WDT_UNLOCK_PATTERN_0
+        .write(TIMER_WDT_UNLOCK_PATTERN_0::UNLOCK_PATTERN.val(UNLOCK_PATTERN));
+    watchdog
+        .TIMER_WDT_COMMAND_0
+        .write(TIMER_WDT_COMMAND_0::DISABLE_COUNTER::SET);
+
+    // Disable the source timer.
+    timer.TIMER_TMR_PTV_0.set(0);
+}
@@ -23,6 +23,13 @@
 //! turns the Falcon into a black box that doesn't expose any of its inner workings
 //! to, for example, the host system.
 //!
+//! Note that the mode transitions are constrained by the hardware: Heavy Secure Mode
+//! is only ever entered by executing signed microcode and Light Secure Mode can only
+//! be granted from a Heavy Secure context, i.e. by HS microcode programming the `SCTL`
+//! register. The host has no way of entering Light Secure Mode on its own, it can
+//! only observe the mode and start LS microcode once it was granted, which is what
+//! [`Tsec::boot_secure`] does.
+//!
 //! # Firmware
 //!
 //! [envytools] have proven to be valuable tools when it comes to working with
@@ -92,10 +99,16 @@
 //! }
 //! ```
 //!
+//! Firmware that is only known at runtime, e.g. because it is read from storage,
+//! can be copied into an [`AlignedFirmware`] buffer instead, which is available
+//! with the `alloc` feature enabled.
+//!
 //! [NVIDIA Falcon]: https://envytools.readthedocs.io/en/latest/hw/falcon/index.html
 //! [envytools]: https://github.com/envytools/envytools
 //! [`Tsec`]: struct.Tsec.html
+//! [`Tsec::boot_secure`]: struct.Tsec.html#method.boot_secure
 //! [`FIRMWARE_ALIGNMENT`]: constant.FIRMWARE_ALIGNMENT.html
+//! [`AlignedFirmware`]: struct.AlignedFirmware.html
 
 mod registers;
 
@@ -104,9 +117,11 @@ use core::ops::{Deref, DerefMut};
 use enum_primitive::FromPrimitive;
 use tock_registers::interfaces::*;
 
-use crate::car::Clock;
+use crate::arm;
+use crate::car::{self, Clock};
+use crate::host1x;
 use crate::kfuse;
-use crate::timer::get_milliseconds;
+use crate::timer::{get_milliseconds, has_elapsed};
 pub use crate::tsec::registers::*;
 
 /// The alignment bits for TSEC firmware blobs.
@@ -152,6 +167,78 @@ impl<T, const N: usize> From<[T; N]> for Firmware<T, { N }> {
     }
 }
 
+/// A heap-allocated firmware buffer aligned to the 0x100 byte Falcon code pages.
+///
+/// This is the runtime counterpart of [`Firmware`] for blobs that are loaded
+/// from storage rather than embedded into the binary. The allocation is padded
+/// with zeroes up to the next page boundary, while the buffer itself only spans
+/// the copied data.
+///
+/// NOTE: Only available with the `alloc` feature enabled.
+///
+/// [`Firmware`]: struct.Firmware.html
+#[cfg(feature = "alloc")]
+pub struct AlignedFirmware {
+    ptr: core::ptr::NonNull<u8>,
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl AlignedFirmware {
+    /// Allocates an aligned buffer and copies the given firmware blob into it.
+    pub fn new(firmware: &[u8]) -> Self {
+        let layout = Self::layout(firmware.len());
+
+        let ptr = unsafe { alloc::alloc::alloc_zeroed(layout) };
+        let ptr = match core::ptr::NonNull::new(ptr) {
+            Some(ptr) => ptr,
+            None => alloc::alloc::handle_alloc_error(layout),
+        };
+
+        unsafe {
+            core::ptr::copy_nonoverlapping(firmware.as_ptr(), ptr.as_ptr(), firmware.len());
+        }
+
+        AlignedFirmware {
+            ptr,
+            len: firmware.len(),
+        }
+    }
+
+    /// Gets a pointer to the start of the aligned firmware buffer.
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    fn layout(len: usize) -> core::alloc::Layout {
+        let size = len.max(1).div_ceil(FIRMWARE_ALIGNMENT) * FIRMWARE_ALIGNMENT;
+        core::alloc::Layout::from_size_align(size, FIRMWARE_ALIGNMENT).unwrap()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Deref for AlignedFirmware {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DerefMut for AlignedFirmware {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Drop for AlignedFirmware {
+    fn drop(&mut self) {
+        unsafe { alloc::alloc::dealloc(self.ptr.as_ptr(), Self::layout(self.len)) }
+    }
+}
+
 enum_from_primitive! {
     /// Enumeration of potential Falcon processor exception clauses
     /// that may occur during code execution on the TSEC.
@@ -204,6 +291,19 @@ pub enum FalconError {
     DmaTimeout,
     /// The firmware blob is misaligned.
     FirmwareMisaligned,
+    /// The Falcon is not in the secure mode that was required or requested.
+    SecureModeRejected,
+    /// A buffer exceeds the size of the Falcon memory it should be used with.
+    OutOfBounds,
+    /// The KFUSE did not become ready in time or failed its CRC check.
+    KfuseNotReady,
+    /// The Falcon did not complete a reset in time.
+    ResetTimeout,
+    /// The TSEC did not signal the completion of its operations in time.
+    SyncpointTimeout,
+    /// The MAC verification of signed microcode failed when jumping to its
+    /// secure pages.
+    AuthenticationFailed,
     /// A Falcon exception that occurred during execution, consisting of the Program
     /// Counter where execution stopped and a [`FalconExceptionClause`] which
     /// provides additional context.
@@ -237,20 +337,27 @@ impl Tsec {
     ///
     /// NOTE: This method must be called once before the TSEC is usable.
     /// Otherwise, the SoC will hang itself whenever the device is accessed.
-    pub fn init(&self) {
+    ///
+    /// [`FalconError::KfuseNotReady`] is returned if the KFUSE, which the TSEC
+    /// sources its keys from, fails to initialize.
+    ///
+    /// [`FalconError::KfuseNotReady`]: enum.FalconError.html#variant.KfuseNotReady
+    pub fn init(&self) -> Result<(), FalconError> {
         let tsec = unsafe { &*self.registers };
 
         // Enable the device clocks that are required by the TSEC.
-        Clock::HOST1X.enable();
-        Clock::TSEC.enable();
-        Clock::TSECB.enable();
-        Clock::SOR_SAFE.enable();
-        Clock::SOR0.enable();
-        Clock::SOR1.enable();
-        Clock::KFUSE.enable();
+        car::enable_clocks(&[
+            &Clock::HOST1X,
+            &Clock::TSEC,
+            &Clock::TSECB,
+            &Clock::SOR_SAFE,
+            &Clock::SOR0,
+            &Clock::SOR1,
+            &Clock::KFUSE,
+        ]);
 
         // Ensure that KFUSE is ready (since TSEC sources the KFUSE key from it).
-        kfuse::wait_until_ready().unwrap();
+        kfuse::wait_until_ready().map_err(|_| FalconError::KfuseNotReady)?;
 
         // Configure the Falcon processor.
         tsec.TSEC_FALCON_DMACTL.set(0);
@@ -288,29 +395,33 @@ impl Tsec {
         );
         tsec.TSEC_FALCON_ITFEN
             .write(TSEC_FALCON_ITFEN::MTHDEN::SET + TSEC_FALCON_ITFEN::CTXEN::SET);
+
+        Ok(())
     }
 
     /// Shuts the TSEC down and makes it inaccessible.
     pub fn finalize(&self) {
         // Disable all device clocks for TSEC.
-        Clock::KFUSE.disable();
-        Clock::SOR1.disable();
-        Clock::SOR0.disable();
-        Clock::SOR_SAFE.disable();
-        Clock::TSECB.disable();
-        Clock::TSEC.disable();
-        Clock::HOST1X.disable();
+        car::disable_clocks(&[
+            &Clock::KFUSE,
+            &Clock::SOR1,
+            &Clock::SOR0,
+            &Clock::SOR_SAFE,
+            &Clock::TSECB,
+            &Clock::TSEC,
+            &Clock::HOST1X,
+        ]);
     }
 
     fn dma_wait_idle(&self) -> Result<(), FalconError> {
         let tsec = unsafe { &*self.registers };
 
-        let timeout = get_milliseconds() + 10_000;
+        let start = get_milliseconds();
         while !tsec
             .TSEC_FALCON_DMATRFCMD
             .is_set(TSEC_FALCON_DMATRFCMD::IDLE)
         {
-            if get_milliseconds() > timeout {
+            if has_elapsed(start, get_milliseconds(), 10_000) {
                 return Err(FalconError::DmaTimeout);
             }
         }
@@ -330,38 +441,97 @@ impl Tsec {
     /// [aligned]: constant.FIRMWARE_ALIGNMENT.html
     /// [`Tsec::boot`]: #method.boot
     pub fn load_firmware(&self, firmware: &[u8]) -> Result<(), FalconError> {
+        self.load_firmware_at(firmware.as_ptr() as usize, 0, firmware)
+    }
+
+    /// Loads Falcon microcode from the given physical address into the processor
+    /// memory at `dest_offset`.
+    ///
+    /// This works like [`Tsec::load_firmware`], but lifts the requirement for the
+    /// firmware length to be a multiple of the 0x100 byte page size. A partial final
+    /// page is staged in a zero-filled, page-aligned buffer on the stack before it is
+    /// transferred, so the code segment never receives bytes past the end of `data`.
+    ///
+    /// `phys_base` is the physical address of `data` as seen by the Falcon DMA engine.
+    /// It and `dest_offset` are still required to be [aligned] to 0x100 byte pages.
+    /// The staging buffer is assumed to be identity-mapped. An error is returned if
+    /// the firmware doesn't fit into IMEM at `dest_offset`.
+    ///
+    /// [aligned]: constant.FIRMWARE_ALIGNMENT.html
+    /// [`Tsec::load_firmware`]: #method.load_firmware
+    pub fn load_firmware_at(
+        &self,
+        phys_base: usize,
+        dest_offset: u32,
+        data: &[u8],
+    ) -> Result<(), FalconError> {
         let tsec = unsafe { &*self.registers };
 
-        // Check if the firmware is being aligned correctly.
-        let firmware_address = firmware.as_ptr() as usize;
-        if (firmware_address % FIRMWARE_ALIGNMENT) != 0 {
+        // Check if the firmware and the destination are being aligned correctly.
+        let misaligned = |address: usize| address & (FIRMWARE_ALIGNMENT - 1) != 0;
+        if misaligned(phys_base) || misaligned(dest_offset as usize) {
             return Err(FalconError::FirmwareMisaligned);
         }
 
+        // Check if the firmware fits into IMEM at the destination.
+        match (dest_offset as usize).checked_add(data.len()) {
+            Some(end) if end <= self.imem_size() => {}
+            _ => return Err(FalconError::OutOfBounds),
+        }
+
         // Make sure the DMA engine is in idle state.
         self.dma_wait_idle()?;
 
         // Load in the memory base address of the firmware buffer.
         tsec.TSEC_FALCON_DMATRFBASE
-            .set((firmware_address >> FIRMWARE_ALIGN_BITS) as u32);
+            .set((phys_base >> FIRMWARE_ALIGN_BITS) as u32);
 
-        // Configure the DMA engine to transfer the firmware buffer into the Falcon IMEM.
-        for (index, _) in firmware.chunks(FIRMWARE_ALIGNMENT).enumerate() {
+        // Configure the DMA engine to transfer all full pages into the Falcon IMEM.
+        let full_pages = data.len() / FIRMWARE_ALIGNMENT;
+        for index in 0..full_pages {
             let base = (index * FIRMWARE_ALIGNMENT) as u32;
-            let offset = base;
+            self.dma_transfer_page(dest_offset + base, base, false)?;
+        }
 
-            tsec.TSEC_FALCON_DMATRFMOFFS
-                .write(TSEC_FALCON_DMATRFMOFFS::OFFS.val(offset));
-            tsec.TSEC_FALCON_DMATRFFBOFFS.set(base);
-            tsec.TSEC_FALCON_DMATRFCMD
-                .write(TSEC_FALCON_DMATRFCMD::IMEM::SET);
+        // Stage a partial final page in a zero-filled buffer and transfer it from there.
+        let remainder = &data[full_pages * FIRMWARE_ALIGNMENT..];
+        if !remainder.is_empty() {
+            let mut page = Firmware::new([0u8; FIRMWARE_ALIGNMENT]);
+            page[..remainder.len()].copy_from_slice(remainder);
+            unsafe {
+                arm::cache::flush_data_cache(&*page, FIRMWARE_ALIGNMENT);
+            }
 
-            self.dma_wait_idle()?;
+            tsec.TSEC_FALCON_DMATRFBASE
+                .set((page.as_ptr() as usize >> FIRMWARE_ALIGN_BITS) as u32);
+            self.dma_transfer_page(
+                dest_offset + (full_pages * FIRMWARE_ALIGNMENT) as u32,
+                0,
+                false,
+            )?;
         }
 
         Ok(())
     }
 
+    fn dma_transfer_page(
+        &self,
+        imem_offset: u32,
+        source_offset: u32,
+        secure: bool,
+    ) -> Result<(), FalconError> {
+        let tsec = unsafe { &*self.registers };
+
+        tsec.TSEC_FALCON_DMATRFMOFFS
+            .write(TSEC_FALCON_DMATRFMOFFS::OFFS.val(imem_offset));
+        tsec.TSEC_FALCON_DMATRFFBOFFS.set(source_offset);
+        tsec.TSEC_FALCON_DMATRFCMD.write(
+            TSEC_FALCON_DMATRFCMD::IMEM::SET + TSEC_FALCON_DMATRFCMD::SEC.val(secure as u32),
+        );
+
+        self.dma_wait_idle()
+    }
+
     /// Boots the Falcon from the specified boot vector.
     ///
     /// The firmware must have been loaded into the Falcon in advance, either by
@@ -369,6 +539,10 @@ impl Tsec {
     /// The boot vector then specifies from where code should be executed, most code
     /// blobs presumably expect `0` to be passed, and the CPU boots up.
     ///
+    /// If a breakpoint configured through [`Tsec::set_breakpoint`] is hit, execution
+    /// stops and a [`FalconExceptionClause::BreakpointHit`] exception is reported
+    /// along with the Program Counter of the breakpoint.
+    ///
     /// There is also support for both shared mailboxes which act as scratch registers
     /// to share data between the Falcon and the host system. Through the respective
     /// arguments, mailboxes can be filled with supplied values and at the end of
@@ -381,6 +555,8 @@ impl Tsec {
     /// for malformed or misaligned blobs or through code fucking up internal state.
     ///
     /// [`Tsec::load_firmware`]: #method.load_firmware
+    /// [`Tsec::set_breakpoint`]: #method.set_breakpoint
+    /// [`FalconExceptionClause::BreakpointHit`]: enum.FalconExceptionClause.html#variant.BreakpointHit
     pub unsafe fn boot(
         &self,
         boot_vector: u32,
@@ -400,8 +576,23 @@ impl Tsec {
         // Wait for the DMA engine to enter idle state.
         res = self.dma_wait_idle();
         if res.is_ok() {
-            // Wait for the CPU to be halted.
-            while !tsec.TSEC_FALCON_CPUCTL.is_set(TSEC_FALCON_CPUCTL::HALTED) {}
+            // Wait for the CPU to be halted or stopped by a breakpoint. STOPPED may
+            // still be latched right after STARTCPU, so it only counts together with
+            // a breakpoint exception.
+            loop {
+                let cpuctl = tsec.TSEC_FALCON_CPUCTL.extract();
+                if cpuctl.is_set(TSEC_FALCON_CPUCTL::HALTED) {
+                    break;
+                }
+
+                if cpuctl.is_set(TSEC_FALCON_CPUCTL::STOPPED)
+                    && tsec
+                        .TSEC_FALCON_EXCI
+                        .matches_all(TSEC_FALCON_EXCI::EXCAUSE::BrkptIns)
+                {
+                    break;
+                }
+            }
 
             // Check if the CPU has crashed.
             let exception_info = tsec.TSEC_FALCON_EXCI.get();
@@ -420,26 +611,352 @@ impl Tsec {
         res
     }
 
+    /// Boots the Falcon from the specified boot vector and exchanges mailbox values
+    /// with it.
+    ///
+    /// This is a by-value variant of [`Tsec::boot`]: The mailboxes are filled with
+    /// the given values before execution and their final state is returned as a
+    /// `(mailbox0, mailbox1)` tuple.
+    ///
+    /// # Safety
+    ///
+    /// This method is considered unsafe for the same reasons as [`Tsec::boot`].
+    ///
+    /// [`Tsec::boot`]: #method.boot
+    pub unsafe fn exchange(
+        &self,
+        boot_vector: u32,
+        mailbox0_in: u32,
+        mailbox1_in: u32,
+    ) -> Result<(u32, u32), FalconError> {
+        let mut mailbox0 = mailbox0_in;
+        let mut mailbox1 = mailbox1_in;
+
+        self.boot(boot_vector, &mut mailbox0, &mut mailbox1)?;
+
+        Ok((mailbox0, mailbox1))
+    }
+
+    /// Loads and boots signed Heavy Secure microcode.
+    ///
+    /// `blob` is loaded into the code segment starting from address `0`. All pages
+    /// from `signature_offset` onwards are tagged as secure, with the first one of
+    /// them acting as the authentication entry point that the Falcon is booted from.
+    /// Upon entry, the hardware verifies the MAC of the secure pages before granting
+    /// Heavy Secure Mode to the code.
+    ///
+    /// A failed verification raises a [`FalconExceptionClause::AuthenticationEntry`]
+    /// exception which is reported as [`FalconError::AuthenticationFailed`]. On
+    /// success, the final state of both mailboxes is returned as a
+    /// `(mailbox0, mailbox1)` tuple.
+    ///
+    /// NOTE: Both the blob and `signature_offset` are required to be [aligned] to
+    /// 0x100 byte pages and the signed code must end on a page boundary.
+    ///
+    /// # Safety
+    ///
+    /// This method is considered unsafe for the same reasons as [`Tsec::boot`].
+    ///
+    /// [aligned]: constant.FIRMWARE_ALIGNMENT.html
+    /// [`FalconExceptionClause::AuthenticationEntry`]: enum.FalconExceptionClause.html#variant.AuthenticationEntry
+    /// [`FalconError::AuthenticationFailed`]: enum.FalconError.html#variant.AuthenticationFailed
+    /// [`Tsec::boot`]: #method.boot
+    pub unsafe fn boot_authenticated(
+        &self,
+        blob: &[u8],
+        signature_offset: usize,
+    ) -> Result<(u32, u32), FalconError> {
+        let tsec = &*self.registers;
+
+        // Check if the firmware and its secure pages are being aligned correctly.
+        let misaligned = |value: usize| value & (FIRMWARE_ALIGNMENT - 1) != 0;
+        if misaligned(blob.as_ptr() as usize)
+            || misaligned(blob.len())
+            || misaligned(signature_offset)
+        {
+            return Err(FalconError::FirmwareMisaligned);
+        }
+        if signature_offset >= blob.len() || blob.len() > self.imem_size() {
+            return Err(FalconError::OutOfBounds);
+        }
+
+        // Make sure the DMA engine is in idle state.
+        self.dma_wait_idle()?;
+
+        // Transfer the pages and tag the signed ones as secure.
+        tsec.TSEC_FALCON_DMATRFBASE
+            .set((blob.as_ptr() as usize >> FIRMWARE_ALIGN_BITS) as u32);
+        for base in (0..blob.len()).step_by(FIRMWARE_ALIGNMENT) {
+            self.dma_transfer_page(base as u32, base as u32, base >= signature_offset)?;
+        }
+
+        match self.exchange(signature_offset as u32, 0, 0) {
+            Err(FalconError::Exception(_, FalconExceptionClause::AuthenticationEntry)) => {
+                Err(FalconError::AuthenticationFailed)
+            }
+            res => res,
+        }
+    }
+
+    /// Boots signed microcode and confirms that it elevated the Falcon into Heavy
+    /// Secure Mode.
+    ///
+    /// This behaves like [`Tsec::boot_authenticated`], but additionally checks the
+    /// `HSMODE` bit of the `SCTL` register once the Falcon halted.
+    /// [`FalconError::SecureModeRejected`] is returned if the MAC authentication did
+    /// not grant Heavy Secure Mode. Therefore, the microcode must halt from within its
+    /// secure pages for the transition to be observed.
+    ///
+    /// # Safety
+    ///
+    /// This method is considered unsafe for the same reasons as [`Tsec::boot`].
+    ///
+    /// [`Tsec::boot_authenticated`]: #method.boot_authenticated
+    /// [`FalconError::SecureModeRejected`]: enum.FalconError.html#variant.SecureModeRejected
+    /// [`Tsec::boot`]: #method.boot
+    pub unsafe fn enter_hs_mode(
+        &self,
+        blob: &[u8],
+        signature_offset: usize,
+    ) -> Result<(u32, u32), FalconError> {
+        let mailboxes = self.boot_authenticated(blob, signature_offset)?;
+
+        if self.is_heavy_secure() {
+            Ok(mailboxes)
+        } else {
+            Err(FalconError::SecureModeRejected)
+        }
+    }
+
+    /// Configures one of the five hardware instruction breakpoints of the Falcon.
+    ///
+    /// `index` selects the `TSEC_FALCON_IBRKPT` register in the range of `1` to `5`,
+    /// an error is returned for other values. Execution stops when the code at `pc`
+    /// is reached while the breakpoint is enabled.
+    pub fn set_breakpoint(&self, index: u8, pc: u32, enable: bool) -> Result<(), FalconError> {
+        let tsec = unsafe { &*self.registers };
+
+        let breakpoint = match index {
+            1 => &tsec.TSEC_FALCON_IBRKPT1,
+            2 => &tsec.TSEC_FALCON_IBRKPT2,
+            3 => &tsec.TSEC_FALCON_IBRKPT3,
+            4 => &tsec.TSEC_FALCON_IBRKPT4,
+            5 => &tsec.TSEC_FALCON_IBRKPT5,
+            _ => return Err(FalconError::OutOfBounds),
+        };
+
+        breakpoint
+            .write(TSEC_FALCON_IBRKPT::EN.val(enable as u32) + TSEC_FALCON_IBRKPT::PC.val(pc));
+
+        Ok(())
+    }
+
+    /// Waits until the TSEC finished the operation it is currently processing.
+    ///
+    /// THI is instructed to increment the Host1X `syncpoint` once the engine reports the
+    /// operation as done, which is awaited for at most `timeout` microseconds. The
+    /// syncpoint should be reserved for the TSEC, as other users incrementing it would
+    /// make the operation appear to be done prematurely.
+    ///
+    /// [`FalconError::SyncpointTimeout`] is returned if the syncpoint was not incremented
+    /// in time.
+    ///
+    /// [`FalconError::SyncpointTimeout`]: enum.FalconError.html#variant.SyncpointTimeout
+    pub fn wait_for_completion(&self, syncpoint: u32, timeout: u32) -> Result<(), FalconError> {
+        let tsec = unsafe { &*self.registers };
+
+        let threshold = host1x::read(syncpoint).wrapping_add(1);
+        tsec.TSEC_THI_INCR_SYNCPT
+            .write(TSEC_THI_INCR_SYNCPT::COND::OpDone + TSEC_THI_INCR_SYNCPT::INDX.val(syncpoint));
+
+        host1x::wait(syncpoint, threshold, timeout)
+            .map(|_| ())
+            .map_err(|_| FalconError::SyncpointTimeout)
+    }
+
+    /// Indicates whether the Falcon is currently in Light Secure Mode.
+    pub fn is_light_secure(&self) -> bool {
+        let tsec = unsafe { &*self.registers };
+
+        tsec.TSEC_FALCON_SCTL.is_set(TSEC_FALCON_SCTL::LSMODE)
+    }
+
+    /// Indicates whether the Falcon is currently in Heavy Secure Mode.
+    pub fn is_heavy_secure(&self) -> bool {
+        let tsec = unsafe { &*self.registers };
+
+        tsec.TSEC_FALCON_SCTL.is_set(TSEC_FALCON_SCTL::HSMODE)
+    }
+
+    /// Boots the Falcon from the specified boot vector in Light Secure Mode.
+    ///
+    /// This behaves like [`Tsec::boot`], but is meant for LS microcode. Light Secure
+    /// Mode must already have been granted by Heavy Secure microcode that ran before,
+    /// e.g. a loader booted through [`Tsec::enter_hs_mode`], as the host can't enter
+    /// it on its own. [`FalconError::SecureModeRejected`] is returned without starting
+    /// the CPU if the Falcon is not in Light Secure Mode.
+    ///
+    /// # Safety
+    ///
+    /// This method is considered unsafe for the same reasons as [`Tsec::boot`].
+    ///
+    /// [`Tsec::boot`]: #method.boot
+    /// [`Tsec::enter_hs_mode`]: #method.enter_hs_mode
+    /// [`FalconError::SecureModeRejected`]: enum.FalconError.html#variant.SecureModeRejected
+    pub unsafe fn boot_secure(
+        &self,
+        boot_vector: u32,
+        mailbox0: &mut u32,
+        mailbox1: &mut u32,
+    ) -> Result<(), FalconError> {
+        if !self.is_light_secure() {
+            return Err(FalconError::SecureModeRejected);
+        }
+
+        self.boot(boot_vector, mailbox0, mailbox1)
+    }
+
+    /// Resets the Falcon processor into a clean state for loading new firmware.
+    ///
+    /// This issues a soft reset through `TSEC_FALCON_CPUCTL`, which also recovers
+    /// a processor that was halted by an exception, and scrubs the contents of
+    /// IMEM and DMEM. The IMEM tags are invalidated afterwards, so that firmware
+    /// can be loaded again through [`Tsec::load_firmware`]. Unlike a cycle of
+    /// [`Tsec::finalize`] and [`Tsec::init`], the device clocks are left untouched.
+    ///
+    /// An error is returned when the processor does not come out of reset in time.
+    ///
+    /// NOTE: This is only usable while TSEC is in No Secure mode context.
+    ///
+    /// [`Tsec::load_firmware`]: #method.load_firmware
+    /// [`Tsec::finalize`]: #method.finalize
+    /// [`Tsec::init`]: #method.init
+    pub fn reset_falcon(&self) -> Result<(), FalconError> {
+        let tsec = unsafe { &*self.registers };
+
+        // Soft-reset the Falcon core.
+        tsec.TSEC_FALCON_CPUCTL
+            .write(TSEC_FALCON_CPUCTL::SRESET::SET);
+
+        // Wait for the reset and the hardware memory scrubbing to complete.
+        let start = get_milliseconds();
+        while tsec.TSEC_FALCON_CPUCTL.is_set(TSEC_FALCON_CPUCTL::SRESET)
+            || tsec
+                .TSEC_FALCON_DMACTL
+                .is_set(TSEC_FALCON_DMACTL::IMEM_SCRUBBING)
+            || tsec
+                .TSEC_FALCON_DMACTL
+                .is_set(TSEC_FALCON_DMACTL::DMEM_SCRUBBING)
+        {
+            if has_elapsed(start, get_milliseconds(), 100) {
+                return Err(FalconError::ResetTimeout);
+            }
+        }
+
+        // Scrub DMEM with auto-incrementing addresses.
+        tsec.TSEC_FALCON_DMEMC0
+            .write(TSEC_FALCON_DMEMC::AINCW::SET + TSEC_FALCON_DMEMC::OFFS.val(0));
+        for _ in 0..self.dmem_size() >> 2 {
+            tsec.TSEC_FALCON_DMEMD0.set(0);
+        }
+
+        // Scrub IMEM with auto-incrementing addresses, tagging each 256-byte block.
+        tsec.TSEC_FALCON_IMEMC0
+            .write(TSEC_FALCON_IMEMC::AINCW::SET + TSEC_FALCON_IMEMC::OFFS.val(0));
+        for word in 0..self.imem_size() >> 2 {
+            if word & 0x3F == 0 {
+                tsec.TSEC_FALCON_IMEMT0
+                    .write(TSEC_FALCON_IMEMT::TAG.val((word >> 6) as u32));
+            }
+            tsec.TSEC_FALCON_IMEMD0.set(0);
+        }
+
+        // Invalidate all IMEM tags so that no stale code can be executed.
+        tsec.TSEC_FALCON_CPUCTL
+            .write(TSEC_FALCON_CPUCTL::IINVAL::SET);
+
+        // Clear the state left behind by the previous firmware.
+        tsec.TSEC_FALCON_MAILBOX0.set(0);
+        tsec.TSEC_FALCON_MAILBOX1.set(0);
+        tsec.TSEC_FALCON_BOOTVEC.set(0);
+        tsec.TSEC_FALCON_DMACTL.set(0);
+
+        Ok(())
+    }
+
+    /// Gets the size of the Falcon DMEM in bytes, as reported by `TSEC_FALCON_HWCFG`.
+    pub fn dmem_size(&self) -> usize {
+        let tsec = unsafe { &*self.registers };
+
+        (tsec.TSEC_FALCON_HWCFG.read(TSEC_FALCON_HWCFG::DMEM_SIZE) as usize) << 8
+    }
+
+    /// Gets the size of the Falcon IMEM in bytes, as reported by `TSEC_FALCON_HWCFG`.
+    pub fn imem_size(&self) -> usize {
+        let tsec = unsafe { &*self.registers };
+
+        (tsec.TSEC_FALCON_HWCFG.read(TSEC_FALCON_HWCFG::IMEM_SIZE) as usize) << 8
+    }
+
     /// Dumps the DMEM of the Falcon engine into the supplied buffer.
     ///
     /// This function is useful for debugging purposes and to examine how firmware
     /// interacts with the data memory.
     ///
-    /// The DMEM size of TSEC is hardcoded to `0x4000` bytes. For other Falcon
-    /// engines, the size of DMEM in words should be determined by reading
-    /// `(FALCON_HWCFG >> 9 & 0x1F) << 6`.
+    /// The buffer is filled with words starting from DMEM address `0`. If it is
+    /// larger than the DMEM size reported by [`Tsec::dmem_size`], an error is
+    /// returned and nothing is read.
     ///
     /// NOTE: This is only usable while TSEC is in No Secure mode context.
-    pub fn dump_dmem(&self, output: &mut [u32; 0x1000]) {
+    ///
+    /// [`Tsec::dmem_size`]: #method.dmem_size
+    pub fn dump_dmem(&self, output: &mut [u32]) -> Result<(), FalconError> {
         let tsec = unsafe { &*self.registers };
 
-        // Configure a full dump of DMEM with auto-incrementing addresses.
+        if output.len() * 4 > self.dmem_size() {
+            return Err(FalconError::OutOfBounds);
+        }
+
+        // Configure a dump of DMEM with auto-incrementing addresses.
         tsec.TSEC_FALCON_DMEMC0
             .write(TSEC_FALCON_DMEMC::AINCR::SET + TSEC_FALCON_DMEMC::OFFS.val(0));
 
-        // Read all words of the DMEM into the output buffer.
+        // Read the words of the DMEM into the output buffer.
         for i in output.iter_mut() {
             *i = tsec.TSEC_FALCON_DMEMD0.get();
         }
+
+        Ok(())
+    }
+
+    /// Dumps the IMEM of the Falcon engine into the supplied buffer.
+    ///
+    /// This function is useful for debugging purposes and to verify that firmware
+    /// was correctly uploaded into the code memory.
+    ///
+    /// The buffer is filled with words starting from IMEM address `0`. If it is
+    /// larger than the IMEM size reported by [`Tsec::imem_size`], an error is
+    /// returned and nothing is read.
+    ///
+    /// NOTE: This is only usable while TSEC is in No Secure mode context.
+    ///
+    /// [`Tsec::imem_size`]: #method.imem_size
+    pub fn dump_imem(&self, output: &mut [u32]) -> Result<(), FalconError> {
+        let tsec = unsafe { &*self.registers };
+
+        if output.len() * 4 > self.imem_size() {
+            return Err(FalconError::OutOfBounds);
+        }
+
+        // Configure a dump of IMEM with auto-incrementing addresses.
+        tsec.TSEC_FALCON_IMEMC0
+            .write(TSEC_FALCON_IMEMC::AINCR::SET + TSEC_FALCON_IMEMC::OFFS.val(0));
+
+        // Read the words of the IMEM into the output buffer.
+        for i in output.iter_mut() {
+            *i = tsec.TSEC_FALCON_IMEMD0.get();
+        }
+
+        Ok(())
     }
 }
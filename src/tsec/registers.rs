@@ -13,7 +13,10 @@ register_bitfields! {
 
     /// Bitfields of the `TSEC_THI_INCR_SYNCPT` register.
     pub TSEC_THI_INCR_SYNCPT [
-        COND OFFSET(10) NUMBITS(8) [],
+        COND OFFSET(10) NUMBITS(8) [
+            Immediate = 0,
+            OpDone = 1
+        ],
 
         INDX OFFSET(0) NUMBITS(10) []
     ],
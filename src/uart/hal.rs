@@ -1,19 +1,43 @@
-use embedded_hal::blocking::serial::Write;
+use embedded_hal::{blocking, serial};
 
 use super::Uart;
 
-impl Write<u8> for Uart {
+impl blocking::serial::Write<u8> for Uart {
     type Error = ();
 
     fn bwrite_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
-        self.write(buffer);
+        Uart::write(self, buffer);
 
         Ok(())
     }
 
     fn bflush(&mut self) -> Result<(), Self::Error> {
-        self.flush();
+        Uart::flush(self);
 
         Ok(())
     }
 }
+
+impl serial::Read<u8> for Uart {
+    type Error = ();
+
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        self.try_read_byte().ok_or(nb::Error::WouldBlock)
+    }
+}
+
+impl serial::Write<u8> for Uart {
+    type Error = ();
+
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.try_write_byte(word).map_err(|_| nb::Error::WouldBlock)
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        if self.is_transmit_complete() {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
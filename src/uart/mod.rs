@@ -56,6 +56,19 @@
 //! uart.read(&mut buffer);
 //! ```
 //!
+//! # DMA
+//!
+//! Large buffers can be transferred through the APB DMA Controller instead,
+//! which saves the CPU from feeding the FIFOs byte by byte:
+//!
+//! ```no_run
+//! use libtegra::{apb::dma::Controller, uart::Uart};
+//!
+//! let mut dma = Controller::new();
+//!
+//! Uart::A.write_dma(&mut dma, &[0x55; 0x1000]).unwrap();
+//! ```
+//!
 //! # Flushing
 //!
 //! In some cases, you may want to flush the underlying FIFOs:
@@ -76,16 +89,54 @@ mod hal;
 use core::{
     fmt::{self, Error},
     marker::Sync,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
 };
 
 use tock_registers::interfaces::*;
 
 pub use crate::uart::registers::*;
-use crate::{car::Clock, timer::usleep};
+use crate::{
+    apb::dma::{
+        BusWidth, Channel, Controller, DmaError, TransferConfig, TransferDirection,
+        APBDMACHAN_CHANNEL_CSR_0, MAX_BLOCK_WORDS,
+    },
+    arm,
+    car::Clock,
+    timer::usleep,
+};
 
 /// The default baud rate that can be used to intiialize UARTs.
 pub const BAUD_115200: u32 = 115_200;
 
+/// The depth of the TX FIFO in bytes.
+const TX_FIFO_SIZE: usize = 32;
+
+/// The baud rates the UARTs A through E are configured with.
+///
+/// These are kept outside of [`Uart`] so that all copies of the public constants
+/// share the configuration done by [`Uart::init`].
+///
+/// [`Uart`]: struct.Uart.html
+/// [`Uart::init`]: struct.Uart.html#method.init
+static BAUD_RATES: [AtomicU32; 5] = [
+    AtomicU32::new(0),
+    AtomicU32::new(0),
+    AtomicU32::new(0),
+    AtomicU32::new(0),
+    AtomicU32::new(0),
+];
+
+/// Whether the UARTs A through E currently have DMA mode selected in their FCR.
+///
+/// The FCR is write-only, so this state is needed to preserve the mode across writes.
+static DMA_MODES: [AtomicBool; 5] = [
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+];
+
 /// Representation of a UART.
 ///
 /// NOTE: Instances of this struct should never be created manually.
@@ -93,8 +144,8 @@ pub const BAUD_115200: u32 = 115_200;
 /// the UARTs A through E.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Uart {
-    // The baud rate the UART is configured with.
-    baud: u32,
+    // The index of the UART into the table of configured baud rates.
+    index: usize,
     // The device clock instance for the corresponding UART peripheral.
     clock: &'static Clock,
     // A pointer to the UART register block in memory.
@@ -105,55 +156,140 @@ pub struct Uart {
 impl Uart {
     /// Representation of UART A.
     pub const A: Self = Uart {
-        baud: 0,
+        index: 0,
         clock: &Clock::UART_A,
         registers: UART_A_REGISTERS,
     };
 
     /// Representation of UART B.
     pub const B: Self = Uart {
-        baud: 0,
+        index: 1,
         clock: &Clock::UART_B,
         registers: UART_B_REGISTERS,
     };
 
     /// Representation of UART C.
     pub const C: Self = Uart {
-        baud: 0,
+        index: 2,
         clock: &Clock::UART_C,
         registers: UART_C_REGISTERS,
     };
 
     /// Representation of UART D.
     pub const D: Self = Uart {
-        baud: 0,
+        index: 3,
         clock: &Clock::UART_D,
         registers: UART_D_REGISTERS,
     };
 
     /// Representation of UART E.
     pub const E: Self = Uart {
-        baud: 0,
+        index: 4,
         clock: &Clock::UART_APE,
         registers: UART_E_REGISTERS,
     };
 }
 
 impl Uart {
+    #[inline(always)]
+    fn baud(&self) -> u32 {
+        BAUD_RATES[self.index].load(Ordering::Relaxed)
+    }
+
     #[inline(always)]
     fn wait_cycles(&self, amount: u32) {
-        let baud_rate = self.baud;
+        let baud_rate = self.baud();
         usleep((amount * 1_000_000 + 16 * baud_rate - 1) / (16 * baud_rate));
     }
 
     #[inline(always)]
     fn wait_symbols(&self, amount: u32) {
-        let baud_rate = self.baud;
+        let baud_rate = self.baud();
         usleep((amount * 1_000_000 + baud_rate - 1) / baud_rate);
     }
 
+    fn dma_request(&self) -> Option<u32> {
+        use APBDMACHAN_CHANNEL_CSR_0::REQ_SEL::Value;
+
+        // UART E in the Audio Processing Engine has no request line to the APB DMA.
+        match self.index {
+            0 => Some(Value::UartA as u32),
+            1 => Some(Value::UartB as u32),
+            2 => Some(Value::UartC as u32),
+            3 => Some(Value::UartD as u32),
+            _ => None,
+        }
+    }
+
+    fn write_fifo_control(&self, clear: bool) {
+        let uart = unsafe { &*self.registers };
+
+        // Every write to the FCR has to carry the full configuration, as it can't be
+        // read back. The FIFOs always stay enabled with the default trigger levels.
+        let mode = if DMA_MODES[self.index].load(Ordering::Relaxed) {
+            UART_IIR_FCR_0::DMA::DmaMode1
+        } else {
+            UART_IIR_FCR_0::DMA::DmaMode0
+        };
+        let mut value = UART_IIR_FCR_0::FCR_EN_FIFO::SET
+            + UART_IIR_FCR_0::RX_TRIG::FifoCountGreater1
+            + UART_IIR_FCR_0::TX_TRIG::FifoCountGreater16
+            + mode;
+        if clear {
+            value = value + UART_IIR_FCR_0::TX_CLR::SET + UART_IIR_FCR_0::RX_CLR::SET;
+        }
+
+        uart.UART_IIR_FCR_0.write(value);
+    }
+
+    fn set_dma_mode(&self, enable: bool) {
+        DMA_MODES[self.index].store(enable, Ordering::Relaxed);
+        self.write_fifo_control(false);
+    }
+
+    fn run_dma(
+        &self,
+        channel: &Channel,
+        slave: u32,
+        buffer: u32,
+        words: u32,
+        direction: TransferDirection,
+    ) -> Result<(), DmaError> {
+        // The FIFO register is accessed byte-wise on the APB side.
+        let config = TransferConfig {
+            apb_width: BusWidth::Bits8,
+            ..TransferConfig::PERIPHERAL
+        };
+        let fifo = self.registers as usize as u32;
+
+        let mut offset = 0;
+        while offset < words {
+            let size = (words - offset).min(MAX_BLOCK_WORDS);
+            let address = buffer + offset * 4;
+            match direction {
+                TransferDirection::ToPeripheral => {
+                    channel.query_with_config(slave, address, fifo, size, &config)?
+                }
+                TransferDirection::FromPeripheral => {
+                    channel.write_with_config(slave, address, fifo, size, &config)?
+                }
+            }
+
+            channel.start();
+            while channel.is_busy() {
+                // Wait until the block was transferred.
+            }
+            channel.finish();
+            channel.clear_interrupt();
+
+            offset += size;
+        }
+
+        Ok(())
+    }
+
     fn round_baud_rate(&self) -> u32 {
-        let baud_rate = self.baud;
+        let baud_rate = self.baud();
 
         (8 * baud_rate + 408_000_000) / (16 * baud_rate)
     }
@@ -164,13 +300,16 @@ impl Uart {
     /// send and receive data. Further, it is required to do the respective
     /// [`pinmux`] configuration before calling this method.
     ///
+    /// The baud rate is shared by all copies of the [`Uart`] constants.
+    ///
     /// [`Uart`]: struct.Uart.html
     /// [`pinmux`]: ../pinmux
-    pub fn init(&mut self, baud_rate: u32) {
+    pub fn init(&self, baud_rate: u32) {
         let uart = unsafe { &*self.registers };
 
-        // Store the provided baud rate.
-        self.baud = baud_rate;
+        // Store the provided baud rate and start out without DMA.
+        BAUD_RATES[self.index].store(baud_rate, Ordering::Relaxed);
+        DMA_MODES[self.index].store(false, Ordering::Relaxed);
 
         // Bring up the device clock.
         self.clock.enable();
@@ -205,8 +344,7 @@ impl Uart {
         // Enable FIFO with default settings.
 
         // Enable FIFO mode.
-        uart.UART_IIR_FCR_0
-            .write(UART_IIR_FCR_0::EN_FIFO::Mode16550);
+        self.write_fifo_control(false);
         // Dummy read.
         uart.UART_SPR_0.get();
         // Wait for 3 baud cycles.
@@ -230,6 +368,19 @@ impl Uart {
         uart.UART_THR_DLAB_0_0.get() as u8
     }
 
+    /// Attempts to read a single byte over UART without blocking.
+    ///
+    /// Returns `None` if no data is available to read.
+    pub fn try_read_byte(&self) -> Option<u8> {
+        let uart = unsafe { &*self.registers };
+
+        if uart.UART_LSR_0.is_set(UART_LSR_0::RDR) {
+            Some(uart.UART_THR_DLAB_0_0.get() as u8)
+        } else {
+            None
+        }
+    }
+
     /// Fills a mutable buffer of data with bytes read over UART.
     ///
     /// This method blocks until the buffer is filled.
@@ -254,14 +405,161 @@ impl Uart {
         uart.UART_THR_DLAB_0_0.set(byte as u32);
     }
 
+    /// Attempts to write a single byte over UART without blocking.
+    ///
+    /// Fails if the TX FIFO is full.
+    ///
+    /// NOTE: This relies on the FIFOs being enabled by [`Uart::init`]. Without them,
+    /// the FIFO full status is never set and a byte that was not sent yet would be
+    /// overwritten.
+    ///
+    /// [`Uart::init`]: #method.init
+    pub fn try_write_byte(&self, byte: u8) -> Result<(), ()> {
+        let uart = unsafe { &*self.registers };
+
+        // With the FIFOs enabled, there is room for another byte until they are full.
+        if uart.UART_LSR_0.is_set(UART_LSR_0::TX_FIFO_FULL) {
+            Err(())
+        } else {
+            uart.UART_THR_DLAB_0_0.set(byte as u32);
+            Ok(())
+        }
+    }
+
+    /// Indicates whether all written data has left the transmitter.
+    pub fn is_transmit_complete(&self) -> bool {
+        let uart = unsafe { &*self.registers };
+
+        uart.UART_LSR_0.is_set(UART_LSR_0::TMTY)
+    }
+
     /// Writes a buffer of bytes over UART.
     ///
     /// This method blocks until everything was transferred.
     pub fn write(&self, data: &[u8]) {
-        // Write the bytes from the buffer.
-        for byte in data.iter() {
-            self.write_byte(*byte);
+        let uart = unsafe { &*self.registers };
+
+        let mut remaining = data;
+        while !remaining.is_empty() {
+            let status = uart.UART_LSR_0.extract();
+
+            // An empty FIFO can take a whole burst of bytes without further
+            // polling, otherwise fill it up byte by byte until it is full.
+            let burst = if status.is_set(UART_LSR_0::THRE) {
+                TX_FIFO_SIZE
+            } else if !status.is_set(UART_LSR_0::TX_FIFO_FULL) {
+                1
+            } else {
+                continue;
+            };
+
+            let (chunk, rest) = remaining.split_at(burst.min(remaining.len()));
+            for byte in chunk.iter() {
+                uart.UART_THR_DLAB_0_0.set(*byte as u32);
+            }
+            remaining = rest;
+        }
+    }
+
+    /// Writes a buffer of bytes over UART through a free channel of the APB DMA `controller`.
+    ///
+    /// The DMA controller transfers whole words from word-aligned addresses, so the leading
+    /// bytes up to the first word boundary and the trailing bytes that don't fill a whole
+    /// word are written through [`Uart::write`] instead. The same applies to the whole
+    /// buffer on UART E, which has no DMA request line.
+    ///
+    /// This method blocks until everything was transferred.
+    ///
+    /// [`Uart::write`]: #method.write
+    pub fn write_dma(&self, controller: &mut Controller, data: &[u8]) -> Result<(), DmaError> {
+        // The AHB address of a transfer has to be word-aligned, the controller ignores its low bits.
+        let (head, data) = data.split_at(data.as_ptr().align_offset(4).min(data.len()));
+        let (aligned, rest) = data.split_at(data.len() & !3);
+
+        Uart::write(self, head);
+
+        match self.dma_request() {
+            Some(slave) if !aligned.is_empty() => {
+                // Make the data visible to the DMA controller.
+                unsafe {
+                    arm::cache::clean_data_cache(aligned.as_ptr(), aligned.len());
+                }
+
+                self.set_dma_mode(true);
+                let result = controller.execute_in_channel(None, |channel| {
+                    self.run_dma(
+                        channel,
+                        slave,
+                        aligned.as_ptr() as usize as u32,
+                        (aligned.len() >> 2) as u32,
+                        TransferDirection::ToPeripheral,
+                    )
+                });
+                self.set_dma_mode(false);
+                result?;
+            }
+            _ => Uart::write(self, aligned),
         }
+
+        Uart::write(self, rest);
+
+        Ok(())
+    }
+
+    /// Fills a mutable buffer of data with bytes read over UART through a free channel
+    /// of the APB DMA `controller`.
+    ///
+    /// Only the part of the buffer that covers whole data cache lines is transferred over
+    /// DMA, so that invalidating the cache never discards data outside of it. The leading
+    /// bytes up to the first cache line boundary and the trailing bytes that don't fill a
+    /// whole cache line are read through [`Uart::read`] instead. The same applies to the
+    /// whole buffer on UART E, which has no DMA request line.
+    ///
+    /// This method blocks until the buffer is filled.
+    ///
+    /// [`Uart::read`]: #method.read
+    pub fn read_dma(&self, controller: &mut Controller, data: &mut [u8]) -> Result<(), DmaError> {
+        let head_length = data
+            .as_ptr()
+            .align_offset(arm::cache::DATA_CACHE_LINE_SIZE)
+            .min(data.len());
+        let (head, data) = data.split_at_mut(head_length);
+        self.read(head);
+
+        let length = data.len() & !(arm::cache::DATA_CACHE_LINE_SIZE - 1);
+        let (aligned, rest) = data.split_at_mut(length);
+
+        match self.dma_request() {
+            Some(slave) if !aligned.is_empty() => {
+                // Write back and discard the cache lines, so none are evicted over the DMA data.
+                unsafe {
+                    arm::cache::flush_data_cache(&*aligned, length);
+                }
+
+                self.set_dma_mode(true);
+                let result = controller.execute_in_channel(None, |channel| {
+                    self.run_dma(
+                        channel,
+                        slave,
+                        aligned.as_mut_ptr() as usize as u32,
+                        (length >> 2) as u32,
+                        TransferDirection::FromPeripheral,
+                    )
+                });
+                self.set_dma_mode(false);
+                result?;
+
+                // Make the received data visible to the CPU.
+                unsafe {
+                    arm::cache::invalidate_data_cache(aligned.as_mut_ptr(), length);
+                }
+            }
+            _ => self.read(aligned),
+        }
+
+        self.read(rest);
+
+        Ok(())
     }
 
     /// Enables or disables inversion of the UART signal with the desired bitmask.
@@ -303,12 +601,8 @@ impl Uart {
         // XXX: Figure out how to calculate this from code.
         usleep(96);
 
-        // Issue flush requests for TX FIFO and RX FIFO.
-        uart.UART_IIR_FCR_0.write(
-            UART_IIR_FCR_0::EN_FIFO::Mode16550
-                + UART_IIR_FCR_0::TX_CLR::SET
-                + UART_IIR_FCR_0::RX_CLR::SET,
-        );
+        // Issue flush requests for TX FIFO and RX FIFO, keeping the FIFOs enabled.
+        self.write_fifo_control(true);
         // Dummy read.
         uart.UART_SPR_0.get();
         // Wait for 32 baud cycles.
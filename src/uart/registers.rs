@@ -67,6 +67,9 @@ register_bitfields! {
     /// Bitfields of the `UART_IIR_FCR_0` register.
     pub UART_IIR_FCR_0 [
         /// FIFO Mode Status.
+        ///
+        /// NOTE: This field is only valid when reading the IIR. Writes to these bits
+        /// program `RX_TRIG` instead, the FIFOs are enabled through `FCR_EN_FIFO`.
         EN_FIFO OFFSET(6) NUMBITS(2) [
             /// 16450 Mode.
             ///
@@ -12,7 +12,344 @@
 //! proc-amp, and compositing for up to 8 input surfaces. It supports advanced
 //! features like gamma/de-gamma programming, color correct processing and pixel
 //! decompression.
+//!
+//! # Implementation details
+//!
+//! Operations are described by a configuration structure in memory which is
+//! handed to the VIC firmware through methods pushed into the THI method FIFO.
+//! The [`Vic`] type implements a small subset of it: format conversion, scaling
+//! and mirroring of a single input surface onto an output surface.
+//!
+//! [`Vic`]: struct.Vic.html
 
 mod registers;
 
 pub use registers::*;
+
+use tock_registers::interfaces::*;
+
+use crate::{arm, host1x};
+
+/// The size of the VIC configuration structure in 64-bit words.
+const CONFIG_STRUCT_WORDS: usize = 194;
+
+/// The method to select the application that should process the following methods.
+const SET_APPLICATION_ID: u32 = 0x200;
+
+/// The method to kick off the configured operation.
+const EXECUTE: u32 = 0x300;
+
+/// The time in microseconds to wait for an operation to complete.
+const EXECUTE_TIMEOUT: u32 = 100_000;
+
+/// The method to specify the size of the configuration structure.
+const SET_CONTROL_PARAMS: u32 = 0x704;
+
+/// The method to specify the address of the configuration structure.
+const SET_CONFIG_STRUCT_OFFSET: u32 = 0x708;
+
+/// The method to specify the address of the output surface.
+const SET_OUTPUT_SURFACE_LUMA_OFFSET: u32 = 0x720;
+
+/// The method to specify the address of the surface in slot 0.
+const SET_SURFACE0_SLOT0_LUMA_OFFSET: u32 = 0x400;
+
+/// The maximum width and height of a surface, in pixels.
+const MAX_SURFACE_DIMENSION: u32 = 1 << 14;
+
+/// The required alignment of surfaces and the configuration structure in memory.
+const SURFACE_ALIGNMENT: u32 = 0x100;
+
+/// Pixel formats of surfaces that are supported by the driver.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 32-bit ARGB, with the blue channel in the least significant byte.
+    A8R8G8B8 = 32,
+    /// 32-bit RGBA, with the alpha channel in the least significant byte.
+    R8G8B8A8 = 34,
+}
+
+/// A pitch-linear surface in physical memory.
+#[derive(Clone, Copy, Debug)]
+pub struct Surface {
+    /// The physical address of the surface, aligned to 256 bytes.
+    pub address: u32,
+    /// The format of the pixels in the surface.
+    pub format: PixelFormat,
+    /// The width of the surface, in pixels.
+    pub width: u32,
+    /// The height of the surface, in pixels.
+    pub height: u32,
+    /// The distance between two lines of the surface, in pixels.
+    pub stride: u32,
+}
+
+/// The slots a [`Surface`] may be bound to.
+///
+/// [`Surface`]: struct.Surface.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SurfaceSlot {
+    /// The surface that pixels are read from.
+    Input,
+    /// The surface that pixels are composited into.
+    Output,
+}
+
+/// A rectangle within a surface, in pixels.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Rect {
+    /// The horizontal position of the upper left corner.
+    pub x: u32,
+    /// The vertical position of the upper left corner.
+    pub y: u32,
+    /// The width of the rectangle.
+    pub width: u32,
+    /// The height of the rectangle.
+    pub height: u32,
+}
+
+/// Parameters of a blit operation from the input to the output surface.
+///
+/// The source rectangle is scaled to the size of the destination rectangle and
+/// converted to the pixel format of the output surface.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlitConfig {
+    /// The area of the input surface to read from.
+    pub source: Rect,
+    /// The area of the output surface to write to.
+    pub destination: Rect,
+    /// Whether the output should be mirrored horizontally.
+    pub flip_x: bool,
+    /// Whether the output should be mirrored vertically.
+    pub flip_y: bool,
+}
+
+/// The in-memory configuration structure consumed by the VIC firmware.
+///
+/// It must reside in memory that is accessible by the VIC through its physical
+/// address, which is assumed to be identical to the virtual one.
+#[repr(C, align(256))]
+pub struct ConfigStruct([u64; CONFIG_STRUCT_WORDS]);
+
+impl ConfigStruct {
+    /// Creates a new, zeroed configuration structure.
+    pub const fn new() -> Self {
+        ConfigStruct([0; CONFIG_STRUCT_WORDS])
+    }
+
+    fn set(&mut self, byte_offset: usize, shift: u32, bits: u32, value: u32) {
+        let mask = ((1 << bits) - 1) << shift;
+        let word = &mut self.0[byte_offset / 8];
+
+        *word = (*word & !mask) | ((value as u64) << shift & mask);
+    }
+
+    fn set_surface(&mut self, byte_offset: usize, surface: &Surface) {
+        // Pitch-linear layout without chroma planes.
+        self.set(byte_offset, 0, 7, surface.format as u32);
+        self.set(byte_offset, 32, 14, surface.width - 1);
+        self.set(byte_offset, 46, 14, surface.height - 1);
+        self.set(byte_offset + 8, 0, 14, surface.stride - 1);
+        self.set(byte_offset + 8, 14, 14, surface.height - 1);
+        self.set(byte_offset + 8, 32, 14, MAX_SURFACE_DIMENSION - 1);
+        self.set(byte_offset + 8, 46, 14, MAX_SURFACE_DIMENSION - 1);
+    }
+}
+
+impl Default for ConfigStruct {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Representation of the Video Image Compositor.
+///
+/// The VIC Falcon must already be running its firmware with the method interface
+/// enabled before any operations can be executed. Operations use a single input
+/// surface in slot 0 and are synchronous, their completion is signaled by the VIC
+/// through a Host1X syncpoint.
+pub struct Vic {
+    // The configuration structure that is shared with the VIC firmware.
+    config: &'static mut ConfigStruct,
+    // The syncpoint that is incremented when an operation is done.
+    syncpoint: u32,
+    // The surface that pixels are read from.
+    input: Option<Surface>,
+    // The surface that pixels are written to.
+    output: Option<Surface>,
+}
+
+impl Vic {
+    /// Creates a new VIC instance that uses the given memory for its configuration
+    /// and the given syncpoint to wait for the completion of operations.
+    ///
+    /// The syncpoint should be reserved for the VIC, as other users incrementing
+    /// it would make operations appear to be done prematurely.
+    pub fn new(config: &'static mut ConfigStruct, syncpoint: u32) -> Self {
+        assert!(
+            syncpoint < host1x::SYNCPOINT_COUNT,
+            "Invalid syncpoint given!"
+        );
+
+        Vic {
+            config,
+            syncpoint,
+            input: None,
+            output: None,
+        }
+    }
+
+    fn write_method(&self, method: u32, data: u32) {
+        let vic = unsafe { &*REGISTERS };
+
+        vic.NV_PVIC_THI_METHOD0
+            .write(NV_PVIC_THI_METHOD0::NV_PVIC_THI_METHOD0_OFFSET.val(method >> 2));
+        vic.NV_PVIC_THI_METHOD1.set(data);
+    }
+
+    /// Binds a surface to the given slot.
+    ///
+    /// Fails if the surface is not properly aligned or exceeds the dimensions
+    /// supported by the hardware.
+    pub fn configure_surface(&mut self, slot: SurfaceSlot, surface: Surface) -> Result<(), ()> {
+        if surface.address & (SURFACE_ALIGNMENT - 1) != 0
+            || surface.width == 0
+            || surface.height == 0
+            || surface.stride < surface.width
+            || surface.stride > MAX_SURFACE_DIMENSION
+            || surface.height > MAX_SURFACE_DIMENSION
+        {
+            return Err(());
+        }
+
+        match slot {
+            SurfaceSlot::Input => self.input = Some(surface),
+            SurfaceSlot::Output => self.output = Some(surface),
+        }
+
+        Ok(())
+    }
+
+    /// Executes a blit from the input to the output surface and waits for its
+    /// completion.
+    ///
+    /// Fails if either surface was not configured, the rectangles exceed the
+    /// bounds of their surfaces, or the VIC does not signal completion in time.
+    pub fn execute(&mut self, config: &BlitConfig) -> Result<(), ()> {
+        let vic = unsafe { &*REGISTERS };
+
+        let input = self.input.ok_or(())?;
+        let output = self.output.ok_or(())?;
+
+        // Rectangles whose edges overflow are out of bounds as well.
+        let fits = |rect: &Rect, surface: &Surface| {
+            rect.width != 0
+                && rect.height != 0
+                && matches!(rect.x.checked_add(rect.width), Some(right) if right <= surface.width)
+                && matches!(rect.y.checked_add(rect.height), Some(bottom) if bottom <= surface.height)
+        };
+        if !fits(&config.source, &input) || !fits(&config.destination, &output) {
+            return Err(());
+        }
+
+        let c = &mut *self.config;
+        *c = ConfigStruct::new();
+
+        // Pipe configuration, no downsampling.
+        c.set(0x00, 0, 11, 1 << 2);
+        c.set(0x00, 16, 11, 1 << 2);
+
+        // Output configuration with an opaque background and the target area.
+        c.set(0x10, 6, 10, 0x3FF);
+        c.set(0x10, 48, 1, config.flip_x as u32);
+        c.set(0x10, 49, 1, config.flip_y as u32);
+        c.set(0x18, 0, 14, config.destination.x);
+        c.set(
+            0x18,
+            16,
+            14,
+            config.destination.x + config.destination.width - 1,
+        );
+        c.set(0x18, 32, 14, config.destination.y);
+        c.set(
+            0x18,
+            48,
+            14,
+            config.destination.y + config.destination.height - 1,
+        );
+        c.set_surface(0x20, &output);
+
+        // Slot 0 configuration. Source rectangles are in 14.16 fixed point.
+        c.set(0x90, 0, 1, 1);
+        c.set(0xA0, 10, 10, 0x3FF);
+        c.set(0xA0, 32, 10, 0x3FF);
+        c.set(0xA0, 42, 1, 1);
+        c.set(0xB0, 0, 30, config.source.x << 16);
+        c.set(
+            0xB0,
+            32,
+            30,
+            (config.source.x + config.source.width - 1) << 16,
+        );
+        c.set(0xB8, 0, 30, config.source.y << 16);
+        c.set(
+            0xB8,
+            32,
+            30,
+            (config.source.y + config.source.height - 1) << 16,
+        );
+        c.set(0xC0, 0, 14, config.destination.x);
+        c.set(
+            0xC0,
+            16,
+            14,
+            config.destination.x + config.destination.width - 1,
+        );
+        c.set(0xC0, 32, 14, config.destination.y);
+        c.set(
+            0xC0,
+            48,
+            14,
+            config.destination.y + config.destination.height - 1,
+        );
+        c.set_surface(0xD0, &input);
+        c.set(0xD0, 19, 3, 2); // 64Bx4 cache width.
+
+        // Blend the slot as a plain copy, replacing the destination pixels.
+        c.set(0x130, 0, 10, 0x3FF);
+        c.set(0x130, 32, 3, 0);
+        c.set(0x130, 36, 3, 5);
+        c.set(0x130, 40, 3, 0);
+        c.set(0x130, 44, 3, 2);
+
+        // Make the configuration visible to the VIC.
+        unsafe {
+            arm::cache::clean_data_cache(
+                c as *const ConfigStruct as *const u8,
+                core::mem::size_of::<ConfigStruct>(),
+            );
+        }
+        let config_address = c as *const ConfigStruct as u32;
+
+        self.write_method(SET_APPLICATION_ID, 1);
+        self.write_method(
+            SET_CONTROL_PARAMS,
+            (core::mem::size_of::<ConfigStruct>() as u32 / 16) << 16,
+        );
+        self.write_method(SET_CONFIG_STRUCT_OFFSET, config_address >> 8);
+        self.write_method(SET_OUTPUT_SURFACE_LUMA_OFFSET, output.address >> 8);
+        self.write_method(SET_SURFACE0_SLOT0_LUMA_OFFSET, input.address >> 8);
+        // The VIC may not have picked up the operation yet, so its idle state is no
+        // indication of completion. Have it increment the syncpoint once done instead.
+        let threshold = host1x::read(self.syncpoint).wrapping_add(1);
+        self.write_method(EXECUTE, 1 << 8);
+        vic.NV_PVIC_THI_INCR_SYNCPT.write(
+            NV_PVIC_THI_INCR_SYNCPT::NV_PVIC_THI_INCR_SYNCPT_COND::CondOpDone
+                + NV_PVIC_THI_INCR_SYNCPT::NV_PVIC_THI_INCR_SYNCPT_INDX.val(self.syncpoint),
+        );
+
+        host1x::wait(self.syncpoint, threshold, EXECUTE_TIMEOUT)?;
+
+        Ok(())
+    }
+}